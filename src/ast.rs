@@ -1,25 +1,220 @@
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
+use crate::format::FormatSpec;
+
 #[derive(Debug, Clone)]
 pub enum Expr {
     None,
+    Bool(bool),
     Int(i64),
+    BigInt(BigInt),
     Float(f64),
+    Decimal(Decimal),
     Str(String),
+    FString(Vec<FStringPart>),
     Var(String),
+    Array(Vec<Expr>),
+    Tuple(Vec<Expr>),
+    /// `{key: value, ...}`, entries in source order.
+    Dict(Vec<(Expr, Expr)>),
+    Index {
+        target: Box<Expr>,
+        index: Box<Expr>,
+    },
+    Attribute {
+        target: Box<Expr>,
+        name: String,
+        line: usize,
+    },
+    MethodCall {
+        receiver: Box<Expr>,
+        name: String,
+        args: Vec<Expr>,
+        line: usize,
+    },
     Binary {
         left: Box<Expr>,
         op: BinOp,
         right: Box<Expr>,
     },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
     Call {
         name: String,
         args: Vec<Expr>,
+        kwargs: KeywordArgs,
     },
 }
 
+/// `name=value` arguments of a call, in source order.
+pub type KeywordArgs = Vec<(String, Expr)>;
+
+#[derive(Debug, Clone)]
+pub enum FStringPart {
+    Lit(String),
+    Expr { expr: Expr, spec: Option<FormatSpec> },
+}
+
+/// A statement. Declarations carry their `doc`: the docstring or `#` comments right above them,
+/// in BBCode (see `doc`).
 #[derive(Debug, Clone)]
 pub enum Stmt {
-    Let { name: String, expr: Expr },
+    /// `var name[: type] = expr`; `ty` is a type name such as `int` or a named enum.
+    Let { name: String, ty: Option<String>, expr: Expr, line: usize, doc: Option<String> },
     Expr(Expr),
+    With { expr: Expr, name: Option<String>, body: Vec<Stmt> },
+    Try {
+        body: Vec<Stmt>,
+        handlers: Vec<ExceptClause>,
+        else_body: Vec<Stmt>,
+        finally_body: Option<Vec<Stmt>>,
+    },
+    /// `raise value`, or a bare `raise` re-raising the exception an `except` block is handling.
+    Raise(Option<Expr>),
+    Assert { condition: Expr, message: Option<Expr> },
+    /// `enum [Name]:` and its members. Members of anonymous enums, and of named ones marked
+    /// `@anonymous_shadow`, are usable without the enum's name.
+    Enum { name: Option<String>, members: Vec<EnumMember>, shadow: bool, line: usize, doc: Option<String> },
+    /// `switch subject:` with `case` branches and an optional `default`.
+    Switch { subject: Expr, cases: Vec<SwitchCase>, default: Option<Vec<Stmt>> },
+    /// `match subject:` with `case pattern [if guard]:` arms and an optional `default`.
+    Match { subject: Expr, arms: Vec<MatchArm>, default: Option<Vec<Stmt>>, line: usize },
+    /// `func name(params) [-> type]:` and its body.
+    Func(FuncDecl),
+    /// `return [value]`, only inside a function.
+    Return(Option<Expr>),
+    /// `signal name(params)`, declaring a signal its handlers are called with.
+    Signal { name: String, params: Vec<Param>, line: usize, doc: Option<String> },
+    /// `target = expr`, where the target is a variable or an attribute (`self.name = name`).
+    Assign { target: Expr, expr: Expr, line: usize },
+    /// `class Name [extends Parent]:` and its fields and methods.
+    Class(ClassDecl),
+    /// `constructor(params):` and its body, in a class body.
+    Constructor(FuncDecl),
+    /// `static var`, `static func` or `static constructor -> bool:` in a class body.
+    Static(Box<Stmt>),
+    /// `static:` and its block, in a class body; it runs with the class body's other free code.
+    StaticBlock(Vec<Stmt>),
+    /// `if cond:` and its `elif cond:` branches in order, with an optional `else` block.
+    If { branches: Vec<(Expr, Vec<Stmt>)>, else_body: Option<Vec<Stmt>> },
+    /// `import path [as alias]`, or `import *` for every module in the importing file's folder.
+    Import(Import),
+    /// `exclude path, ...`: modules the file's later imports skip.
+    Exclude { paths: Vec<String>, line: usize },
+}
+
+/// An `import` or `from path import names` statement. `path` is a module name (`Utils`), a quoted
+/// path relative to the importing file (`"../debugger/light_debugger"`, `.pd` optional), or a
+/// folder (`"../debugger/"`, or `./` for `import *`).
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub path: String,
+    pub alias: Option<String>,
+    /// For `from path import ...`: the names to import, each with its optional alias; empty for
+    /// `*`.
+    pub names: Option<Vec<(String, Option<String>)>>,
+    pub line: usize,
+}
+
+impl Import {
+    /// Whether the import is of a folder's modules rather than a single module.
+    pub fn is_folder(&self) -> bool {
+        self.path.ends_with('/')
+    }
+}
+
+/// `class Name [extends Parent]:` and its body: fields declared with `var`, methods declared
+/// with `func`, constructors, static members, inner classes, and free code run when the class is
+/// declared.
+#[derive(Debug, Clone)]
+pub struct ClassDecl {
+    pub name: String,
+    /// The parent class's name, qualified for an inner class (`Utils.Counter`).
+    pub parent: Option<String>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+    /// Whether the class is a class module's: declared by `class Name` and `extends Parent`
+    /// headers at the top of its file (or just named after the file), with the rest of the file
+    /// as its body.
+    pub header: bool,
+    /// The docstring or `#` comments right above the declaration, in BBCode (see `doc`).
+    pub doc: Option<String>,
+}
+
+/// A function declaration.
+#[derive(Debug, Clone)]
+pub struct FuncDecl {
+    pub name: String,
+    pub params: Vec<Param>,
+    /// The `-> type` annotation; `void` means the function returns `none`.
+    pub returns: Option<String>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+    /// The docstring or `#` comments right above the declaration, in BBCode (see `doc`).
+    pub doc: Option<String>,
+}
+
+/// A parameter of a function or signal, with its optional `: type` annotation.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub ty: Option<String>,
+}
+
+/// `case pattern if guard:` and its block.
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+}
+
+/// The shape a `match` arm tests its subject against.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `_`, matching anything.
+    Wildcard,
+    /// A name, matching anything and binding it to the name (unless the name is an enum member).
+    Bind(String),
+    /// A literal or an enum member (`Color.RED`), matching equal values of the same type.
+    Value(Expr),
+    /// `start..end`, matching numbers from `start` to `end` inclusive.
+    Range { start: Expr, end: Expr },
+    /// `(p1, p2)`, matching tuples of exactly that length.
+    Tuple(Vec<Pattern>),
+    /// `[p1, p2]`, matching arrays of exactly that length.
+    Array(Vec<Pattern>),
+    /// `{key: p, ...}`, matching dicts that have at least these keys.
+    Dict(Vec<(Expr, Pattern)>),
+    /// `p1 | p2` (or `p1, p2` at the top of a `case`), matching when any alternative does.
+    Or(Vec<Pattern>),
+}
+
+/// `case value1, value2:` and its block.
+#[derive(Debug, Clone)]
+pub struct SwitchCase {
+    pub values: Vec<Expr>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+}
+
+/// A member of an enum, with its explicit value if it has one (`NIGHT = 6`).
+#[derive(Debug, Clone)]
+pub struct EnumMember {
+    pub name: String,
+    pub value: Option<Expr>,
+    pub line: usize,
+}
+
+/// `except Class1, Class2 as name:` and its block; no classes catches every exception.
+#[derive(Debug, Clone)]
+pub struct ExceptClause {
+    pub classes: Vec<String>,
+    pub name: Option<String>,
+    pub body: Vec<Stmt>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,4 +225,21 @@ pub enum BinOp {
     Div,
     IntDiv,
     Mod,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+pub enum UnaryOp {
+    BitNot,
 }
@@ -0,0 +1,478 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Shl, Shr, Sub};
+
+/// Largest magnitude (in bits) a `BigInt` result may reach.
+///
+/// Product, power and shift results beyond this are reported as overflow, estimated from the
+/// operands before computing them, instead of allocating unbounded memory. It also keeps the
+/// quadratic schoolbook multiplication of such results well under a second.
+pub const MAX_BITS: u64 = 1 << 18;
+
+/// Arbitrary-precision signed integer, used when integer arithmetic is promoted past 64 bits.
+///
+/// Stored as a sign and a magnitude of little-endian base 2^32 limbs without trailing zero
+/// limbs, so zero is an empty magnitude and is never negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    /// Builds a normalized `BigInt` from a sign and a (possibly untrimmed) magnitude.
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        trim(&mut magnitude);
+        let negative = negative && !magnitude.is_empty();
+        Self { negative, magnitude }
+    }
+
+    /// Parses a non-empty string of ASCII decimal digits, or `None` if any other character appears.
+    pub fn parse(digits: &str) -> Option<BigInt> {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut magnitude = vec![];
+        // Fold in base 10^9 chunks so every step is a single-limb multiply-add
+        for chunk in digits.as_bytes().chunks(9) {
+            let value = chunk.iter().fold(0u32, |acc, b| acc * 10 + (b - b'0') as u32);
+            mag_mul_small_add(&mut magnitude, 10u32.pow(chunk.len() as u32), value);
+        }
+        Some(BigInt::from_parts(false, magnitude))
+    }
+
+    /// Parses a non-empty string of digits in `radix` (2 to 36, letters in either case), or `None`
+    /// if any other character appears.
+    pub fn parse_radix(digits: &str, radix: u32) -> Option<BigInt> {
+        if digits.is_empty() {
+            return None;
+        }
+        let mut magnitude = vec![];
+        for c in digits.chars() {
+            mag_mul_small_add(&mut magnitude, radix, c.to_digit(radix)?);
+        }
+        Some(BigInt::from_parts(false, magnitude))
+    }
+
+    /// Converts a finite float with no fractional part exactly, otherwise `None`.
+    pub fn from_f64(f: f64) -> Option<BigInt> {
+        if !f.is_finite() || f.fract() != 0.0 {
+            return None;
+        }
+        let bits = f.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64;
+        if exponent == 0 {
+            // Zero (subnormals all have a fractional part)
+            return Some(BigInt::from(0));
+        }
+        let mantissa = BigInt::from(((bits & ((1 << 52) - 1)) | (1 << 52)) as i64);
+        // value = mantissa * 2^(exponent - 1075)
+        let shift = exponent - 1075;
+        let magnitude = if shift >= 0 { &mantissa << shift as u64 } else { &mantissa >> -shift as u64 };
+        Some(if f < 0.0 { -&magnitude } else { magnitude })
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.magnitude.first().is_some_and(|low| low & 1 == 1)
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    /// Number of significant bits in the magnitude (`0` for zero).
+    pub fn bits(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Converts to `i64` when the value fits, otherwise `None`.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let value = self.low_u64() as i128;
+        i64::try_from(if self.negative { -value } else { value }).ok()
+    }
+
+    /// Low 64 bits of the two's complement representation, i.e. the value wrapped into `i64`.
+    pub fn wrapping_to_i64(&self) -> i64 {
+        let low = self.low_u64();
+        if self.negative { low.wrapping_neg() as i64 } else { low as i64 }
+    }
+
+    /// Converts to `i64`, clamping to `i64::MIN` / `i64::MAX` when the value doesn't fit.
+    pub fn saturating_to_i64(&self) -> i64 {
+        self.to_i64().unwrap_or(if self.negative { i64::MIN } else { i64::MAX })
+    }
+
+    /// Nearest `f64` approximation (`inf` when the magnitude exceeds the float range).
+    pub fn to_f64(&self) -> f64 {
+        let value = self.magnitude.iter().rev()
+            .fold(0.0, |acc, &limb| acc * 4_294_967_296.0 + limb as f64);
+        if self.negative { -value } else { value }
+    }
+
+    /// Truncating division and remainder, matching `i64` `/` and `%` semantics.
+    ///
+    /// Returns `None` when `divisor` is zero.
+    pub fn div_rem(&self, divisor: &BigInt) -> Option<(BigInt, BigInt)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = mag_div_rem(&self.magnitude, &divisor.magnitude);
+        Some((
+            BigInt::from_parts(self.negative != divisor.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    /// Raises `self` to `exp` by repeated squaring.
+    pub fn pow(&self, mut exp: u64) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    fn low_u64(&self) -> u64 {
+        let low = self.magnitude.first().copied().unwrap_or(0) as u64;
+        let high = self.magnitude.get(1).copied().unwrap_or(0) as u64;
+        high << 32 | low
+    }
+
+    /// Two's complement limbs of `self`, sign-extended to `len` limbs.
+    fn to_twos(&self, len: usize) -> Vec<u32> {
+        let mut limbs = self.magnitude.clone();
+        limbs.resize(len, 0);
+        if self.negative {
+            for limb in limbs.iter_mut() {
+                *limb = !*limb;
+            }
+            increment(&mut limbs);
+        }
+        limbs
+    }
+
+    /// Reads a two's complement limb vector back into sign and magnitude.
+    fn from_twos(mut limbs: Vec<u32>) -> BigInt {
+        let negative = limbs.last().is_some_and(|top| top >> 31 == 1);
+        if negative {
+            for limb in limbs.iter_mut() {
+                *limb = !*limb;
+            }
+            increment(&mut limbs);
+        }
+        BigInt::from_parts(negative, limbs)
+    }
+
+    /// Applies a limb-wise bitwise operation with two's complement semantics.
+    fn bitwise(&self, other: &BigInt, op: impl Fn(u32, u32) -> u32) -> BigInt {
+        let len = self.magnitude.len().max(other.magnitude.len()) + 1;
+        let (a, b) = (self.to_twos(len), other.to_twos(len));
+        BigInt::from_twos(a.iter().zip(&b).map(|(&x, &y)| op(x, y)).collect())
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => mag_cmp(&self.magnitude, &other.magnitude),
+            (true, true) => mag_cmp(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        let abs = n.unsigned_abs();
+        BigInt::from_parts(n < 0, vec![abs as u32, (abs >> 32) as u32])
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, mag_add(&self.magnitude, &other.magnitude));
+        }
+        // Signs differ: subtract the smaller magnitude from the larger one
+        if mag_cmp(&self.magnitude, &other.magnitude).is_ge() {
+            BigInt::from_parts(self.negative, mag_sub(&self.magnitude, &other.magnitude))
+        } else {
+            BigInt::from_parts(other.negative, mag_sub(&other.magnitude, &self.magnitude))
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mag_mul(&self.magnitude, &other.magnitude))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Not for &BigInt {
+    type Output = BigInt;
+
+    /// Bitwise complement, `-self - 1` like `i64`.
+    fn not(self) -> BigInt {
+        &(-self) - &BigInt::from(1)
+    }
+}
+
+impl Shl<u64> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, shift: u64) -> BigInt {
+        let limbs = (shift / 32) as usize;
+        let bits = (shift % 32) as u32;
+        let mut magnitude = vec![0; limbs];
+        let mut carry = 0;
+        for &limb in &self.magnitude {
+            let wide = (limb as u64) << bits | carry;
+            magnitude.push(wide as u32);
+            carry = wide >> 32;
+        }
+        magnitude.push(carry as u32);
+        BigInt::from_parts(self.negative, magnitude)
+    }
+}
+
+impl Shr<u64> for &BigInt {
+    type Output = BigInt;
+
+    /// Arithmetic shift, rounding toward negative infinity like `i64`.
+    fn shr(self, shift: u64) -> BigInt {
+        if self.negative {
+            // -a >> n == -((a - 1) >> n) - 1
+            let shifted = &(&(-self) - &BigInt::from(1)) >> shift;
+            return &(-&shifted) - &BigInt::from(1);
+        }
+        let limbs = (shift / 32) as usize;
+        let bits = (shift % 32) as u32;
+        if limbs >= self.magnitude.len() {
+            return BigInt::from(0);
+        }
+        let source = &self.magnitude[limbs..];
+        let magnitude = (0..source.len())
+            .map(|i| {
+                let high = source.get(i + 1).copied().unwrap_or(0) as u64;
+                ((high << 32 | source[i] as u64) >> bits) as u32
+            })
+            .collect();
+        BigInt::from_parts(false, magnitude)
+    }
+}
+
+impl BitAnd for &BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a ^ b)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Peel off base 10^9 chunks, least significant first
+        let mut chunks = vec![];
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let (quotient, remainder) = mag_div_small(&rest, 1_000_000_000);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+/// Adds one to a limb vector in place, dropping any carry out of the top limb.
+fn increment(limbs: &mut [u32]) {
+    for limb in limbs.iter_mut() {
+        let (sum, overflow) = limb.overflowing_add(1);
+        *limb = sum;
+        if !overflow {
+            break;
+        }
+    }
+}
+
+fn mag_cmp(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Computes `limbs * mul + add` in place.
+fn mag_mul_small_add(limbs: &mut Vec<u32>, mul: u32, add: u32) {
+    let mut carry = add as u64;
+    for limb in limbs.iter_mut() {
+        let cur = *limb as u64 * mul as u64 + carry;
+        *limb = cur as u32;
+        carry = cur >> 32;
+    }
+    if carry != 0 {
+        limbs.push(carry as u32);
+    }
+}
+
+fn mag_add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = a.get(i).copied().unwrap_or(0) as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    result.push(carry as u32);
+    result
+}
+
+/// Subtracts magnitude `b` from `a`; requires `a >= b`.
+fn mag_sub(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = limb as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    result
+}
+
+fn mag_mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let cur = result[i + j] as u64 + x as u64 * y as u64 + carry;
+            result[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+/// Divides a magnitude by a single limb, returning the trimmed quotient and the remainder.
+fn mag_div_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = remainder << 32 | a[i] as u64;
+        quotient[i] = (cur / divisor as u64) as u32;
+        remainder = cur % divisor as u64;
+    }
+    trim(&mut quotient);
+    (quotient, remainder as u32)
+}
+
+/// Divides magnitudes with binary long division; `b` must be non-zero.
+fn mag_div_rem(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = b {
+        let (quotient, remainder) = mag_div_small(a, *divisor);
+        return (quotient, vec![remainder]);
+    }
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder << 1 | next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+        if mag_cmp(&remainder, b).is_ge() {
+            remainder = mag_sub(&remainder, b);
+            trim(&mut remainder);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, remainder)
+}
@@ -0,0 +1,205 @@
+use crate::bigint::BigInt;
+use crate::color;
+use crate::decimal::RoundingMode;
+use crate::error::PalladError;
+use crate::file::FileRef;
+use crate::methods::check_args;
+use crate::value::Value;
+
+/// A builtin function implemented in Rust, called with its arguments in order.
+pub type NativeFunction = fn(&[Value]) -> Result<Value, PalladError>;
+
+const FUNCTIONS: &[(&str, NativeFunction)] = &[
+    ("int", int),
+    ("byte", byte),
+    ("float", float),
+    ("string", string),
+    ("bool", bool),
+    ("open", open),
+    ("Color", color::construct),
+];
+
+/// Builtin namespaces, used through their methods and attributes (`log.error(...)`,
+/// `Color.RED`, `Signal.ONE_SHOT`); a variable of the same name hides them.
+const NAMESPACES: &[&str] = &["log", "Color", "Signal"];
+
+/// Finds the builtin function `name`.
+///
+/// `print` isn't listed here, as it writes to the VM's output.
+pub fn lookup(name: &str) -> Option<NativeFunction> {
+    FUNCTIONS.iter()
+        .find(|(function, _)| *function == name)
+        .map(|(_, function)| *function)
+}
+
+/// Finds the builtin namespace `name`.
+pub fn namespace(name: &str) -> Option<&'static str> {
+    NAMESPACES.iter().find(|namespace| **namespace == name).copied()
+}
+
+/// `int(value)`, `int(text, radix)` or `int(number, rounding)`.
+///
+/// Strings must be a sign and digits only (`int("12abc")` and `int(" 12")` fail); with a radix
+/// from 2 to 36, a matching `0b`, `0o` or `0x` prefix is allowed. Floats and decimals are rounded
+/// toward zero unless a rounding mode is given by name (`"half-even"`, `"half-up"`, `"half-down"`,
+/// `"up"`, `"down"`, `"ceiling"`, or `"floor"`). Integers too large for 64 bits become big integers.
+fn int(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("int", args, 1, 2)?;
+    let value = &args[0];
+    let fail = || PalladError::ConversionError { value: value.clone(), target: "integer" };
+
+    match (value, args.get(1)) {
+        (Value::Int(_) | Value::BigInt(_), None) => Ok(value.clone()),
+        (Value::Bool(b), None) => Ok(Value::Int(*b as i64)),
+        (Value::Str(s), None) => parse_int(s, 10).ok_or_else(fail),
+        (Value::Str(s), Some(Value::Int(radix))) => {
+            if !(2..=36).contains(radix) {
+                return Err(PalladError::InvalidArgument {
+                    function: "int".to_string(),
+                    reason: "radix must be between 2 and 36",
+                });
+            }
+            parse_int(s, *radix as u32).ok_or_else(fail)
+        }
+        (Value::Str(_), Some(other)) => Err(PalladError::ArgumentType {
+            function: "int".to_string(),
+            expected: "an integer radix",
+            got: other.clone(),
+        }),
+        (Value::Float(f), rounding) => {
+            let rounded = round_float(*f, rounding_arg(rounding)?);
+            BigInt::from_f64(rounded).map(Value::from).ok_or_else(fail)
+        }
+        (Value::Decimal(d), rounding) => Ok(Value::from(d.to_integer(rounding_arg(rounding)?))),
+        (_, None) => Err(fail()),
+        (_, Some(_)) => Err(PalladError::InvalidArgument {
+            function: "int".to_string(),
+            reason: "a radix or rounding mode only applies to strings, floats and decimals",
+        }),
+    }
+}
+
+/// `byte(value)` or `byte(text, radix)`, an integer from 0 to 255, converted like `int` does but
+/// with strings read as binary unless a radix is given (`byte("00001111")` is `15`).
+fn byte(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("byte", args, 1, 2)?;
+    let value = &args[0];
+    let converted = match (value, args.get(1)) {
+        (Value::Str(_), None) => int(&[value.clone(), Value::Int(2)]),
+        _ => int(args),
+    };
+    let fail = || PalladError::ConversionError { value: value.clone(), target: "byte" };
+    match converted {
+        Ok(Value::Int(n)) if (0..=255).contains(&n) => Ok(Value::Int(n)),
+        Ok(_) | Err(PalladError::ConversionError { .. }) => Err(fail()),
+        Err(PalladError::InvalidArgument { reason, .. }) => Err(PalladError::InvalidArgument { function: "byte".to_string(), reason }),
+        Err(PalladError::ArgumentType { expected, got, .. }) => Err(PalladError::ArgumentType { function: "byte".to_string(), expected, got }),
+        Err(error) => Err(error),
+    }
+}
+
+/// `float(value)`; strings use the usual float syntax (`"1.5"`, `"-2e3"`, `"inf"`) with nothing
+/// around it.
+fn float(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("float", args, 1, 1)?;
+    let value = &args[0];
+    let f = match value {
+        Value::Float(f) => *f,
+        Value::Int(n) => *n as f64,
+        Value::Bool(b) => *b as i64 as f64,
+        Value::Str(s) => s.parse()
+            .map_err(|_| PalladError::ConversionError { value: value.clone(), target: "float" })?,
+        Value::BigInt(n) => n.to_f64(),
+        Value::Decimal(d) => d.to_string().parse().unwrap_or(f64::NAN),
+        Value::None | Value::Array(_) | Value::Tuple(_) | Value::Dict(_) | Value::File(_) | Value::Exception(_)
+        | Value::Color(_) | Value::Namespace(_) | Value::Function(_) | Value::Signal(_) | Value::Class(_)
+        | Value::Object(_) | Value::Module(_) => {
+            return Err(PalladError::ConversionError { value: value.clone(), target: "float" });
+        }
+    };
+    // Finite numbers beyond the float range can't be represented
+    if !f.is_finite() && matches!(value, Value::BigInt(_) | Value::Decimal(_)) {
+        return Err(PalladError::ConversionError { value: value.clone(), target: "float" });
+    }
+    Ok(Value::Float(f))
+}
+
+/// `string(value)`, the text `print` shows for the value.
+fn string(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("string", args, 1, 1)?;
+    Ok(Value::Str(args[0].stringify()))
+}
+
+/// `bool(value)`, whether the value is truthy (see `Value::is_truthy`).
+fn bool(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("bool", args, 1, 1)?;
+    Ok(Value::Bool(args[0].is_truthy()))
+}
+
+/// `open(path, mode)`, a file value for reading or writing (see `FileRef::open`); the mode
+/// defaults to `"r"`.
+fn open(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("open", args, 1, 2)?;
+    let text_arg = |index: usize, expected| match &args[index] {
+        Value::Str(s) => Ok(s.as_str()),
+        other => Err(PalladError::ArgumentType {
+            function: "open".to_string(),
+            expected,
+            got: other.clone(),
+        }),
+    };
+    let path = text_arg(0, "a path string")?;
+    let mode = if args.len() > 1 { text_arg(1, "a mode string")? } else { "r" };
+    Ok(Value::File(FileRef::open(path, mode)?))
+}
+
+/// Parses an optionally signed integer in `radix`, accepting the matching `0b`/`0o`/`0x` prefix.
+fn parse_int(text: &str, radix: u32) -> Option<Value> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let prefix = match radix {
+        2 => Some("0b"),
+        8 => Some("0o"),
+        16 => Some("0x"),
+        _ => None,
+    };
+    let digits = prefix
+        .and_then(|prefix| {
+            digits.strip_prefix(prefix).or_else(|| digits.strip_prefix(&prefix.to_uppercase()))
+        })
+        .unwrap_or(digits);
+    let magnitude = BigInt::parse_radix(digits, radix)?;
+    Some(Value::from(if negative { -&magnitude } else { magnitude }))
+}
+
+/// The rounding mode named by `int`'s optional second argument (toward zero by default).
+fn rounding_arg(arg: Option<&Value>) -> Result<RoundingMode, PalladError> {
+    match arg {
+        None => Ok(RoundingMode::Down),
+        Some(Value::Str(name)) => RoundingMode::from_name(name).ok_or(PalladError::InvalidArgument {
+            function: "int".to_string(),
+            reason: "unknown rounding mode",
+        }),
+        Some(other) => Err(PalladError::ArgumentType {
+            function: "int".to_string(),
+            expected: "a rounding mode name",
+            got: other.clone(),
+        }),
+    }
+}
+
+/// Rounds a float to a whole number with `mode`.
+fn round_float(f: f64, mode: RoundingMode) -> f64 {
+    match mode {
+        RoundingMode::Down => f.trunc(),
+        RoundingMode::Up => if f < 0.0 { f.floor() } else { f.ceil() },
+        RoundingMode::Ceiling => f.ceil(),
+        RoundingMode::Floor => f.floor(),
+        RoundingMode::HalfUp => f.round(),
+        RoundingMode::HalfEven => f.round_ties_even(),
+        RoundingMode::HalfDown if f.fract().abs() == 0.5 => f.trunc(),
+        RoundingMode::HalfDown => f.round(),
+    }
+}
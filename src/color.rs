@@ -0,0 +1,70 @@
+use crate::error::PalladError;
+use crate::methods::check_args;
+use crate::value::Value;
+
+/// An RGBA color with components from 0 to 1, as created by `Color(r, g, b, a)` or named by
+/// `Color.RED` and the like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+const NAMED: &[(&str, Color)] = &[
+    ("BLACK", Color::rgb(0.0, 0.0, 0.0)),
+    ("WHITE", Color::rgb(1.0, 1.0, 1.0)),
+    ("GRAY", Color::rgb(0.5, 0.5, 0.5)),
+    ("RED", Color::rgb(1.0, 0.0, 0.0)),
+    ("GREEN", Color::rgb(0.0, 1.0, 0.0)),
+    ("BLUE", Color::rgb(0.0, 0.0, 1.0)),
+    ("YELLOW", Color::rgb(1.0, 1.0, 0.0)),
+    ("CYAN", Color::rgb(0.0, 1.0, 1.0)),
+    ("MAGENTA", Color::rgb(1.0, 0.0, 1.0)),
+    ("ORANGE", Color::rgb(1.0, 0.5, 0.0)),
+    ("TRANSPARENT", Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 }),
+];
+
+impl Color {
+    pub const fn rgb(r: f64, g: f64, b: f64) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// The color `Color.<name>`, e.g. `RED`.
+    pub fn named(name: &str) -> Option<Self> {
+        NAMED.iter().find(|(color, _)| *color == name).map(|(_, color)| *color)
+    }
+
+    /// The escape sequence that sets a terminal's foreground to this color (24-bit).
+    pub fn ansi(&self) -> String {
+        let channel = |c: f64| (c * 255.0).round() as u8;
+        format!("\x1b[38;2;{};{};{}m", channel(self.r), channel(self.g), channel(self.b))
+    }
+}
+
+/// `Color(r, g, b)` or `Color(r, g, b, a)`, each component a number from 0 to 1 (alpha defaults
+/// to 1).
+pub fn construct(args: &[Value]) -> Result<Value, PalladError> {
+    check_args("Color", args, 3, 4)?;
+    let mut components = [1.0; 4];
+    for (component, arg) in components.iter_mut().zip(args) {
+        *component = match arg {
+            Value::Int(n) => *n as f64,
+            Value::Float(f) => *f,
+            other => return Err(PalladError::ArgumentType {
+                function: "Color".to_string(),
+                expected: "a number",
+                got: other.clone(),
+            }),
+        };
+        if !(0.0..=1.0).contains(component) {
+            return Err(PalladError::InvalidArgument {
+                function: "Color".to_string(),
+                reason: "components must be between 0 and 1",
+            });
+        }
+    }
+    let [r, g, b, a] = components;
+    Ok(Value::Color(Color { r, g, b, a }))
+}
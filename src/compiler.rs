@@ -1,89 +1,1286 @@
-use crate::ast::{Stmt, Expr, BinOp};
-use crate::error::PalladError;
-use crate::ir::Instr;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::rc::Rc;
+use crate::ast::{Stmt, Expr, ClassDecl, EnumMember, ExceptClause, FStringPart, FuncDecl, Import, MatchArm, Param, Pattern, SwitchCase, BinOp, UnaryOp};
+use crate::error::{PalladError, Warning};
+use crate::ir::{self, Annotation, CaseKey, Instr};
+use crate::module::module_name;
+use crate::object;
+use crate::value::Value;
 
-/// Compile a sequence of AST statements into a vector of IR instructions.
+/// Integer switches whose cases span at most this many values, with at least half of them used,
+/// compile to a jump table; others use a hashed lookup.
+const JUMP_TABLE_MAX_SPAN: i64 = 256;
+
+/// Type names usable in `var name: type` annotations, with the `Value::type_name` they check
+/// for. Named enums and classes are types too.
+const TYPES: &[(&str, &str)] = &[
+    ("bool", "bool"),
+    ("int", "integer"),
+    ("float", "float"),
+    ("decimal", "decimal"),
+    ("string", "string"),
+    ("array", "array"),
+    ("tuple", "tuple"),
+    ("dict", "dict"),
+    ("file", "file"),
+    ("color", "color"),
+    ("exception", "exception"),
+    ("function", "function"),
+    ("signal", "signal"),
+];
+
+/// Compile a sequence of AST statements into a vector of IR instructions, along with any warnings.
 ///
 /// The function traverses the provided statements in order and emits the corresponding
 /// low-level instructions for each statement (e.g., evaluating expressions, storing
-/// variables, calling builtins, and popping expression results).
+/// variables, calling builtins, and popping expression results). Enums and class names are
+/// collected first, so enum members compile to constants wherever they are used and classes can
+/// be used as types. Function and method bodies follow the program's own code, which jumps past
+/// them.
 ///
 /// # Examples
 ///
 /// ```
-/// use crate::{compile, ast::{Stmt, Expr}};
+/// use pallad::ast::{Expr, Stmt};
+/// use pallad::compiler::compile;
 ///
 /// let stmts = vec![Stmt::Expr(Expr::Int(42))];
-/// let program = compile(stmts).unwrap();
-/// assert!(!program.is_empty());
+/// let (program, warnings) = compile(stmts).unwrap();
+/// assert!(!program.is_empty() && warnings.is_empty());
 /// ```
 ///
 /// # Returns
-/// 
-/// `Ok(Vec<Instr>)` containing the compiled IR program on success, or `Err(PalladError)` if compilation fails.
-pub fn compile(stmts: Vec<Stmt>) -> Result<Vec<Instr>, PalladError> {
-    let mut program = vec![];
-
-    for stmt in stmts {
-        match stmt {
-            Stmt::Let { name, expr } => {
-                compile_expr(expr, &mut program);
-                program.push(Instr::StoreVar(name));
-            }
-            Stmt::Expr(Expr::Call { name, args }) => {
+///
+/// `Ok((program, warnings))` containing the compiled IR program and the problems found that don't
+/// stop compilation (e.g. duplicate `case` values), or `Err(PalladError)` if compilation fails.
+pub fn compile(stmts: Vec<Stmt>) -> Result<(Vec<Instr>, Vec<Warning>), PalladError> {
+    compile_module(stmts, 0)
+}
+
+/// Like `compile`, for the module with index `module` in the VM, whose functions use that module's
+/// globals. The program starts at 0; `ir::relocate` moves it after the code already loaded.
+pub fn compile_module(stmts: Vec<Stmt>, module: usize) -> Result<(Vec<Instr>, Vec<Warning>), PalladError> {
+    let mut compiler = Compiler {
+        module,
+        program: vec![],
+        warnings: vec![],
+        enums: HashMap::new(),
+        constants: HashMap::new(),
+        classes: HashMap::new(),
+        imports: HashSet::new(),
+        functions: vec![],
+        owner: None,
+        in_constructor: false,
+        exits: vec![],
+        imported: vec![],
+        annotations: HashMap::new(),
+    };
+    compiler.collect_declarations(&stmts)?;
+    compiler.compile_block(stmts)?;
+    compiler.compile_functions()?;
+    Ok((compiler.program, compiler.warnings))
+}
+
+struct Compiler {
+    /// Index of the module being compiled.
+    module: usize,
+    program: Vec<Instr>,
+    warnings: Vec<Warning>,
+    /// Members of each named enum, with their values.
+    enums: HashMap<String, HashMap<String, i64>>,
+    /// Enum members usable by their name alone, from anonymous enums and `@anonymous_shadow`.
+    constants: HashMap<String, i64>,
+    /// Each declared class, with the name of its parent.
+    classes: HashMap<String, Option<String>>,
+    /// Names modules are imported as, usable as types since a module can be a class.
+    imports: HashSet<String>,
+    /// Paths of the modules and folders imported by the code compiled so far, which later
+    /// `exclude`s can't exclude anymore.
+    imported: Vec<String>,
+    /// The type annotation of each variable and parameter declared with one, by name; `None` for
+    /// names declared with different types in different places.
+    annotations: HashMap<String, Option<String>>,
+    /// Bodies of the declared functions and methods not compiled yet.
+    functions: Vec<PendingFunction>,
+    /// The class of the method whose body is being compiled.
+    owner: Option<String>,
+    /// Whether the body being compiled is a constructor's, whose `return` returns `self`.
+    in_constructor: bool,
+    /// The blocks enclosing the code being compiled that jumping out of them must close, innermost
+    /// last.
+    exits: Vec<Exit>,
+}
+
+/// A function body left for `compile_functions`.
+struct PendingFunction {
+    /// Index of the function's `MakeFunction`.
+    at: usize,
+    body: Vec<Stmt>,
+    /// The class of a method, class initializer or constructor.
+    owner: Option<String>,
+    kind: BodyKind,
+}
+
+/// A block that code leaving it early, such as `return`, must close first.
+#[derive(Clone)]
+enum Exit {
+    /// The body of a `try`, under an exception handler.
+    Handler,
+    /// A `try` with a `finally` block, which has to run.
+    Finally(Vec<Stmt>),
+    /// A `with` block, whose resource has to be closed.
+    With,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BodyKind {
+    Function,
+    /// A class initializer: first initializes the parent's fields, and returns `self`.
+    Initializer,
+    /// A constructor: first initializes the fields of its class, and returns `self`.
+    Constructor,
+}
+
+impl Compiler {
+    /// Records the members of every enum and the name of every class in `stmts`, including nested
+    /// blocks, so they can be used anywhere in the program. Members count up from 0, or from the
+    /// last explicit value.
+    fn collect_declarations(&mut self, stmts: &[Stmt]) -> Result<(), PalladError> {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Enum { name, members, shadow, line, .. } => {
+                    let values = enum_values(members)?;
+                    if let Some(name) = name {
+                        if self.enums.contains_key(name) || self.constants.contains_key(name) || self.classes.contains_key(name) {
+                            return Err(PalladError::DuplicateName { name: name.clone(), line: *line });
+                        }
+                        self.enums.insert(name.clone(), values.iter().cloned().collect());
+                    }
+                    if name.is_none() || *shadow {
+                        for (member, value) in values {
+                            if self.constants.contains_key(&member) || self.enums.contains_key(&member) {
+                                return Err(PalladError::DuplicateName { name: member, line: *line });
+                            }
+                            self.constants.insert(member, value);
+                        }
+                    }
+                }
+                Stmt::Let { name, ty: Some(ty), .. } => self.annotate(name, ty),
+                Stmt::With { body, .. } => self.collect_declarations(body)?,
+                Stmt::Switch { cases, default, .. } => {
+                    for case in cases {
+                        self.collect_declarations(&case.body)?;
+                    }
+                    if let Some(default) = default {
+                        self.collect_declarations(default)?;
+                    }
+                }
+                Stmt::If { branches, else_body } => {
+                    for (_, body) in branches {
+                        self.collect_declarations(body)?;
+                    }
+                    if let Some(else_body) = else_body {
+                        self.collect_declarations(else_body)?;
+                    }
+                }
+                Stmt::Import(import) if import.names.is_none() && !import.is_folder() => {
+                    self.imports.insert(import.alias.clone().unwrap_or_else(|| module_name(&import.path)));
+                }
+                Stmt::Import(Import { names: Some(names), .. }) => {
+                    for (name, alias) in names {
+                        self.imports.insert(alias.clone().unwrap_or_else(|| name.clone()));
+                    }
+                }
+                Stmt::Func(decl) | Stmt::Constructor(decl) => {
+                    for param in &decl.params {
+                        if let Some(ty) = &param.ty {
+                            self.annotate(&param.name, ty);
+                        }
+                    }
+                    self.collect_declarations(&decl.body)?;
+                }
+                Stmt::Static(member) => self.collect_declarations(std::slice::from_ref(&**member))?,
+                Stmt::StaticBlock(body) => self.collect_declarations(body)?,
+                Stmt::Class(decl) => {
+                    if self.classes.contains_key(&decl.name) || self.enums.contains_key(&decl.name) || self.constants.contains_key(&decl.name) {
+                        return Err(PalladError::DuplicateName { name: decl.name.clone(), line: decl.line });
+                    }
+                    self.classes.insert(decl.name.clone(), decl.parent.clone());
+                    self.collect_declarations(&decl.body)?;
+                }
+                Stmt::Match { arms, default, .. } => {
+                    for arm in arms {
+                        self.collect_declarations(&arm.body)?;
+                    }
+                    if let Some(default) = default {
+                        self.collect_declarations(default)?;
+                    }
+                }
+                Stmt::Try { body, handlers, else_body, finally_body } => {
+                    self.collect_declarations(body)?;
+                    for clause in handlers {
+                        self.collect_declarations(&clause.body)?;
+                    }
+                    self.collect_declarations(else_body)?;
+                    if let Some(finally_body) = finally_body {
+                        self.collect_declarations(finally_body)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits IR instructions for each statement of a block, in order. Functions declared in the
+    /// block are defined first, so they can be called anywhere in it.
+    fn compile_block(&mut self, stmts: Vec<Stmt>) -> Result<(), PalladError> {
+        let (functions, stmts): (Vec<Stmt>, Vec<Stmt>) = stmts.into_iter().partition(|stmt| matches!(stmt, Stmt::Func(_)));
+        for function in functions {
+            if let Stmt::Func(decl) = function {
+                self.declare_function(decl)?;
+            }
+        }
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::Let { name, ty, expr, line, .. } => {
+                    if self.constants.contains_key(&name) || self.enums.contains_key(&name) {
+                        return Err(PalladError::AssignToConstant { name, line });
+                    }
+                    let expected = ty.map(|ty| self.check_annotation(&name, &ty, &expr, line)).transpose()?;
+                    self.compile_expr(expr)?;
+                    match expected {
+                        Some(expected) => self.program.push(Instr::StoreTyped { name, expected }),
+                        None => self.program.push(Instr::StoreVar(name)),
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    self.compile_expr(expr)?;
+                    self.program.push(Instr::Pop);
+                }
+                Stmt::With { expr, name, body } => {
+                    // The VM closes the resource at `ExitWith`, or while unwinding on an error
+                    self.compile_expr(expr)?;
+                    self.program.push(Instr::EnterWith);
+                    match name {
+                        Some(name) => self.program.push(Instr::StoreVar(name)),
+                        None => self.program.push(Instr::Pop),
+                    }
+                    self.exits.push(Exit::With);
+                    self.compile_block(body)?;
+                    self.exits.pop();
+                    self.program.push(Instr::ExitWith);
+                }
+                Stmt::Try { body, handlers, else_body, finally_body } => {
+                    self.compile_try(body, handlers, else_body, finally_body)?;
+                }
+                Stmt::Raise(Some(expr)) => {
+                    self.compile_expr(expr)?;
+                    self.program.push(Instr::Raise);
+                }
+                Stmt::Raise(None) => {
+                    // The handled exception stays on the stack while its `except` block runs
+                    self.program.push(Instr::Dup);
+                    self.program.push(Instr::Raise);
+                }
+                Stmt::Assert { condition, message } => {
+                    self.compile_expr(condition)?;
+                    let pass = self.emit(Instr::JumpIfTrue(0));
+                    let argc = message.is_some() as usize;
+                    if let Some(message) = message {
+                        self.compile_expr(message)?;
+                    }
+                    self.program.push(Instr::CallBuiltin { name: "AssertionError".to_string(), argc, kwargs: vec![] });
+                    self.program.push(Instr::Raise);
+                    self.patch_jump(pass);
+                }
+                Stmt::Switch { subject, cases, default } => {
+                    self.compile_switch(subject, cases, default)?;
+                }
+                Stmt::Match { subject, arms, default, line } => {
+                    self.compile_match(subject, arms, default, line)?;
+                }
+                Stmt::Return(expr) => {
+                    match expr {
+                        Some(expr) => self.compile_expr(expr)?,
+                        None if self.in_constructor => self.program.push(Instr::LoadVar("self".to_string())),
+                        None => self.program.push(Instr::LoadNone),
+                    }
+                    self.close_exits(0)?;
+                    self.program.push(Instr::Return);
+                }
+                Stmt::Assign { target, expr, line } => match target {
+                    Expr::Var(name) => {
+                        if self.constants.contains_key(&name) || self.enums.contains_key(&name) {
+                            return Err(PalladError::AssignToConstant { name, line });
+                        }
+                        self.compile_expr(expr)?;
+                        self.program.push(Instr::AssignVar(name));
+                    }
+                    Expr::Attribute { target, name, .. } => {
+                        if matches!(&*target, Expr::Var(enum_name) if self.enums.contains_key(enum_name)) {
+                            return Err(PalladError::AssignToConstant { name, line });
+                        }
+                        self.check_private(&name, line)?;
+                        self.compile_expr(*target)?;
+                        self.compile_expr(expr)?;
+                        self.program.push(Instr::SetAttr(name));
+                    }
+                    // The parser only accepts variables and attributes as targets
+                    _ => {}
+                },
+                Stmt::Class(decl) => self.compile_class(decl)?,
+                Stmt::If { branches, else_body } => {
+                    let mut exits = vec![];
+                    for (condition, body) in branches {
+                        self.compile_expr(condition)?;
+                        let next = self.emit(Instr::JumpIfFalse(0));
+                        self.compile_block(body)?;
+                        exits.push(self.emit(Instr::Jump(0)));
+                        self.patch_jump(next);
+                    }
+                    if let Some(else_body) = else_body {
+                        self.compile_block(else_body)?;
+                    }
+                    for exit in exits {
+                        self.patch_jump(exit);
+                    }
+                }
+                Stmt::Import(import) => self.compile_import(import)?,
+                Stmt::Exclude { paths, line } => {
+                    if let Some(path) = paths.iter().find(|path| self.imported.iter().any(|import| overlaps(import, path))) {
+                        return Err(PalladError::ExcludeAfterImport { path: path.clone(), line });
+                    }
+                    self.program.push(Instr::Exclude(paths));
+                }
+                Stmt::Signal { name, params, line, .. } => {
+                    if self.constants.contains_key(&name) || self.enums.contains_key(&name) {
+                        return Err(PalladError::AssignToConstant { name, line });
+                    }
+                    let params = self.params(params, line)?;
+                    self.program.push(Instr::MakeSignal { name: name.clone(), params });
+                    self.program.push(Instr::StoreVar(name));
+                }
+                // Collected before compiling; members are constants
+                Stmt::Enum { .. } => {}
+                // Defined at the start of the block
+                Stmt::Func(_) => {}
+                // The parser allows these only in class bodies
+                Stmt::Constructor(_) | Stmt::Static(_) | Stmt::StaticBlock(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits an `import` or `from ... import`, with the names it stores resolved: a module is
+    /// stored as its alias, or else its file name, and each imported name as its own alias, or
+    /// else itself. Private names can't be imported.
+    fn compile_import(&mut self, import: Import) -> Result<(), PalladError> {
+        let alias = match &import.alias {
+            None if import.names.is_none() && !import.is_folder() => Some(module_name(&import.path)),
+            alias => alias.clone(),
+        };
+        let Import { path, names, line, .. } = import;
+        self.imported.push(path.clone());
+        let names = names.map(|names| {
+            names.into_iter()
+                .map(|(name, alias)| {
+                    self.check_private(&name, line)?;
+                    let binding = alias.unwrap_or_else(|| name.clone());
+                    Ok((name, binding))
+                })
+                .collect::<Result<Vec<_>, PalladError>>()
+        }).transpose()?;
+        let bindings = alias.iter().chain(names.iter().flatten().map(|(_, binding)| binding));
+        for binding in bindings {
+            if self.constants.contains_key(binding) || self.enums.contains_key(binding) {
+                return Err(PalladError::AssignToConstant { name: binding.clone(), line });
+            }
+        }
+        self.program.push(Instr::Import { path, alias, names, line });
+        Ok(())
+    }
+
+    /// Stores the function `decl` in a variable of its name. Its body is compiled later, by
+    /// `compile_functions`.
+    fn declare_function(&mut self, decl: FuncDecl) -> Result<(), PalladError> {
+        let name = decl.name.clone();
+        if self.constants.contains_key(&name) || self.enums.contains_key(&name) || self.classes.contains_key(&name) {
+            return Err(PalladError::DuplicateName { name, line: decl.line });
+        }
+        self.make_function(decl, None)?;
+        self.program.push(Instr::StoreVar(name));
+        Ok(())
+    }
+
+    /// Emits a `MakeFunction` for `decl`, a method of `owner` if given, leaving its body for
+    /// `compile_functions`.
+    fn make_function(&mut self, decl: FuncDecl, owner: Option<&str>) -> Result<(), PalladError> {
+        let FuncDecl { name, params, returns, body, line, .. } = decl;
+        let returns = match returns {
+            Some(ty) if ty == "void" => Some(ty),
+            Some(ty) => Some(self.value_type(&ty, line)?),
+            None => None,
+        };
+        let owner = owner.map(str::to_string);
+        let function = ir::Function { name, params: self.params(params, line)?, returns, entry: 0, owner: owner.clone(), module: self.module };
+        let at = self.emit(Instr::MakeFunction(Rc::new(function)));
+        self.functions.push(PendingFunction { at, body, owner, kind: BodyKind::Function });
+        Ok(())
+    }
+
+    /// Emits a class declaration: its parent class, its initializer, which sets each field of a new
+    /// instance to its initial value, its static initializer, which runs the free code of the body
+    /// (static variable values and `static:` blocks included) in order, and its methods,
+    /// constructors and static functions, which `MakeClass` turns into the class. The static
+    /// initializer runs right after the class is stored.
+    ///
+    /// Inner classes are static members of the class, declared at the start of its static
+    /// initializer wherever they appear in the body, so all of its code can use them.
+    ///
+    /// Fields, methods and static members share one namespace. Constructors are named after the
+    /// class, and can't share their parameter types. A class name that isn't PascalCase is warned
+    /// about.
+    fn compile_class(&mut self, decl: ClassDecl) -> Result<(), PalladError> {
+        let ClassDecl { name, parent, body, line, .. } = decl;
+        if !is_pascal_case(&name) {
+            self.warnings.push(Warning::ClassName { name: name.clone(), line });
+        }
+        if let Some(parent) = &parent {
+            let mut path = parent.split('.');
+            if let Some(outer) = path.next() {
+                self.program.push(Instr::LoadVar(outer.to_string()));
+            }
+            for inner in path {
+                self.program.push(Instr::GetAttr(inner.to_string()));
+            }
+        }
+
+        let mut members: Vec<String> = vec![];
+        let mut declare = |member: &str, line: usize| {
+            if members.iter().any(|m| m == member) {
+                return Err(PalladError::DuplicateName { name: member.to_string(), line });
+            }
+            members.push(member.to_string());
+            Ok(())
+        };
+        let mut fields: Vec<ir::Param> = vec![];
+        let mut statics: Vec<ir::Param> = vec![];
+        let mut init_body = vec![];
+        let mut static_body = vec![];
+        let mut inner_classes = vec![];
+        let mut methods: Vec<FuncDecl> = vec![];
+        let mut static_methods: Vec<FuncDecl> = vec![];
+        let mut constructors: Vec<(ir::Function, Vec<Stmt>)> = vec![];
+        let mut static_constructor: Option<FuncDecl> = None;
+        for stmt in body {
+            match stmt {
+                Stmt::Let { name: field, ty, expr, line, .. } => {
+                    declare(&field, line)?;
+                    // The field's type is checked on every assignment, by `SetAttr`
+                    fields.push(ir::Param { name: field.clone(), expected: self.member_type(&field, ty, &expr, line)? });
+                    let target = Expr::Attribute { target: Box::new(Expr::Var("self".to_string())), name: field, line };
+                    init_body.push(Stmt::Assign { target, expr, line });
+                }
+                Stmt::Func(method) => {
+                    declare(&method.name, method.line)?;
+                    methods.push(method);
+                }
+                Stmt::Constructor(FuncDecl { params, body, line, .. }) => {
+                    let params = self.params(params, line)?;
+                    let signature = |params: &[ir::Param]| -> Vec<Option<String>> {
+                        params.iter().map(|param| param.expected.clone()).collect()
+                    };
+                    if constructors.iter().any(|(other, _)| signature(&other.params) == signature(&params)) {
+                        let types: Vec<&str> = params.iter().map(|param| param.expected.as_deref().unwrap_or("any")).collect();
+                        return Err(PalladError::DuplicateName { name: format!("constructor({})", types.join(", ")), line });
+                    }
+                    let function = ir::Function { name: name.clone(), params, returns: None, entry: 0, owner: Some(name.clone()), module: self.module };
+                    constructors.push((function, body));
+                }
+                Stmt::Static(member) => match *member {
+                    Stmt::Let { name: variable, ty, expr, line, .. } => {
+                        declare(&variable, line)?;
+                        // Static variables are set in order with the rest of the free code
+                        statics.push(ir::Param { name: variable.clone(), expected: self.member_type(&variable, ty, &expr, line)? });
+                        static_body.push(Stmt::Assign { target: Expr::Var(variable), expr, line });
+                    }
+                    Stmt::Func(function) => {
+                        declare(&function.name, function.line)?;
+                        statics.push(ir::Param { name: function.name.clone(), expected: Some("function".to_string()) });
+                        static_methods.push(function);
+                    }
+                    Stmt::Constructor(decl) => {
+                        if static_constructor.is_some() {
+                            return Err(PalladError::DuplicateName { name: "static constructor".to_string(), line: decl.line });
+                        }
+                        static_constructor = Some(decl);
+                    }
+                    // The parser allows only static variables, functions and constructors
+                    _ => {}
+                },
+                Stmt::StaticBlock(block) => static_body.extend(block),
+                Stmt::Class(inner) => {
+                    declare(&inner.name, inner.line)?;
+                    statics.push(ir::Param { name: inner.name.clone(), expected: Some("class".to_string()) });
+                    inner_classes.push(Stmt::Class(inner));
+                }
+                free => static_body.push(free),
+            }
+        }
+
+        inner_classes.extend(static_body);
+        let static_body = inner_classes;
+
+        let init = ir::Function { name: name.clone(), params: vec![], returns: None, entry: 0, owner: Some(name.clone()), module: self.module };
+        let at = self.emit(Instr::MakeFunction(Rc::new(init)));
+        self.functions.push(PendingFunction { at, body: init_body, owner: Some(name.clone()), kind: BodyKind::Initializer });
+        let static_init = ir::Function { name: name.clone(), params: vec![], returns: None, entry: 0, owner: Some(name.clone()), module: self.module };
+        let at = self.emit(Instr::MakeFunction(Rc::new(static_init)));
+        self.functions.push(PendingFunction { at, body: static_body, owner: Some(name.clone()), kind: BodyKind::Function });
+        let method_count = methods.len();
+        for method in methods {
+            self.make_function(method, Some(&name))?;
+        }
+        let constructor_count = constructors.len();
+        for (function, body) in constructors {
+            let at = self.emit(Instr::MakeFunction(Rc::new(function)));
+            self.functions.push(PendingFunction { at, body, owner: Some(name.clone()), kind: BodyKind::Constructor });
+        }
+        let static_method_count = static_methods.len();
+        for function in static_methods {
+            self.make_function(function, Some(&name))?;
+        }
+        let has_static_constructor = static_constructor.is_some();
+        if let Some(decl) = static_constructor {
+            self.make_function(FuncDecl { name: name.clone(), ..decl }, Some(&name))?;
+        }
+        self.program.push(Instr::MakeClass {
+            name: name.clone(),
+            parent: parent.is_some(),
+            fields,
+            statics,
+            methods: method_count,
+            constructors: constructor_count,
+            static_methods: static_method_count,
+            static_constructor: has_static_constructor,
+        });
+        self.program.push(Instr::Dup);
+        self.program.push(Instr::StoreVar(name));
+        self.program.push(Instr::InitStatics);
+        self.program.push(Instr::Pop);
+        Ok(())
+    }
+
+    /// The `Value::type_name` a field or static variable declared as `var name[: ty] = expr` must
+    /// hold, checking an enum type's initial value now.
+    fn member_type(&self, name: &str, ty: Option<String>, expr: &Expr, line: usize) -> Result<Option<String>, PalladError> {
+        let Some(ty) = ty else {
+            return Ok(None);
+        };
+        self.check_annotation(name, &ty, expr, line)?;
+        Ok(Some(self.value_type(&ty, line)?))
+    }
+
+    /// Emits the bodies of the declared functions after the rest of the program, which jumps past
+    /// them, and points each `MakeFunction` at its body. A body that ends without `return`
+    /// returns `none`, or `self` for a class initializer or constructor.
+    fn compile_functions(&mut self) -> Result<(), PalladError> {
+        if self.functions.is_empty() {
+            return Ok(());
+        }
+        let skip = self.emit(Instr::Jump(0));
+        // Functions declared inside function bodies are added as those bodies compile
+        while !self.functions.is_empty() {
+            let PendingFunction { at, body, owner, kind } = self.functions.remove(0);
+            let entry = self.program.len();
+            if let Instr::MakeFunction(function) = &mut self.program[at] {
+                Rc::make_mut(function).entry = entry;
+            }
+            self.owner = owner;
+            self.in_constructor = kind == BodyKind::Constructor;
+            match kind {
+                BodyKind::Function => {}
+                BodyKind::Initializer => self.program.push(Instr::InitParent),
+                BodyKind::Constructor => self.program.push(Instr::InitFields),
+            }
+            if kind != BodyKind::Function {
+                self.program.push(Instr::Pop);
+            }
+            self.compile_block(body)?;
+            match kind {
+                BodyKind::Function => self.program.push(Instr::LoadNone),
+                _ => self.program.push(Instr::LoadVar("self".to_string())),
+            }
+            self.program.push(Instr::Return);
+        }
+        self.owner = None;
+        self.in_constructor = false;
+        self.patch_jump(skip);
+        Ok(())
+    }
+
+    /// The parameters of a function or signal, with their types resolved to `Value::type_name`s.
+    fn params(&self, params: Vec<Param>, line: usize) -> Result<Vec<ir::Param>, PalladError> {
+        params.into_iter()
+            .map(|param| {
+                let expected = param.ty.map(|ty| self.value_type(&ty, line)).transpose()?;
+                Ok(ir::Param { name: param.name, expected })
+            })
+            .collect()
+    }
+
+    /// The `Value::type_name` of values of the annotated type `ty`; named enums are integers, and
+    /// a class name (or the name of an imported module, which may be a class) stands for
+    /// instances of the class.
+    fn value_type(&self, ty: &str, line: usize) -> Result<String, PalladError> {
+        if self.enums.contains_key(ty) {
+            return Ok("integer".to_string());
+        }
+        if self.classes.contains_key(ty) || self.imports.contains(ty) {
+            return Ok(ty.to_string());
+        }
+        TYPES.iter()
+            .find(|(type_name, _)| *type_name == ty)
+            .map(|(_, value_type)| value_type.to_string())
+            .ok_or_else(|| PalladError::UnknownType { name: ty.to_string(), line })
+    }
+
+    /// Records that the variable or parameter `name` is declared with the type `ty`.
+    fn annotate(&mut self, name: &str, ty: &str) {
+        self.annotations.entry(name.to_string())
+            .and_modify(|known| if known.as_deref() != Some(ty) { *known = None })
+            .or_insert_with(|| Some(ty.to_string()));
+    }
+
+    /// Checks `var name: ty = expr`, and returns what the variable accepts. A named enum type needs
+    /// one of its members, a variable annotated with the same enum, or no value; a value whose enum
+    /// can't be told here is checked to be a member's value when it is stored, like values of other
+    /// types are.
+    fn check_annotation(&self, name: &str, ty: &str, expr: &Expr, line: usize) -> Result<Annotation, PalladError> {
+        if let Some(members) = self.enums.get(ty) {
+            let is_member = match expr {
+                Expr::None => Some(true),
+                Expr::Attribute { target, name: member, .. } => match &**target {
+                    Expr::Var(enum_name) if self.enums.contains_key(enum_name) => {
+                        Some(enum_name == ty && members.contains_key(member))
+                    }
+                    _ => None,
+                },
+                // Shadowed members are usable by their name alone
+                Expr::Var(member) if self.constants.contains_key(member) => Some(members.contains_key(member)),
+                Expr::Var(variable) => match self.annotations.get(variable) {
+                    Some(Some(annotation)) => Some(annotation == ty),
+                    _ => None,
+                },
+                Expr::Bool(_) | Expr::Int(_) | Expr::BigInt(_) | Expr::Float(_) | Expr::Decimal(_) | Expr::Str(_)
+                | Expr::FString(_) | Expr::Array(_) | Expr::Tuple(_) | Expr::Dict(_) => Some(false),
+                _ => None,
+            };
+            if is_member == Some(false) {
+                return Err(PalladError::EnumTypeMismatch { name: name.to_string(), enum_name: ty.to_string(), line });
+            }
+            let mut members: Vec<i64> = members.values().copied().collect();
+            members.sort_unstable();
+            return Ok(Annotation::Enum { name: ty.to_string(), members });
+        }
+        Ok(Annotation::Type(self.value_type(ty, line)?))
+    }
+
+    /// Emits a `try` statement. With a `finally` block, the whole statement runs under an outer
+    /// handler, and the `finally` block is emitted twice: once for normal completion, and once on
+    /// the exception path, followed by re-raising the exception.
+    ///
+    /// The `except` clauses run with the caught exception on the stack; each checks its classes and
+    /// falls through to the next, and an exception no clause matches is raised again.
+    fn compile_try(
+        &mut self,
+        body: Vec<Stmt>,
+        handlers: Vec<ExceptClause>,
+        else_body: Vec<Stmt>,
+        finally_body: Option<Vec<Stmt>>,
+    ) -> Result<(), PalladError> {
+        let finally_handler = finally_body.is_some().then(|| self.emit(Instr::SetupTry(0)));
+        if let Some(finally_body) = &finally_body {
+            self.exits.push(Exit::Finally(finally_body.clone()));
+        }
+
+        if handlers.is_empty() {
+            self.compile_block(body)?;
+        } else {
+            let setup = self.emit(Instr::SetupTry(0));
+            self.exits.push(Exit::Handler);
+            self.compile_block(body)?;
+            self.exits.pop();
+            self.program.push(Instr::PopTry);
+            self.compile_block(else_body)?;
+            let mut exits = vec![self.emit(Instr::Jump(0))];
+
+            self.patch_jump(setup);
+            for clause in handlers {
+                let skip = (!clause.classes.is_empty())
+                    .then(|| self.emit(Instr::JumpIfNotException { classes: clause.classes, target: 0 }));
+                if let Some(name) = clause.name {
+                    self.program.push(Instr::Dup);
+                    self.program.push(Instr::StoreVar(name));
+                }
+                self.compile_block(clause.body)?;
+                self.program.push(Instr::Pop);
+                exits.push(self.emit(Instr::Jump(0)));
+                if let Some(skip) = skip {
+                    self.patch_jump(skip);
+                }
+            }
+            self.program.push(Instr::Raise);
+            for exit in exits {
+                self.patch_jump(exit);
+            }
+        }
+
+        if let (Some(finally_body), Some(finally_handler)) = (finally_body, finally_handler) {
+            self.exits.pop();
+            self.program.push(Instr::PopTry);
+            self.compile_block(finally_body.clone())?;
+            let done = self.emit(Instr::Jump(0));
+            self.patch_jump(finally_handler);
+            self.compile_block(finally_body)?;
+            self.program.push(Instr::Raise);
+            self.patch_jump(done);
+        }
+        Ok(())
+    }
+
+    /// Emits what leaving the blocks in `exits` past the first `depth` takes, innermost first:
+    /// removing their exception handlers, running their `finally` blocks and closing their `with`
+    /// resources. A `finally` block runs outside the blocks it encloses, so a `return` in it only
+    /// closes the ones around it.
+    fn close_exits(&mut self, depth: usize) -> Result<(), PalladError> {
+        let exits = self.exits.clone();
+        while self.exits.len() > depth {
+            match self.exits.pop() {
+                Some(Exit::Handler) => self.program.push(Instr::PopTry),
+                Some(Exit::Finally(body)) => {
+                    self.program.push(Instr::PopTry);
+                    self.compile_block(body)?;
+                }
+                Some(Exit::With) => self.program.push(Instr::ExitWith),
+                None => {}
+            }
+        }
+        self.exits = exits;
+        Ok(())
+    }
+
+    /// Emits a `switch`: the subject, a dispatch instruction, then each case's block followed by a
+    /// jump past the rest. Dense integer cases dispatch through a `JumpTable`, others through a
+    /// `JumpMap`. A value repeated in a later case is reported as a warning, and the first case
+    /// with the value wins.
+    fn compile_switch(&mut self, subject: Expr, cases: Vec<SwitchCase>, default: Option<Vec<Stmt>>) -> Result<(), PalladError> {
+        self.compile_expr(subject)?;
+        let dispatch = self.emit(Instr::Pop);
+
+        let mut targets: HashMap<CaseKey, usize> = HashMap::new();
+        let mut exits = vec![];
+        for case in cases {
+            let start = self.program.len();
+            for value in &case.values {
+                let key = self.case_key(value).ok_or(PalladError::InvalidCaseValue { line: case.line })?;
+                match targets.entry(key) {
+                    Entry::Occupied(entry) => {
+                        self.warnings.push(Warning::DuplicateCase { value: entry.key().to_string(), line: case.line });
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(start);
+                    }
+                }
+            }
+            self.compile_block(case.body)?;
+            exits.push(self.emit(Instr::Jump(0)));
+        }
+        let default_target = self.program.len();
+        if let Some(default) = default {
+            self.compile_block(default)?;
+        }
+        for exit in exits {
+            self.patch_jump(exit);
+        }
+
+        self.program[dispatch] = dispatch_instr(targets, default_target);
+        Ok(())
+    }
+
+    /// Emits a `match`. When every arm is an unguarded integer or string constant (or alternatives
+    /// of them) it compiles like a `switch`. Otherwise each arm tests its pattern in turn with
+    /// `MatchPattern`, binding its captures in a scope of the arm on a match, then checks its
+    /// guard; the subject stays on the stack until an arm or the `default` block is chosen.
+    ///
+    /// A match over members of a named enum with no catch-all arm is reported as a warning when it
+    /// leaves members out, and so is an unguarded arm matching anything before other arms.
+    fn compile_match(&mut self, subject: Expr, arms: Vec<MatchArm>, default: Option<Vec<Stmt>>, line: usize) -> Result<(), PalladError> {
+        self.check_exhaustive(&arms, default.is_some(), line);
+
+        let constant_cases: Option<Vec<SwitchCase>> = arms.iter()
+            .map(|arm| {
+                let values = self.constant_values(&arm.pattern)?;
+                arm.guard.is_none().then(|| SwitchCase { values, body: arm.body.clone(), line: arm.line })
+            })
+            .collect();
+        if let Some(cases) = constant_cases {
+            return self.compile_switch(subject, cases, default);
+        }
+
+        self.compile_expr(subject)?;
+        let mut exits = vec![];
+        let last = arms.len().saturating_sub(1);
+        for (i, arm) in arms.into_iter().enumerate() {
+            if arm.guard.is_none() && (i < last || default.is_some()) && self.top_level_members(&arm.pattern, &mut vec![]) {
+                self.warnings.push(Warning::UnreachableArms { line: arm.line });
+            }
+            let pattern = self.lower_pattern(arm.pattern, arm.line)?;
+            let test = self.emit(Instr::MatchPattern { pattern, target: 0 });
+            let guard = match arm.guard {
+                Some(guard) => {
+                    self.compile_expr(guard)?;
+                    Some(self.emit(Instr::JumpIfFalse(0)))
+                }
+                None => None,
+            };
+            self.program.push(Instr::Pop);
+            self.compile_block(arm.body)?;
+            self.program.push(Instr::ExitScope);
+            exits.push(self.emit(Instr::Jump(0)));
+            // A failed guard leaves the scope its pattern opened
+            if let Some(guard) = guard {
+                self.patch_jump(guard);
+                self.program.push(Instr::ExitScope);
+            }
+            self.patch_jump(test);
+        }
+        self.program.push(Instr::Pop);
+        if let Some(default) = default {
+            self.compile_block(default)?;
+        }
+        for exit in exits {
+            self.patch_jump(exit);
+        }
+        Ok(())
+    }
+
+    /// The values of a pattern made only of integer or string constants, as `case` values, or
+    /// `None` if it has any other kind of pattern.
+    fn constant_values(&self, pattern: &Pattern) -> Option<Vec<Expr>> {
+        match pattern {
+            Pattern::Value(value) => self.case_key(value).map(|_| vec![value.clone()]),
+            Pattern::Bind(name) if self.constants.contains_key(name) => Some(vec![Expr::Var(name.clone())]),
+            Pattern::Or(alternatives) => {
+                let values: Option<Vec<Vec<Expr>>> = alternatives.iter().map(|p| self.constant_values(p)).collect();
+                values.map(|values| values.concat())
+            }
+            _ => None,
+        }
+    }
+
+    /// Warns about a `match` whose arms test members of a named enum but miss some, unless it has a
+    /// `default` block or an unguarded arm matching anything.
+    fn check_exhaustive(&mut self, arms: &[MatchArm], has_default: bool, line: usize) {
+        let mut used = BTreeSet::new();
+        let mut covered = vec![];
+        let mut catch_all = has_default;
+        for arm in arms {
+            let mut members = vec![];
+            let matches_all = self.top_level_members(&arm.pattern, &mut members);
+            used.extend(members.iter().map(|(enum_name, _)| enum_name.clone()));
+            if arm.guard.is_none() {
+                catch_all |= matches_all;
+                covered.extend(members);
+            }
+        }
+        if catch_all {
+            return;
+        }
+
+        for enum_name in used {
+            let mut missing: Vec<(&String, &i64)> = self.enums[&enum_name].iter()
+                .filter(|(member, _)| !covered.iter().any(|(e, m)| *e == enum_name && m == *member))
+                .collect();
+            if missing.is_empty() {
+                continue;
+            }
+            missing.sort_by_key(|(member, value)| (**value, (*member).clone()));
+            let missing = missing.into_iter().map(|(member, _)| member.clone()).collect();
+            self.warnings.push(Warning::NonExhaustiveMatch { enum_name, missing, line });
+        }
+    }
+
+    /// Collects the named enum members a pattern tests the subject itself against (not those nested
+    /// in tuples and the like), and returns whether the pattern matches any value.
+    fn top_level_members(&self, pattern: &Pattern, members: &mut Vec<(String, String)>) -> bool {
+        let member_of = |name: &str| {
+            let value = self.constants.get(name)?;
+            self.enums.iter()
+                .find(|(_, members)| members.get(name) == Some(value))
+                .map(|(enum_name, _)| (enum_name.clone(), name.to_string()))
+        };
+        match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Bind(name) => match member_of(name) {
+                Some(member) => { members.push(member); false }
+                None => !self.constants.contains_key(name),
+            },
+            Pattern::Value(Expr::Var(name)) => {
+                members.extend(member_of(name));
+                false
+            }
+            Pattern::Value(Expr::Attribute { target, name, .. }) => {
+                if let Expr::Var(enum_name) = &**target
+                    && self.enums.get(enum_name).is_some_and(|members| members.contains_key(name))
+                {
+                    members.push((enum_name.clone(), name.clone()));
+                }
+                false
+            }
+            Pattern::Or(alternatives) => {
+                let mut matches_all = false;
+                for alternative in alternatives {
+                    matches_all |= self.top_level_members(alternative, members);
+                }
+                matches_all
+            }
+            _ => false,
+        }
+    }
+
+    /// Resolves the literals and enum members of a pattern to values. A name that is an enum member
+    /// usable on its own matches that member; any other bare name captures the value.
+    fn lower_pattern(&self, pattern: Pattern, line: usize) -> Result<ir::Pattern, PalladError> {
+        let lower_all = |patterns: Vec<Pattern>| -> Result<Vec<ir::Pattern>, PalladError> {
+            patterns.into_iter().map(|p| self.lower_pattern(p, line)).collect()
+        };
+        Ok(match pattern {
+            Pattern::Wildcard => ir::Pattern::Wildcard,
+            Pattern::Bind(name) => match self.constants.get(&name) {
+                Some(value) => ir::Pattern::Value(Value::Int(*value)),
+                None if self.enums.contains_key(&name) => return Err(PalladError::AssignToConstant { name, line }),
+                None => ir::Pattern::Bind(name),
+            },
+            Pattern::Value(expr) => ir::Pattern::Value(self.pattern_value(&expr, line)?),
+            Pattern::Range { start, end } => {
+                let start = self.pattern_value(&start, line)?;
+                let end = self.pattern_value(&end, line)?;
+                let is_number = |v: &Value| matches!(v, Value::Int(_) | Value::BigInt(_) | Value::Float(_) | Value::Decimal(_));
+                if !is_number(&start) || !is_number(&end) {
+                    return Err(PalladError::InvalidPattern { reason: "range bounds must be numbers", line });
+                }
+                ir::Pattern::Range { start, end }
+            }
+            Pattern::Tuple(items) => ir::Pattern::Tuple(lower_all(items)?),
+            Pattern::Array(items) => ir::Pattern::Array(lower_all(items)?),
+            Pattern::Dict(entries) => ir::Pattern::Dict(
+                entries.into_iter()
+                    .map(|(key, pattern)| Ok((self.pattern_value(&key, line)?, self.lower_pattern(pattern, line)?)))
+                    .collect::<Result<_, PalladError>>()?,
+            ),
+            Pattern::Or(alternatives) => ir::Pattern::Or(lower_all(alternatives)?),
+        })
+    }
+
+    /// The value of a literal (optionally negative) or enum member in a pattern.
+    fn pattern_value(&self, expr: &Expr, line: usize) -> Result<Value, PalladError> {
+        Ok(match expr {
+            Expr::None => Value::None,
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Int(n) => Value::Int(*n),
+            Expr::BigInt(n) => Value::BigInt(n.clone()),
+            Expr::Float(f) => Value::Float(*f),
+            Expr::Decimal(d) => Value::Decimal(d.clone()),
+            Expr::Str(s) => Value::Str(s.clone()),
+            Expr::Binary { left, op: BinOp::Sub, right } if matches!(&**left, Expr::Int(0)) => match &**right {
+                Expr::Int(n) => Value::Int(-n),
+                Expr::Float(f) => Value::Float(-f),
+                _ => return Err(PalladError::InvalidPattern { reason: "only number literals can be negated", line }),
+            },
+            Expr::Attribute { target, name, line } => {
+                let Expr::Var(enum_name) = &**target else {
+                    return Err(PalladError::InvalidPattern { reason: "expected a literal or an enum member", line: *line });
+                };
+                let value = self.enums.get(enum_name)
+                    .ok_or_else(|| PalladError::InvalidPattern { reason: "expected a literal or an enum member", line: *line })?
+                    .get(name)
+                    .ok_or_else(|| PalladError::UnknownEnumMember { enum_name: enum_name.clone(), name: name.clone(), line: *line })?;
+                Value::Int(*value)
+            }
+            Expr::Var(name) if self.constants.contains_key(name) => Value::Int(self.constants[name]),
+            _ => return Err(PalladError::InvalidPattern { reason: "expected a literal or an enum member", line }),
+        })
+    }
+
+    /// The value a `case` matches: an integer or string literal, or an enum member.
+    fn case_key(&self, value: &Expr) -> Option<CaseKey> {
+        match value {
+            Expr::Int(n) => Some(CaseKey::Int(*n)),
+            Expr::Str(s) => Some(CaseKey::Str(s.clone())),
+            Expr::Binary { left, op: BinOp::Sub, right } => match (&**left, &**right) {
+                (Expr::Int(0), Expr::Int(n)) => Some(CaseKey::Int(-n)),
+                _ => None,
+            },
+            Expr::Var(name) => self.constants.get(name).map(|n| CaseKey::Int(*n)),
+            Expr::Attribute { target, name, .. } => match &**target {
+                Expr::Var(enum_name) => self.enums.get(enum_name)?.get(name).map(|n| CaseKey::Int(*n)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Fails when the private class member `name` is used outside any class; inside one, the VM
+    /// checks that it is the class declaring the member.
+    fn check_private(&self, name: &str, line: usize) -> Result<(), PalladError> {
+        if object::is_private(name) && self.owner.is_none() {
+            return Err(PalladError::PrivateAccess { name: name.to_string(), line });
+        }
+        Ok(())
+    }
+
+    /// Pushes `instr` and returns its index, for jumps patched once their target is known.
+    fn emit(&mut self, instr: Instr) -> usize {
+        self.program.push(instr);
+        self.program.len() - 1
+    }
+
+    /// Points the jump or handler at `at` to the next instruction to be emitted.
+    fn patch_jump(&mut self, at: usize) {
+        let next = self.program.len();
+        match &mut self.program[at] {
+            Instr::Jump(target)
+            | Instr::JumpIfTrue(target)
+            | Instr::JumpIfFalse(target)
+            | Instr::MatchPattern { target, .. }
+            | Instr::SetupTry(target)
+            | Instr::JumpIfNotException { target, .. } => *target = next,
+            _ => {}
+        }
+    }
+
+    /// Emits IR instructions for `expr` into the program.
+    ///
+    /// Supports literals, f-strings (each field formatted, then all parts concatenated), variable loads,
+    /// enum members (as constants), arrays, tuples, dicts, indexing, method calls (receiver, then arguments), binary
+    /// operations (left then right), unary operations (operand first), and builtin function calls
+    /// (positional, then keyword arguments compiled in order).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// compiler.compile_expr(Expr::Int(42))?;
+    /// assert_eq!(compiler.program, vec![Instr::LoadInt(42)]);
+    /// ```
+    fn compile_expr(&mut self, expr: Expr) -> Result<(), PalladError> {
+        match expr {
+            Expr::None => self.program.push(Instr::LoadNone),
+            Expr::Bool(b) => self.program.push(Instr::LoadBool(b)),
+            Expr::Int(n) => self.program.push(Instr::LoadInt(n)),
+            Expr::BigInt(n) => self.program.push(Instr::LoadBigInt(n)),
+            Expr::Float(f) => self.program.push(Instr::LoadFloat(f)),
+            Expr::Decimal(d) => self.program.push(Instr::LoadDecimal(d)),
+            Expr::Str(s) => self.program.push(Instr::LoadStr(s)),
+            Expr::FString(parts) => {
+                let count = parts.len();
+                for part in parts {
+                    match part {
+                        FStringPart::Lit(text) => self.program.push(Instr::LoadStr(text)),
+                        FStringPart::Expr { expr, spec } => {
+                            self.compile_expr(expr)?;
+                            self.program.push(Instr::FormatValue(spec));
+                        }
+                    }
+                }
+                self.program.push(Instr::Concat(count));
+            }
+            Expr::Var(name) => match self.constants.get(&name) {
+                Some(value) => self.program.push(Instr::LoadInt(*value)),
+                None => self.program.push(Instr::LoadVar(name)),
+            },
+            Expr::Array(items) => {
+                let count = items.len();
+                for item in items {
+                    self.compile_expr(item)?;
+                }
+                self.program.push(Instr::BuildArray(count));
+            }
+            Expr::Tuple(items) => {
+                let count = items.len();
+                for item in items {
+                    self.compile_expr(item)?;
+                }
+                self.program.push(Instr::BuildTuple(count));
+            }
+            Expr::Dict(entries) => {
+                let count = entries.len();
+                for (key, value) in entries {
+                    self.compile_expr(key)?;
+                    self.compile_expr(value)?;
+                }
+                self.program.push(Instr::BuildDict(count));
+            }
+            Expr::Index { target, index } => {
+                self.compile_expr(*target)?;
+                self.compile_expr(*index)?;
+                self.program.push(Instr::Index);
+            }
+            Expr::Attribute { target, name, line } => {
+                if let Expr::Var(enum_name) = &*target
+                    && let Some(members) = self.enums.get(enum_name)
+                {
+                    let value = members.get(&name).ok_or_else(|| PalladError::UnknownEnumMember {
+                        enum_name: enum_name.clone(),
+                        name: name.clone(),
+                        line,
+                    })?;
+                    self.program.push(Instr::LoadInt(*value));
+                    return Ok(());
+                }
+                self.check_private(&name, line)?;
+                self.compile_expr(*target)?;
+                self.program.push(Instr::GetAttr(name));
+            }
+            Expr::MethodCall { receiver, name, args, line } if matches!(&*receiver, Expr::Var(target) if target == "super") => {
+                let has_parent = self.owner.as_ref().is_some_and(|owner| matches!(self.classes.get(owner), Some(Some(_))));
+                if !has_parent {
+                    return Err(PalladError::InvalidSuper { line });
+                }
                 let argc = args.len();
+                self.program.push(Instr::LoadVar("self".to_string()));
                 for arg in args {
-                    compile_expr(arg, &mut program);
+                    self.compile_expr(arg)?;
                 }
-                program.push(Instr::CallBuiltin { name, argc });
+                self.program.push(Instr::CallSuper { name, argc });
             }
-            Stmt::Expr(expr) => {
-                compile_expr(expr, &mut program);
-                program.push(Instr::Pop);
+            Expr::MethodCall { receiver, name, args, line } => {
+                self.check_private(&name, line)?;
+                let argc = args.len();
+                self.compile_expr(*receiver)?;
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
+                self.program.push(Instr::CallMethod { name, argc, line });
+            }
+            Expr::Binary { left, op, right } => {
+                self.compile_expr(*left)?;
+                self.compile_expr(*right)?;
+                self.program.push(match op {
+                    BinOp::Add => Instr::Add,
+                    BinOp::Sub => Instr::Sub,
+                    BinOp::Mul => Instr::Mul,
+                    BinOp::Div => Instr::Div,
+                    BinOp::IntDiv => Instr::IntDiv,
+                    BinOp::Mod => Instr::Mod,
+                    BinOp::Pow => Instr::Pow,
+                    BinOp::BitAnd => Instr::BitAnd,
+                    BinOp::BitOr => Instr::BitOr,
+                    BinOp::BitXor => Instr::BitXor,
+                    BinOp::Shl => Instr::Shl,
+                    BinOp::Shr => Instr::Shr,
+                    BinOp::Eq => Instr::Eq,
+                    BinOp::NotEq => Instr::NotEq,
+                    BinOp::Lt => Instr::Lt,
+                    BinOp::Le => Instr::Le,
+                    BinOp::Gt => Instr::Gt,
+                    BinOp::Ge => Instr::Ge,
+                });
+            }
+            Expr::Unary { op, operand } => {
+                self.compile_expr(*operand)?;
+                match op {
+                    UnaryOp::BitNot => self.program.push(Instr::BitNot),
+                }
+            }
+            Expr::Call { name, args, kwargs } => {
+                let argc = args.len();
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
+                let mut names = Vec::with_capacity(kwargs.len());
+                for (name, value) in kwargs {
+                    self.compile_expr(value)?;
+                    names.push(name);
+                }
+                self.program.push(Instr::CallBuiltin { name, argc, kwargs: names });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The instruction that jumps to the case matching the switch subject: a `JumpTable` when the
+/// cases are all integers within a small, mostly used range, or a `JumpMap` otherwise.
+fn dispatch_instr(targets: HashMap<CaseKey, usize>, default: usize) -> Instr {
+    let ints: Option<Vec<(i64, usize)>> = targets.iter()
+        .map(|(key, target)| match key {
+            CaseKey::Int(n) => Some((*n, *target)),
+            CaseKey::Str(_) => None,
+        })
+        .collect();
+    if let Some(ints) = ints
+        && let (Some(min), Some(max)) = (ints.iter().map(|(n, _)| *n).min(), ints.iter().map(|(n, _)| *n).max())
+    {
+        let span = max as i128 - min as i128 + 1;
+        if span <= JUMP_TABLE_MAX_SPAN as i128 && span <= 2 * ints.len() as i128 {
+            let mut table = vec![default; span as usize];
+            for (n, target) in ints {
+                table[(n - min) as usize] = target;
             }
+            return Instr::JumpTable { min, targets: table, default };
         }
     }
+    Instr::JumpMap { targets, default }
+}
 
-    Ok(program)
+/// Values of an enum's members in order: explicit values are integer literals (optionally
+/// negative), and the others continue from the previous member.
+fn enum_values(members: &[EnumMember]) -> Result<Vec<(String, i64)>, PalladError> {
+    let mut values: Vec<(String, i64)> = vec![];
+    let mut next = Some(0);
+    for member in members {
+        let invalid = || PalladError::InvalidEnumValue { name: member.name.clone(), line: member.line };
+        if values.iter().any(|(name, _)| *name == member.name) {
+            return Err(PalladError::DuplicateName { name: member.name.clone(), line: member.line });
+        }
+        let value = match &member.value {
+            None => next.ok_or_else(invalid)?,
+            Some(Expr::Int(n)) => *n,
+            Some(Expr::Binary { left, op: BinOp::Sub, right }) => match (&**left, &**right) {
+                (Expr::Int(0), Expr::Int(n)) => -n,
+                _ => return Err(invalid()),
+            },
+            Some(_) => return Err(invalid()),
+        };
+        values.push((member.name.clone(), value));
+        next = value.checked_add(1);
+    }
+    Ok(values)
 }
 
-/// Emits IR instructions for `expr` into the provided `program` buffer.
-///
-/// Supports integer and float literals, variable loads, binary operations (left then right),
-/// and builtin function calls (arguments compiled in order).
-///
-/// # Examples
-///
-/// ```
-/// let mut program = Vec::new();
-/// compile_expr(Expr::Int(42), &mut program);
-/// assert_eq!(program, vec![Instr::LoadInt(42)]);
-/// ```
-fn compile_expr(expr: Expr, program: &mut Vec<Instr>) {
-    match expr {
-        Expr::None => program.push(Instr::LoadNone),
-        Expr::Int(n) => program.push(Instr::LoadInt(n)),
-        Expr::Float(f) => program.push(Instr::LoadFloat(f)),
-        Expr::Str(s) => program.push(Instr::LoadStr(s)),
-        Expr::Var(name) => program.push(Instr::LoadVar(name)),
-        Expr::Binary { left, op, right } => {
-            compile_expr(*left, program);
-            compile_expr(*right, program);
-            match op {
-                BinOp::Add => program.push(Instr::Add),
-                BinOp::Sub => program.push(Instr::Sub),
-                BinOp::Mul => program.push(Instr::Mul),
-                BinOp::Div => program.push(Instr::Div),
-                BinOp::IntDiv => program.push(Instr::IntDiv),
-                BinOp::Mod => program.push(Instr::Mod),
-            }
-        }
-        Expr::Call { name, args } => {
-            let argc = args.len();
-            for arg in args {
-                compile_expr(arg, program);
-            }
-            program.push(Instr::CallBuiltin { name, argc });
-        }
-    }
-}
\ No newline at end of file
+/// Whether `name` follows the naming rule for classes: PascalCase, like `User` or `HttpClient`.
+/// The `_` of a private class is ignored.
+fn is_pascal_case(name: &str) -> bool {
+    let name = name.trim_start_matches('_');
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Whether the module or folder `excluded` is, or is inside, the `imported` one, or the other way
+/// around, going by their paths: `exclude utils` overlaps `import utils.pd`, `import *` and
+/// `from "./" import *`.
+fn overlaps(imported: &str, excluded: &str) -> bool {
+    let normalize = |path: &str| {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        path.strip_suffix(".pd").unwrap_or(path).to_string()
+    };
+    // The folder a module is in, with its `/`, or `""` for the importing module's own folder
+    let folder = |path: &str| path.rfind('/').map_or("", |end| &path[..=end]).to_string();
+    let (imported, excluded) = (normalize(imported), normalize(excluded));
+    imported == excluded
+        || (imported.ends_with('/') || imported.is_empty()) && folder(&excluded) == imported
+        || (excluded.ends_with('/') || excluded.is_empty()) && folder(&imported) == excluded
+}
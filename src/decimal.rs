@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+use std::fmt;
+use crate::bigint::{self, BigInt};
+
+/// How a decimal result is rounded when it has more fractional digits than the context allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to the even neighbour (banker's rounding).
+    #[default]
+    HalfEven,
+    /// Round to nearest, ties away from zero.
+    HalfUp,
+    /// Round to nearest, ties toward zero.
+    HalfDown,
+    /// Round away from zero.
+    Up,
+    /// Round toward zero (truncate).
+    Down,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round toward negative infinity.
+    Floor,
+}
+
+impl RoundingMode {
+    /// Looks up a rounding mode by its command-line name (`half-even`, `half-up`, `half-down`,
+    /// `up`, `down`, `ceiling`, or `floor`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "half-even" => Some(RoundingMode::HalfEven),
+            "half-up" => Some(RoundingMode::HalfUp),
+            "half-down" => Some(RoundingMode::HalfDown),
+            "up" => Some(RoundingMode::Up),
+            "down" => Some(RoundingMode::Down),
+            "ceiling" => Some(RoundingMode::Ceiling),
+            "floor" => Some(RoundingMode::Floor),
+            _ => None,
+        }
+    }
+}
+
+/// Largest `DecimalContext::precision`, which keeps the operands of a division, scaled up to the
+/// precision, within a size that divides quickly.
+pub const MAX_PRECISION: u32 = 1000;
+
+/// Why a decimal operation has no result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The result would exceed `bigint::MAX_BITS`.
+    Overflow,
+    /// The operation divides by zero, as a zero base with a negative exponent does.
+    DivisionByZero,
+}
+
+/// Precision and rounding applied to decimal arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalContext {
+    /// Maximum number of fractional digits kept in a result, at most `MAX_PRECISION`.
+    pub precision: u32,
+    pub rounding: RoundingMode,
+}
+
+impl Default for DecimalContext {
+    fn default() -> Self {
+        Self { precision: 28, rounding: RoundingMode::default() }
+    }
+}
+
+/// Exact base-10 number: `coefficient * 10^-scale`.
+///
+/// Addition, subtraction and multiplication are exact (rounded only past the context precision);
+/// division is computed to the context precision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decimal {
+    coefficient: BigInt,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parses a decimal literal such as `12`, `0.10`, or `3.` (digits with at most one `.`).
+    pub fn parse(text: &str) -> Option<Decimal> {
+        let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let coefficient = BigInt::parse(&format!("{}{}", integer, fraction))?;
+        Some(Decimal { coefficient, scale: fraction.len() as u32 })
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    /// Rounds to at most `context.precision` fractional digits.
+    pub fn round(&self, context: &DecimalContext) -> Decimal {
+        if self.scale <= context.precision {
+            return self.clone();
+        }
+        let divisor = pow10(self.scale - context.precision);
+        Decimal {
+            coefficient: divide_rounded(&self.coefficient, &divisor, context.rounding),
+            scale: context.precision,
+        }
+    }
+
+    /// Rounds or zero-extends to exactly `scale` fractional digits.
+    pub fn with_scale(&self, scale: u32, rounding: RoundingMode) -> Decimal {
+        if scale >= self.scale {
+            return Decimal {
+                coefficient: &self.coefficient * &pow10(scale - self.scale),
+                scale,
+            };
+        }
+        self.round(&DecimalContext { precision: scale, rounding })
+    }
+
+    /// Rounds to a whole number with `rounding` and returns it as an integer.
+    pub fn to_integer(&self, rounding: RoundingMode) -> BigInt {
+        self.with_scale(0, rounding).coefficient
+    }
+
+    /// Compares numerically, regardless of scale (`1.50d` equals `1.5d`).
+    pub fn compare(&self, other: &Decimal) -> Ordering {
+        let (a, b, _) = align(self, other);
+        a.cmp(&b)
+    }
+
+    /// Fails with `DecimalError::Overflow` when the result would exceed `bigint::MAX_BITS`, as
+    /// `sub`, `mul`, `div` and `pow` do.
+    pub fn add(&self, other: &Decimal, context: &DecimalContext) -> Result<Decimal, DecimalError> {
+        check_aligned_size(self, other)?;
+        let (a, b, scale) = align(self, other);
+        Ok(Decimal { coefficient: &a + &b, scale }.round(context))
+    }
+
+    pub fn sub(&self, other: &Decimal, context: &DecimalContext) -> Result<Decimal, DecimalError> {
+        check_aligned_size(self, other)?;
+        let (a, b, scale) = align(self, other);
+        Ok(Decimal { coefficient: &a - &b, scale }.round(context))
+    }
+
+    pub fn mul(&self, other: &Decimal, context: &DecimalContext) -> Result<Decimal, DecimalError> {
+        let scale = self.scale as u64 + other.scale as u64;
+        check_size(self.coefficient.bits() + other.coefficient.bits(), scale)?;
+        Ok(Decimal {
+            coefficient: &self.coefficient * &other.coefficient,
+            scale: scale as u32,
+        }.round(context))
+    }
+
+    /// Divides to `context.precision` fractional digits, then drops trailing zeros down to the
+    /// natural scale of the operands (so `1d / 4d` is `0.25`, and `10.00d / 2d` is `5.00`).
+    ///
+    /// Fails with `DecimalError::DivisionByZero` when `other` is zero.
+    pub fn div(&self, other: &Decimal, context: &DecimalContext) -> Result<Decimal, DecimalError> {
+        if other.is_zero() {
+            return Err(DecimalError::DivisionByZero);
+        }
+        // self / other == (a * 10^(precision + other.scale)) / (b * 10^self.scale) * 10^-precision
+        let shift = context.precision as u64 + other.scale as u64;
+        check_size(self.coefficient.bits(), shift)?;
+        check_size(other.coefficient.bits(), self.scale as u64)?;
+        let numerator = &self.coefficient * &pow10(context.precision + other.scale);
+        let denominator = &other.coefficient * &pow10(self.scale);
+        let quotient = Decimal {
+            coefficient: divide_rounded(&numerator, &denominator, context.rounding),
+            scale: context.precision,
+        };
+        Ok(quotient.trim_to(self.scale.saturating_sub(other.scale)))
+    }
+
+    /// Truncating integer division and remainder, matching `int` `//` and `%` semantics.
+    ///
+    /// The quotient is a whole decimal and the remainder has the sign of `self`; returns `None`
+    /// when `other` is zero.
+    pub fn div_rem(&self, other: &Decimal) -> Option<(Decimal, Decimal)> {
+        let (a, b, scale) = align(self, other);
+        let (quotient, remainder) = a.div_rem(&b)?;
+        Some((Decimal { coefficient: quotient, scale: 0 }, Decimal { coefficient: remainder, scale }))
+    }
+
+    /// Raises to an integer power; negative exponents divide `1` by the positive power.
+    ///
+    /// Fails with `DecimalError::Overflow` when the result would exceed `bigint::MAX_BITS`, and
+    /// with `DecimalError::DivisionByZero` for a zero base with a negative exponent.
+    pub fn pow(&self, exp: i64, context: &DecimalContext) -> Result<Decimal, DecimalError> {
+        let exp_abs = exp.unsigned_abs();
+        let scale = (self.scale as u64).saturating_mul(exp_abs);
+        check_size(self.coefficient.bits().saturating_mul(exp_abs), scale)?;
+        let power = Decimal { coefficient: self.coefficient.pow(exp_abs), scale: scale as u32 };
+        if exp >= 0 {
+            return Ok(power.round(context));
+        }
+        Decimal::from(BigInt::from(1)).div(&power, context)
+    }
+
+    /// Removes trailing fractional zeros while the scale stays above `min_scale`.
+    fn trim_to(mut self, min_scale: u32) -> Decimal {
+        let ten = BigInt::from(10);
+        while self.scale > min_scale {
+            match self.coefficient.div_rem(&ten) {
+                Some((quotient, remainder)) if remainder.is_zero() => {
+                    self.coefficient = quotient;
+                    self.scale -= 1;
+                }
+                _ => break,
+            }
+        }
+        self
+    }
+}
+
+impl From<BigInt> for Decimal {
+    fn from(n: BigInt) -> Self {
+        Decimal { coefficient: n, scale: 0 }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.coefficient.abs().to_string();
+        let scale = self.scale as usize;
+        if self.coefficient.is_negative() {
+            write!(f, "-")?;
+        }
+        if scale == 0 {
+            return write!(f, "{}", digits);
+        }
+        // Left-pad so there is at least one digit before the point
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}.{}", integer, fraction)
+    }
+}
+
+fn pow10(exp: u32) -> BigInt {
+    BigInt::from(10).pow(exp as u64)
+}
+
+/// Fails with `DecimalError::Overflow` unless a coefficient of `bits` bits and the power of ten
+/// that scales it by `scale` digits, as aligning and rounding it take, fit in `bigint::MAX_BITS`
+/// together.
+fn check_size(bits: u64, scale: u64) -> Result<(), DecimalError> {
+    // log2(10) is just under 10/3
+    let scale_bits = scale.saturating_mul(10) / 3 + 1;
+    if bits.saturating_add(scale_bits) > bigint::MAX_BITS {
+        return Err(DecimalError::Overflow);
+    }
+    Ok(())
+}
+
+/// Fails like `check_size` when `align` would make either coefficient too large.
+fn check_aligned_size(a: &Decimal, b: &Decimal) -> Result<(), DecimalError> {
+    let scale = a.scale.max(b.scale);
+    check_size(a.coefficient.bits(), (scale - a.scale) as u64)?;
+    check_size(b.coefficient.bits(), (scale - b.scale) as u64)?;
+    check_size(0, scale as u64)
+}
+
+/// Brings both coefficients to the larger of the two scales.
+fn align(a: &Decimal, b: &Decimal) -> (BigInt, BigInt, u32) {
+    let scale = a.scale.max(b.scale);
+    (
+        &a.coefficient * &pow10(scale - a.scale),
+        &b.coefficient * &pow10(scale - b.scale),
+        scale,
+    )
+}
+
+/// Divides `numerator` by a non-zero `denominator`, rounding the quotient with `mode`.
+fn divide_rounded(numerator: &BigInt, denominator: &BigInt, mode: RoundingMode) -> BigInt {
+    let Some((quotient, remainder)) = numerator.div_rem(denominator) else {
+        return BigInt::from(0);
+    };
+    if remainder.is_zero() {
+        return quotient;
+    }
+    let negative = numerator.is_negative() != denominator.is_negative();
+    // Compare the discarded part against one half
+    let half = (&remainder.abs() * &BigInt::from(2)).cmp(&denominator.abs());
+    let away_from_zero = match mode {
+        RoundingMode::Up => true,
+        RoundingMode::Down => false,
+        RoundingMode::Ceiling => !negative,
+        RoundingMode::Floor => negative,
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::HalfDown => half == Ordering::Greater,
+        RoundingMode::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && quotient.is_odd()),
+    };
+    if !away_from_zero {
+        return quotient;
+    }
+    &quotient + &BigInt::from(if negative { -1 } else { 1 })
+}
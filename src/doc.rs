@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use crate::ast::{FuncDecl, Param, Stmt};
+use crate::object;
+
+/// The formats `pallad doc` writes references in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    /// Looks up a format by name (`html`, or `markdown` / `md`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "html" => Some(Format::Html),
+            "markdown" | "md" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A declaration of a reference: a public variable, function, signal, enum or class of a file, or
+/// a public member of one of its classes.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The name, qualified by the class for members (`Shape.describe`).
+    pub name: String,
+    /// What the declaration is, such as `variable` or `static function`.
+    pub kind: &'static str,
+    /// How the declaration reads, with its parameters and types.
+    pub signature: String,
+    /// The docstring or comments above the declaration, in BBCode.
+    pub doc: Option<String>,
+}
+
+/// The entries documenting `stmts`, a file's statements, in source order; each class is followed
+/// by its members.
+pub fn entries(stmts: &[Stmt]) -> Vec<Entry> {
+    let mut entries = vec![];
+    for stmt in stmts {
+        declare(stmt, None, false, &mut entries);
+    }
+    entries
+}
+
+/// Adds the entry of `stmt` if it is a public declaration, of the class named `class` when it
+/// is a member.
+fn declare(stmt: &Stmt, class: Option<&str>, is_static: bool, entries: &mut Vec<Entry>) {
+    let qualify = |name: &str| class.map_or(name.to_string(), |class| format!("{}.{}", class, name));
+    let mut push = |name: &str, kind, signature: String, doc: &Option<String>| {
+        if !object::is_private(name) {
+            entries.push(Entry { name: qualify(name), kind, signature, doc: doc.clone() });
+        }
+    };
+    match stmt {
+        Stmt::Let { name, ty, doc, .. } => {
+            let kind = match (class, is_static) {
+                (None, _) => "variable",
+                (Some(_), false) => "field",
+                (Some(_), true) => "static variable",
+            };
+            let signature = match ty {
+                Some(ty) => format!("{}: {}", qualify(name), ty),
+                None => qualify(name),
+            };
+            push(name, kind, signature, doc);
+        }
+        Stmt::Func(decl) => {
+            let kind = match (class, is_static) {
+                (None, _) => "function",
+                (Some(_), false) => "method",
+                (Some(_), true) => "static function",
+            };
+            push(&decl.name, kind, function_signature(&qualify(&decl.name), decl), &decl.doc);
+        }
+        Stmt::Constructor(decl) => {
+            let kind = if is_static { "static constructor" } else { "constructor" };
+            push(&decl.name, kind, function_signature(&qualify(&decl.name), decl), &decl.doc);
+        }
+        Stmt::Signal { name, params, doc, .. } => {
+            push(name, "signal", format!("{}({})", qualify(name), parameters(params)), doc);
+        }
+        Stmt::Enum { name: Some(name), members, doc, .. } => {
+            let members: Vec<&str> = members.iter().map(|member| member.name.as_str()).collect();
+            push(name, "enum", format!("{} {{ {} }}", qualify(name), members.join(", ")), doc);
+        }
+        Stmt::Class(decl) if !object::is_private(&decl.name) => {
+            let name = qualify(&decl.name);
+            let signature = match &decl.parent {
+                Some(parent) => format!("{} extends {}", name, parent),
+                None => name.clone(),
+            };
+            push(&decl.name, "class", signature, &decl.doc);
+            for member in &decl.body {
+                declare(member, Some(&name), false, entries);
+            }
+        }
+        Stmt::Static(member) => declare(member, class, true, entries),
+        _ => {}
+    }
+}
+
+fn function_signature(name: &str, decl: &FuncDecl) -> String {
+    match &decl.returns {
+        Some(returns) => format!("{}({}) -> {}", name, parameters(&decl.params), returns),
+        None => format!("{}({})", name, parameters(&decl.params)),
+    }
+}
+
+fn parameters(params: &[Param]) -> String {
+    params.iter()
+        .map(|param| match &param.ty {
+            Some(ty) => format!("{}: {}", param.name, ty),
+            None => param.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes the reference of `stmts`, the statements of the file titled `title`, in `format`.
+///
+/// Each entry shows its signature and kind, then its doc with the BBCode rendered: `[b]`, `[i]`
+/// and `[u]` style text, `[code]` shows its content as is, `[br]` breaks the line, and
+/// `[func name]` and `[var name]` link to the entries documenting `name`. Other tags are kept as
+/// text.
+pub fn render(title: &str, stmts: &[Stmt], format: Format) -> String {
+    let entries = entries(stmts);
+    let mut anchors: HashMap<&str, usize> = HashMap::new();
+    let mut out = match format {
+        Format::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n<dl>\n",
+            escape(title),
+        ),
+        Format::Markdown => format!("# {}\n", title),
+    };
+    for entry in &entries {
+        // Overloaded constructors share a name, so later ones get numbered anchors
+        let count = anchors.entry(&entry.name).or_default();
+        *count += 1;
+        let anchor = match *count {
+            1 => entry.name.clone(),
+            n => format!("{}-{}", entry.name, n),
+        };
+        let doc = entry.doc.as_deref().map(|doc| bbcode(doc, format, &entries, &entry.name));
+        match format {
+            Format::Html => {
+                out.push_str(&format!(
+                    "<dt id=\"{}\"><code>{}</code> <em>{}</em></dt>\n",
+                    escape(&anchor), escape(&entry.signature), entry.kind,
+                ));
+                if let Some(doc) = doc {
+                    out.push_str(&format!("<dd>{}</dd>\n", doc));
+                }
+            }
+            Format::Markdown => {
+                out.push_str(&format!("\n<a id=\"{}\"></a>`{}` _{}_  \n", anchor, entry.signature, entry.kind));
+                if let Some(doc) = doc {
+                    out.push_str(&format!("**->** {}\n", doc));
+                }
+            }
+        }
+    }
+    if format == Format::Html {
+        out.push_str("</dl>\n</body>\n</html>\n");
+    }
+    out
+}
+
+/// Renders the BBCode `text` of the entry `owner` in `format`. Whitespace, line breaks included,
+/// reads as one space.
+fn bbcode(text: &str, format: Format, entries: &[Entry], owner: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let html = format == Format::Html;
+    let plain = |text: &str| if html { escape(text) } else { text.to_string() };
+    let mut out = String::new();
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find('[') {
+        out.push_str(&plain(&rest[..open]));
+        let Some(close) = rest[open..].find(']').map(|close| open + close) else {
+            rest = &rest[open..];
+            break;
+        };
+        let tag = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+        match (tag, format) {
+            ("b" | "/b", Format::Markdown) => out.push_str("**"),
+            ("i" | "/i", Format::Markdown) => out.push('_'),
+            ("b" | "/b" | "i" | "/i" | "u" | "/u", _) => out.push_str(&format!("<{}>", tag)),
+            ("br", Format::Html) => out.push_str("<br>"),
+            ("br", Format::Markdown) => out.push_str("  \n"),
+            ("code", _) => {
+                let end = rest.find("[/code]").unwrap_or(rest.len());
+                let code = &rest[..end];
+                rest = rest.get(end + "[/code]".len()..).unwrap_or("");
+                match format {
+                    Format::Html => out.push_str(&format!("<code>{}</code>", escape(code))),
+                    Format::Markdown => out.push_str(&format!("`{}`", code)),
+                }
+            }
+            _ => match tag.split_once(' ') {
+                Some((kind @ ("func" | "var"), name)) => {
+                    let name = name.trim();
+                    let label = if kind == "func" { format!("{}()", name) } else { name.to_string() };
+                    match (link(entries, owner, kind == "func", name), format) {
+                        (Some(anchor), Format::Html) => {
+                            out.push_str(&format!("<a href=\"#{}\">{}</a>", escape(anchor), escape(&label)));
+                        }
+                        (Some(anchor), Format::Markdown) => out.push_str(&format!("[{}](#{})", label, anchor)),
+                        (None, _) => out.push_str(&plain(&label)),
+                    }
+                }
+                _ => out.push_str(&plain(&format!("[{}]", tag))),
+            },
+        }
+    }
+    out.push_str(&plain(rest));
+    out
+}
+
+/// The name of the entry a `[func name]` (`function`) or `[var name]` tag in the doc of `owner`
+/// refers to: a member of the same class first, then a declaration of the file, then a member of
+/// any class.
+fn link<'a>(entries: &'a [Entry], owner: &str, function: bool, name: &str) -> Option<&'a str> {
+    let kinds: &[&str] = if function {
+        &["function", "method", "static function", "constructor", "signal"]
+    } else {
+        &["variable", "field", "static variable", "enum"]
+    };
+    let candidates: Vec<&Entry> = entries.iter().filter(|entry| kinds.contains(&entry.kind)).collect();
+    let sibling = owner.rsplit_once('.').map(|(class, _)| format!("{}.{}", class, name));
+    sibling.iter().map(String::as_str).chain([name])
+        .find_map(|target| candidates.iter().find(|entry| entry.name == target))
+        .or_else(|| candidates.iter().find(|entry| entry.name.rsplit('.').next() == Some(name)))
+        .map(|entry| entry.name.as_str())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
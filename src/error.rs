@@ -9,13 +9,75 @@ pub enum PalladError {
     UndefinedVariable { name: String },
     StackUnderflow { operation: &'static str },
     TypeMismatch { left: Value, right: Value, operation: &'static str },
+    InvalidOperand { value: Value, operation: &'static str },
     InvalidNumber { value: String, line: usize },
     DivisionByZero { operation: &'static str },
+    IntOverflow { operation: &'static str },
     IntDivOverflow,
+    PowOverflow,
+    DecimalOverflow { operation: &'static str },
+    ShiftOverflow,
+    NegativeShift,
     RepeatOverflow,
     NegativeRepeat,
     InvalidEscape { char: char, line: usize },
     UnterminatedString { line: usize },
+    InconsistentIndent { line: usize },
+    /// A `#endregion` closing no `#region`, or a `#region` never closed.
+    InvalidFString { reason: &'static str, line: usize },
+    InvalidFormatSpec { spec: String, line: usize },
+    /// A format spec of `string.format` that is malformed or uses an unsupported option.
+    UnsupportedFormatSpec { spec: String },
+    FormatMismatch { value: Value, spec: String },
+    UnknownMethod { type_name: &'static str, name: String },
+    UnknownAttribute { type_name: &'static str, name: String },
+    ArgumentCount { function: String, min: usize, max: usize, got: usize },
+    ArgumentType { function: String, expected: &'static str, got: Value },
+    InvalidArgument { function: String, reason: &'static str },
+    IndexOutOfRange { index: i64, len: usize },
+    KeyNotFound { key: Value },
+    ConversionError { value: Value, target: &'static str },
+    Io { operation: &'static str, message: String },
+    DuplicateKeyword { name: String, line: usize },
+    UnexpectedKeyword { function: String, name: String },
+    UnknownException { name: String, line: usize },
+    BareRaise { line: usize },
+    Raised { class: String, message: String },
+    InvalidAnnotation { name: String, reason: &'static str, line: usize },
+    DuplicateName { name: String, line: usize },
+    AssignToConstant { name: String, line: usize },
+    InvalidEnumValue { name: String, line: usize },
+    UnknownEnumMember { enum_name: String, name: String, line: usize },
+    UnknownType { name: String, line: usize },
+    EnumTypeMismatch { name: String, enum_name: String, line: usize },
+    InvalidCaseValue { line: usize },
+    AnnotationMismatch { name: String, expected: String, got: Value },
+    InvalidPattern { reason: &'static str, line: usize },
+    ReturnOutsideFunction { line: usize },
+    ParameterType { function: String, name: String, expected: String, got: Value },
+    ReturnType { function: String, expected: String, got: Value },
+    RecursionLimit { depth: usize },
+    NotCallable { value: Value },
+    SignalConnection { signal: String, reason: String },
+    UnknownMember { class: String, name: String },
+    FieldType { class: String, name: String, expected: String, got: Value },
+    InvalidSuper { line: usize },
+    ReturnInConstructor { line: usize },
+    NoConstructor { class: String, args: Vec<Value> },
+    StaticConstructor { class: String },
+    AccessViolation { class: String, name: String },
+    PrivateAccess { name: String, line: usize },
+    ModuleNotFound { path: String, line: usize },
+    /// Modules importing each other, as the chain of files from the first import of the module.
+    CircularImport { chain: Vec<String> },
+    /// An error tokenizing, parsing or compiling the imported module at `path`.
+    ModuleError { path: String, error: Box<PalladError> },
+    UnknownImport { module: String, name: String },
+    ExcludedModule { path: String, line: usize },
+    /// An `exclude` of a module, or folder, an earlier `import` in the same file already loads.
+    ExcludeAfterImport { path: String, line: usize },
+    PrivateName { module: String, name: String },
+    InvalidClassHeader { reason: &'static str, line: usize },
 }
 
 impl std::fmt::Display for PalladError {
@@ -26,8 +88,8 @@ impl std::fmt::Display for PalladError {
     ///
     /// # Examples
     ///
-    /// ```no_run
-    /// # use crate::error::PalladError;
+    /// ```
+    /// # use pallad::error::PalladError;
     ///
     /// let e = PalladError::UnexpectedToken { got: "}".into(), expected: "identifier".into(), line: 3 };
     /// assert_eq!(format!("{}", e), "Line 3: Expected identifier, got }");
@@ -53,10 +115,22 @@ impl std::fmt::Display for PalladError {
                 write!(f, "Stack underflow: {}", operation),
             PalladError::TypeMismatch { left, right, operation } =>
                 write!(f, "Cannot {} '{}' and '{}'", operation, left, right),
+            PalladError::InvalidOperand { value, operation } =>
+                write!(f, "Cannot {} '{}'", operation, value),
             PalladError::DivisionByZero { operation } =>
                 write!(f, "Division by zero at {} operation is not valid", operation),
+            PalladError::IntOverflow { operation } =>
+                write!(f, "Integer overflow at {} operation", operation),
             PalladError::IntDivOverflow =>
                 write!(f, "Integer division overflow"),
+            PalladError::PowOverflow =>
+                write!(f, "Integer power overflow"),
+            PalladError::DecimalOverflow { operation } =>
+                write!(f, "Decimal overflow at {} operation", operation),
+            PalladError::ShiftOverflow =>
+                write!(f, "Integer shift overflow"),
+            PalladError::NegativeShift =>
+                write!(f, "Shift count can't be negative"),
             PalladError::RepeatOverflow =>
                 write!(f, "String repeat overflow"),
             PalladError::NegativeRepeat =>
@@ -65,8 +139,146 @@ impl std::fmt::Display for PalladError {
                 write!(f, "Line {}: Invalid escaped character: {}", line, char),
             PalladError::UnterminatedString { line } =>
                 write!(f, "Line {}: Unterminated string", line),
+            PalladError::InconsistentIndent { line } =>
+                write!(f, "Line {}: Indentation doesn't match any enclosing block", line),
+            PalladError::InvalidFString { reason, line } =>
+                write!(f, "Line {}: Invalid f-string: {}", line, reason),
+            PalladError::InvalidFormatSpec { spec, line } =>
+                write!(f, "Line {}: Unsupported format spec '{}'", line, spec),
+            PalladError::UnsupportedFormatSpec { spec } =>
+                write!(f, "Unsupported format spec '{}'", spec),
+            PalladError::FormatMismatch { value, spec } =>
+                write!(f, "Cannot format '{}' with format spec '{}'", value, spec),
+            PalladError::UnknownMethod { type_name, name } =>
+                write!(f, "Type '{}' has no method '{}'", type_name, name),
+            PalladError::UnknownAttribute { type_name, name } =>
+                write!(f, "Type '{}' has no attribute '{}'", type_name, name),
+            PalladError::ArgumentCount { function, min, max, got } if min == max =>
+                write!(f, "'{}' takes {} argument(s), got {}", function, min, got),
+            PalladError::ArgumentCount { function, min, max, got } =>
+                write!(f, "'{}' takes {} to {} arguments, got {}", function, min, max, got),
+            PalladError::ArgumentType { function, expected, got } =>
+                write!(f, "'{}' expected {}, got '{}'", function, expected, got),
+            PalladError::InvalidArgument { function, reason } =>
+                write!(f, "Invalid argument to '{}': {}", function, reason),
+            PalladError::IndexOutOfRange { index, len } =>
+                write!(f, "Index {} out of range for length {}", index, len),
+            PalladError::KeyNotFound { key } =>
+                write!(f, "Key {} not found", key.repr()),
+            PalladError::DuplicateKeyword { name, line } =>
+                write!(f, "Line {}: Keyword argument '{}' repeated", line, name),
+            PalladError::UnexpectedKeyword { function, name } =>
+                write!(f, "'{}' got an unexpected keyword argument '{}'", function, name),
+            PalladError::UnknownException { name, line } =>
+                write!(f, "Line {}: Unknown exception class '{}'", line, name),
+            PalladError::BareRaise { line } =>
+                write!(f, "Line {}: 'raise' without a value is only allowed in an 'except' block", line),
+            PalladError::InvalidAnnotation { name, reason, line } =>
+                write!(f, "Line {}: '@{}' {}", line, name, reason),
+            PalladError::DuplicateName { name, line } =>
+                write!(f, "Line {}: '{}' is already defined", line, name),
+            PalladError::AssignToConstant { name, line } =>
+                write!(f, "Line {}: Cannot assign to enum or enum member '{}'", line, name),
+            PalladError::InvalidEnumValue { name, line } =>
+                write!(f, "Line {}: Value of enum member '{}' must be an integer literal", line, name),
+            PalladError::UnknownEnumMember { enum_name, name, line } =>
+                write!(f, "Line {}: Enum '{}' has no member '{}'", line, enum_name, name),
+            PalladError::UnknownType { name, line } =>
+                write!(f, "Line {}: Unknown type '{}'", line, name),
+            PalladError::EnumTypeMismatch { name, enum_name, line } =>
+                write!(f, "Line {}: '{}' must be given a member of enum '{}'", line, name, enum_name),
+            PalladError::InvalidCaseValue { line } =>
+                write!(f, "Line {}: Case values must be integer or string constants, or enum members", line),
+            PalladError::AnnotationMismatch { name, expected, got } =>
+                write!(f, "Variable '{}' is declared as {} but was given '{}'", name, expected, got),
+            PalladError::InvalidPattern { reason, line } =>
+                write!(f, "Line {}: Invalid pattern: {}", line, reason),
+            PalladError::ReturnOutsideFunction { line } =>
+                write!(f, "Line {}: 'return' is only allowed in a function", line),
+            PalladError::ParameterType { function, name, expected, got } =>
+                write!(f, "Parameter '{}' of '{}' expects {}, got '{}'", name, function, expected, got),
+            PalladError::ReturnType { function, expected, got } =>
+                write!(f, "'{}' must return {}, got '{}'", function, expected, got),
+            PalladError::RecursionLimit { depth } =>
+                write!(f, "Maximum call depth of {} exceeded", depth),
+            PalladError::NotCallable { value } =>
+                write!(f, "Value of type '{}' is not callable", value),
+            PalladError::SignalConnection { signal, reason } =>
+                write!(f, "Signal '{}': {}", signal, reason),
+            PalladError::UnknownMember { class, name } =>
+                write!(f, "Class '{}' has no field or method '{}'", class, name),
+            PalladError::FieldType { class, name, expected, got } =>
+                write!(f, "Field '{}' of '{}' is declared as {} but was given '{}'", name, class, expected, got),
+            PalladError::InvalidSuper { line } =>
+                write!(f, "Line {}: 'super' is only allowed in methods of a class that extends another", line),
+            PalladError::ReturnInConstructor { line } =>
+                write!(f, "Line {}: 'return' in a constructor can't return a value", line),
+            PalladError::AccessViolation { class, name } =>
+                write!(f, "'{}' is private to class '{}'", name, class),
+            PalladError::PrivateAccess { name, line } =>
+                write!(f, "Line {}: '{}' is private and can only be used inside its class or module", line, name),
+            PalladError::ModuleNotFound { path, line } =>
+                write!(f, "Line {}: Module '{}' not found", line, path),
+            PalladError::CircularImport { chain } =>
+                write!(f, "Circular import: {}", chain.join(" -> ")),
+            PalladError::ModuleError { path, error } =>
+                write!(f, "In module '{}': {}", path, error),
+            PalladError::UnknownImport { module, name } =>
+                write!(f, "Module '{}' has no '{}'", module, name),
+            PalladError::ExcludedModule { path, line } =>
+                write!(f, "Line {}: Module '{}' is excluded", line, path),
+            PalladError::ExcludeAfterImport { path, line } =>
+                write!(f, "Line {}: '{}' is excluded after it is imported; exclude it before the import", line, path),
+            PalladError::PrivateName { module, name } =>
+                write!(f, "'{}' is private to module '{}'", name, module),
+            PalladError::InvalidClassHeader { reason, line } =>
+                write!(f, "Line {}: Class header {}", line, reason),
+            PalladError::StaticConstructor { class } =>
+                write!(f, "Static constructor of '{}' didn't return true, so it can't be created", class),
+            PalladError::NoConstructor { class, args } => {
+                let types: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "No constructor of '{}' takes ({})", class, types.join(", "))
+            }
+            PalladError::Raised { class, message } if message.is_empty() =>
+                write!(f, "{}", class),
+            PalladError::Raised { class, message } =>
+                write!(f, "{}: {}", class, message),
+            PalladError::Io { operation, message } =>
+                write!(f, "I/O error during {}: {}", operation, message),
+            PalladError::ConversionError { value: Value::None, target } =>
+                write!(f, "Cannot convert none to {}", target),
+            PalladError::ConversionError { value, target } =>
+                write!(f, "Cannot convert {} {} to {}", value, value.repr(), target),
         }
     }
 }
 
-impl std::error::Error for PalladError {}
\ No newline at end of file
+impl std::error::Error for PalladError {}
+
+/// A problem the compiler reports without stopping, e.g. a `case` that can never run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    DuplicateCase { value: String, line: usize },
+    /// A `match` over members of a named enum without a branch for every member.
+    NonExhaustiveMatch { enum_name: String, missing: Vec<String>, line: usize },
+    /// A class name that isn't PascalCase (`User`, `HttpClient`), e.g. one taken from a file name.
+    ClassName { name: String, line: usize },
+    /// An unguarded `match` arm matching every value, such as `case _:` or a capture, before
+    /// other arms or a `default` block.
+    UnreachableArms { line: usize },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::DuplicateCase { value, line } =>
+                write!(f, "Line {}: Duplicate case value {} is never reached", line, value),
+            Warning::NonExhaustiveMatch { enum_name, missing, line } =>
+                write!(f, "Line {}: Match over enum '{}' doesn't cover {}", line, enum_name, missing.join(", ")),
+            Warning::ClassName { name, line } =>
+                write!(f, "Line {}: Class name '{}' should be PascalCase", line, name),
+            Warning::UnreachableArms { line } =>
+                write!(f, "Line {}: Pattern matches every value, so the match's later branches are never reached", line),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::PalladError;
+use crate::methods::check_args;
+use crate::value::Value;
+
+/// The exception classes and their parents. `except` clauses catch an exception when it is an
+/// instance of one of their classes or a subclass of one.
+const CLASSES: &[(&str, Option<&str>)] = &[
+    ("Error", None),
+    ("RuntimeError", Some("Error")),
+    ("OverflowError", Some("RuntimeError")),
+    ("TypeError", Some("Error")),
+    ("ValueError", Some("Error")),
+    ("NameError", Some("Error")),
+    ("IndexError", Some("Error")),
+    ("KeyError", Some("Error")),
+    ("IOError", Some("Error")),
+    ("AssertionError", Some("Error")),
+    ("AccessError", Some("Error")),
+    ("ImportError", Some("Error")),
+];
+
+/// A raised or caught exception: its class and message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exception {
+    pub class: String,
+    pub message: String,
+}
+
+impl Exception {
+    /// The catchable form of a runtime error; errors from `raise` keep their class.
+    pub fn from_error(error: PalladError) -> Self {
+        match error {
+            PalladError::Raised { class, message } => Self { class, message },
+            other => Self { class: class_of(&other).to_string(), message: other.to_string() },
+        }
+    }
+
+    /// The error reported when the exception isn't caught.
+    pub fn into_error(self) -> PalladError {
+        PalladError::Raised { class: self.class, message: self.message }
+    }
+
+    /// Whether the exception's class is `class` or one of its subclasses.
+    pub fn is_instance(&self, class: &str) -> bool {
+        let mut current = Some(self.class.as_str());
+        while let Some(name) = current {
+            if name == class {
+                return true;
+            }
+            current = parent(name);
+        }
+        false
+    }
+}
+
+/// Whether `name` is an exception class.
+pub fn is_class(name: &str) -> bool {
+    CLASSES.iter().any(|(class, _)| *class == name)
+}
+
+fn parent(class: &str) -> Option<&'static str> {
+    CLASSES.iter().find(|(name, _)| *name == class).and_then(|(_, parent)| *parent)
+}
+
+/// `ValueError("message")` and the like: creates an exception of the class `class`, ready for
+/// `raise`. The message defaults to an empty string.
+pub fn construct(class: &str, args: &[Value]) -> Result<Value, PalladError> {
+    check_args(class, args, 0, 1)?;
+    let message = args.first().map(Value::stringify).unwrap_or_default();
+    Ok(Value::Exception(Exception { class: class.to_string(), message }))
+}
+
+/// The class a runtime error is caught as.
+fn class_of(error: &PalladError) -> &'static str {
+    match error {
+        PalladError::IntOverflow { .. }
+        | PalladError::IntDivOverflow
+        | PalladError::PowOverflow
+        | PalladError::DecimalOverflow { .. }
+        | PalladError::ShiftOverflow
+        | PalladError::RepeatOverflow => "OverflowError",
+        PalladError::TypeMismatch { .. }
+        | PalladError::InvalidOperand { .. }
+        | PalladError::FormatMismatch { .. }
+        | PalladError::UnsupportedFormatSpec { .. }
+        | PalladError::UnknownMethod { .. }
+        | PalladError::UnknownAttribute { .. }
+        | PalladError::ArgumentCount { .. }
+        | PalladError::ArgumentType { .. }
+        | PalladError::ParameterType { .. }
+        | PalladError::ReturnType { .. }
+        | PalladError::NotCallable { .. }
+        | PalladError::UnknownMember { .. }
+        | PalladError::FieldType { .. }
+        | PalladError::NoConstructor { .. }
+        | PalladError::UnexpectedKeyword { .. } => "TypeError",
+        PalladError::NegativeShift
+        | PalladError::NegativeRepeat
+        | PalladError::InvalidArgument { .. }
+        | PalladError::SignalConnection { .. }
+        | PalladError::ConversionError { .. } => "ValueError",
+        PalladError::UndefinedVariable { .. } | PalladError::UnknownBuiltin { .. } => "NameError",
+        PalladError::IndexOutOfRange { .. } => "IndexError",
+        PalladError::KeyNotFound { .. } => "KeyError",
+        PalladError::AccessViolation { .. } | PalladError::PrivateName { .. } => "AccessError",
+        PalladError::ModuleNotFound { .. }
+        | PalladError::CircularImport { .. }
+        | PalladError::ModuleError { .. }
+        | PalladError::UnknownImport { .. }
+        | PalladError::ExcludedModule { .. } => "ImportError",
+        PalladError::Io { .. } => "IOError",
+        _ => "RuntimeError",
+    }
+}
@@ -0,0 +1,192 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::rc::Rc;
+use crate::error::PalladError;
+use crate::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+    Append,
+}
+
+/// A file opened by `open(path, mode)`.
+///
+/// Clones refer to the same open file, so closing one (e.g. at the end of a `with` block)
+/// closes them all. Text files read and write strings; binary files (`b` modes) read and write
+/// arrays of byte values.
+#[derive(Clone)]
+pub struct FileRef(Rc<RefCell<FileHandle>>);
+
+struct FileHandle {
+    path: String,
+    mode: String,
+    access: Access,
+    binary: bool,
+    /// `None` once closed.
+    file: Option<File>,
+}
+
+impl FileRef {
+    /// Opens `path` with a mode of `r` (read), `w` (write, truncating), or `a` (append), each
+    /// optionally followed by `b` for binary.
+    pub fn open(path: &str, mode: &str) -> Result<FileRef, PalladError> {
+        let (access, binary) = match mode {
+            "r" => (Access::Read, false),
+            "w" => (Access::Write, false),
+            "a" => (Access::Append, false),
+            "rb" => (Access::Read, true),
+            "wb" => (Access::Write, true),
+            "ab" => (Access::Append, true),
+            _ => return Err(PalladError::InvalidArgument {
+                function: "open".to_string(),
+                reason: "mode must be 'r', 'w' or 'a', optionally followed by 'b'",
+            }),
+        };
+        let mut options = OpenOptions::new();
+        match access {
+            Access::Read => options.read(true),
+            Access::Write => options.write(true).create(true).truncate(true),
+            Access::Append => options.append(true).create(true),
+        };
+        let file = options.open(path).map_err(|e| PalladError::Io {
+            operation: "open",
+            message: format!("{}: {}", path, e),
+        })?;
+
+        Ok(FileRef(Rc::new(RefCell::new(FileHandle {
+            path: path.to_string(),
+            mode: mode.to_string(),
+            access,
+            binary,
+            file: Some(file),
+        }))))
+    }
+
+    pub fn path(&self) -> String {
+        self.0.borrow().path.clone()
+    }
+
+    pub fn mode(&self) -> String {
+        self.0.borrow().mode.clone()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.0.borrow().file.is_none()
+    }
+
+    /// Flushes and closes the file; closing an already closed file does nothing.
+    pub fn close(&self) -> Result<(), PalladError> {
+        let mut handle = self.0.borrow_mut();
+        match handle.file.take() {
+            Some(mut file) => file.flush().map_err(|e| handle.error("close", e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the rest of the file: a string in text mode, an array of bytes in binary mode.
+    pub fn read(&self) -> Result<Value, PalladError> {
+        let mut handle = self.0.borrow_mut();
+        let mut bytes = vec![];
+        handle.readable("read")?
+            .read_to_end(&mut bytes)
+            .map_err(|e| handle.error("read", e))?;
+        if handle.binary {
+            return Ok(Value::Array(bytes.into_iter().map(|b| Value::Int(b as i64)).collect()));
+        }
+        String::from_utf8(bytes).map(Value::Str).map_err(|_| PalladError::Io {
+            operation: "read",
+            message: format!("{}: file is not valid UTF-8 text, open it with a binary mode", handle.path),
+        })
+    }
+
+    /// Reads the rest of a text file as an array of lines, without line endings.
+    pub fn lines(&self) -> Result<Value, PalladError> {
+        if self.0.borrow().binary {
+            return Err(self.0.borrow().message("read", "lines need a text mode"));
+        }
+        let Value::Str(text) = self.read()? else {
+            return Ok(Value::Array(vec![]));
+        };
+        Ok(Value::Array(text.lines().map(|line| Value::Str(line.to_string())).collect()))
+    }
+
+    /// Writes a string (text mode) or an array of bytes (binary mode) and returns how many
+    /// characters or bytes were written.
+    pub fn write(&self, value: &Value) -> Result<Value, PalladError> {
+        let mut handle = self.0.borrow_mut();
+        let (bytes, count) = match (value, handle.binary) {
+            (Value::Str(s), false) => (s.as_bytes().to_vec(), s.chars().count()),
+            (Value::Array(items), true) => {
+                let bytes = items.iter()
+                    .map(|item| match item {
+                        Value::Int(n) => u8::try_from(*n).ok(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| PalladError::ArgumentType {
+                        function: "write".to_string(),
+                        expected: "an array of bytes (0 to 255)",
+                        got: value.clone(),
+                    })?;
+                let count = bytes.len();
+                (bytes, count)
+            }
+            (other, binary) => return Err(PalladError::ArgumentType {
+                function: "write".to_string(),
+                expected: if binary { "an array of bytes (0 to 255)" } else { "a string" },
+                got: other.clone(),
+            }),
+        };
+        handle.writable("write")?
+            .write_all(&bytes)
+            .map_err(|e| handle.error("write", e))?;
+        Ok(Value::Int(count as i64))
+    }
+}
+
+impl FileHandle {
+    fn readable(&mut self, operation: &'static str) -> Result<&mut File, PalladError> {
+        if self.access != Access::Read {
+            return Err(self.message(operation, "file is not open for reading"));
+        }
+        self.open_file(operation)
+    }
+
+    fn writable(&mut self, operation: &'static str) -> Result<&mut File, PalladError> {
+        if self.access == Access::Read {
+            return Err(self.message(operation, "file is not open for writing"));
+        }
+        self.open_file(operation)
+    }
+
+    fn open_file(&mut self, operation: &'static str) -> Result<&mut File, PalladError> {
+        let error = self.message(operation, "file is closed");
+        self.file.as_mut().ok_or(error)
+    }
+
+    fn message(&self, operation: &'static str, message: &str) -> PalladError {
+        PalladError::Io { operation, message: format!("{}: {}", self.path, message) }
+    }
+
+    fn error(&self, operation: &'static str, error: std::io::Error) -> PalladError {
+        self.message(operation, &error.to_string())
+    }
+}
+
+impl PartialEq for FileRef {
+    /// Files are equal only when they are the same open file.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for FileRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let handle = self.0.borrow();
+        write!(f, "FileRef({:?}, {:?})", handle.path, handle.mode)
+    }
+}
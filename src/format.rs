@@ -0,0 +1,373 @@
+use crate::bigint::BigInt;
+use crate::decimal::RoundingMode;
+use crate::error::PalladError;
+use crate::value::Value;
+
+/// Parsed f-string format spec, `[[fill]align][sign][#][0][width][,|_][.precision][type]`.
+///
+/// Follows Python's format mini-language, e.g. `{x:.2f}`, `{name:>10}`, or `{n:08,d}`. Supported
+/// types are `s`, `d`, `b`, `o`, `x`, `X`, `f`, `F`, `e`, `E`, `g`, `G`, and `%`. A float with a
+/// precision and no type is written like `g`, but keeps a digit after the point (`{1.5:.2}` is
+/// `1.5`). The `#` flag prefixes `0b`, `0o` or `0x` to integers, and keeps the point and
+/// trailing zeros of floats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatSpec {
+    text: String,
+    fill: char,
+    align: Option<char>,
+    sign: Option<char>,
+    alternate: bool,
+    width: usize,
+    grouping: Option<char>,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parses the text after `:` in an f-string replacement field, or `None` if it is malformed.
+    pub fn parse(text: &str) -> Option<FormatSpec> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut pos = 0;
+        let mut spec = FormatSpec {
+            text: text.to_string(),
+            fill: ' ',
+            align: None,
+            sign: None,
+            alternate: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            kind: None,
+        };
+        let is_align = |c: &char| matches!(c, '<' | '>' | '^' | '=');
+
+        if chars.get(1).is_some_and(is_align) {
+            spec.fill = chars[0];
+            spec.align = Some(chars[1]);
+            pos = 2;
+        } else if chars.first().is_some_and(is_align) {
+            spec.align = Some(chars[0]);
+            pos = 1;
+        }
+        if let Some(&sign @ ('+' | '-' | ' ')) = chars.get(pos) {
+            spec.sign = Some(sign);
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'#') {
+            spec.alternate = true;
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'0') {
+            // Zero padding goes between the sign and the digits
+            if spec.align.is_none() {
+                spec.fill = '0';
+                spec.align = Some('=');
+            }
+            pos += 1;
+        }
+        let width = read_number(&chars, &mut pos);
+        spec.width = width.unwrap_or(0);
+        if let Some(&grouping @ (',' | '_')) = chars.get(pos) {
+            spec.grouping = Some(grouping);
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'.') {
+            pos += 1;
+            spec.precision = Some(read_number(&chars, &mut pos)?);
+        }
+        if let Some(&kind) = chars.get(pos) {
+            if !"sdboxXfFeEgG%".contains(kind) {
+                return None;
+            }
+            spec.kind = Some(kind);
+            pos += 1;
+        }
+
+        (pos == chars.len()).then_some(spec)
+    }
+
+    /// Formats `value` with this spec, or `None` when the spec doesn't apply to the value's type
+    /// (e.g. `{name:d}` for a string).
+    ///
+    /// `rounding` is used for decimals formatted with a precision.
+    pub fn apply(&self, value: &Value, rounding: RoundingMode) -> Option<String> {
+        let numeric = !matches!(value, Value::Str(_) | Value::None | Value::Bool(_) | Value::Array(_) | Value::Tuple(_) | Value::Dict(_) | Value::File(_) | Value::Exception(_) | Value::Color(_) | Value::Namespace(_) | Value::Function(_) | Value::Signal(_) | Value::Class(_) | Value::Object(_) | Value::Module(_));
+        let text = match (value, self.kind) {
+            (Value::Str(_) | Value::None | Value::Bool(_) | Value::Array(_) | Value::Tuple(_) | Value::Dict(_) | Value::File(_) | Value::Exception(_) | Value::Color(_) | Value::Namespace(_) | Value::Function(_) | Value::Signal(_) | Value::Class(_) | Value::Object(_) | Value::Module(_), None | Some('s')) => {
+                if self.sign.is_some() || self.grouping.is_some() || self.alternate {
+                    return None;
+                }
+                let text = value.stringify();
+                match self.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text,
+                }
+            }
+            (Value::Int(n), None | Some('d')) if self.precision.is_none() => n.to_string(),
+            (Value::BigInt(n), None | Some('d')) if self.precision.is_none() => n.to_string(),
+            (Value::Int(n), Some(kind @ ('b' | 'o' | 'x' | 'X'))) => to_radix(&BigInt::from(*n), kind),
+            (Value::BigInt(n), Some(kind @ ('b' | 'o' | 'x' | 'X'))) => to_radix(n, kind),
+            (Value::Decimal(d), None | Some('f' | 'F')) => match self.precision {
+                Some(precision) => d.with_scale(precision as u32, rounding).to_string(),
+                None => d.to_string(),
+            },
+            (Value::Float(_), None) if self.precision.is_none() => value.stringify(),
+            (Value::Int(_) | Value::BigInt(_) | Value::Float(_), kind) => {
+                let f = match value {
+                    Value::Int(n) => *n as f64,
+                    Value::BigInt(n) => n.to_f64(),
+                    Value::Float(f) => *f,
+                    _ => return None,
+                };
+                match kind {
+                    Some(kind) => self.format_float(f, kind)?,
+                    // Only floats have a default presentation taking a precision
+                    None if matches!(value, Value::Float(_)) => self.format_general(f, None),
+                    None => return None,
+                }
+            }
+            _ => return None,
+        };
+        if !numeric {
+            return Some(self.pad(String::new(), text, '<'));
+        }
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits.to_string()),
+            None => (false, text),
+        };
+        let sign = match (negative, self.sign) {
+            (true, _) => "-",
+            (false, Some('+')) => "+",
+            (false, Some(' ')) => " ",
+            _ => "",
+        };
+        // The radix prefix goes before any zero padding, like the sign
+        let prefix = match self.kind {
+            Some(kind @ ('b' | 'o' | 'x' | 'X')) if self.alternate => format!("0{}", kind),
+            _ => String::new(),
+        };
+        let digits = match self.grouping {
+            Some(separator) => {
+                if matches!(self.kind, Some('b' | 'o' | 'x' | 'X')) {
+                    return None;
+                }
+                group_digits(&digits, separator)
+            }
+            None => digits,
+        };
+        Some(self.pad(format!("{}{}", sign, prefix), digits, '>'))
+    }
+
+    /// Formats a float for the `f`, `e`, `g` and `%` presentation types (`None` for others).
+    fn format_float(&self, f: f64, kind: char) -> Option<String> {
+        let precision = self.precision.unwrap_or(6);
+        // `#` keeps the point even when no digits follow it
+        let point = |text: String| if self.alternate && f.is_finite() && !text.contains('.') { text + "." } else { text };
+        Some(match kind {
+            'f' | 'F' => point(format!("{:.*}", precision, f)),
+            '%' => point(format!("{:.*}", precision, f * 100.0)) + "%",
+            'e' | 'E' => {
+                let text = scientific(f, precision, self.alternate);
+                if kind == 'E' { text.to_uppercase() } else { text }
+            }
+            'g' | 'G' => {
+                let text = self.format_general(f, Some(kind));
+                if kind == 'G' { text.to_uppercase() } else { text }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Formats a float with the precision as significant digits (6 by default): in scientific
+    /// notation when its exponent is below -4 or not below the precision, otherwise in fixed
+    /// point. Trailing zeros are dropped unless the `#` flag is set; without a type (`kind` is
+    /// `None`), fixed point keeps one digit after the point.
+    fn format_general(&self, f: f64, kind: Option<char>) -> String {
+        if !f.is_finite() {
+            return Value::Float(f).stringify();
+        }
+        let precision = self.precision.unwrap_or(6).max(1);
+        // The exponent after rounding to the precision, so 9.99 to 2 digits is 1.0e+01
+        let exp: i32 = format!("{:.*e}", precision - 1, f).split_once('e')
+            .and_then(|(_, exp)| exp.parse().ok())
+            .unwrap_or(0);
+        if exp < -4 || exp >= precision as i32 {
+            let text = scientific(f, precision - 1, self.alternate);
+            if self.alternate {
+                return text;
+            }
+            return match text.split_once('e') {
+                Some((mantissa, exp)) => format!("{}e{}", trim_fraction(mantissa, false), exp),
+                None => text,
+            };
+        }
+        let text = format!("{:.*}", (precision as i32 - 1 - exp) as usize, f);
+        match (self.alternate, text.contains('.')) {
+            (true, false) => text + ".",
+            (true, true) => text,
+            (false, _) => trim_fraction(&text, kind.is_none()),
+        }
+    }
+
+    /// Pads `sign` + `body` to the spec width using its fill and alignment.
+    fn pad(&self, sign: String, body: String, default_align: char) -> String {
+        let len = sign.chars().count() + body.chars().count();
+        let padding = self.width.saturating_sub(len);
+        let fill = |n: usize| self.fill.to_string().repeat(n);
+        match self.align.unwrap_or(default_align) {
+            '<' => format!("{}{}{}", sign, body, fill(padding)),
+            '^' => format!("{}{}{}{}", fill(padding / 2), sign, body, fill(padding - padding / 2)),
+            '=' => format!("{}{}{}", sign, fill(padding), body),
+            _ => format!("{}{}{}", fill(padding), sign, body),
+        }
+    }
+}
+
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Implements `template.format(args...)`: replaces `{}`, `{index}`, `{:spec}` and `{index:spec}`
+/// fields with the formatted arguments. `{{` and `}}` stand for literal braces.
+///
+/// Automatic (`{}`) and manual (`{0}`) numbering can't be mixed in one template.
+pub fn format_template(template: &str, args: &[Value], rounding: RoundingMode) -> Result<String, PalladError> {
+    let invalid = |reason| PalladError::InvalidArgument { function: "format".to_string(), reason };
+    let mut out = String::new();
+    let mut chars = template.chars();
+    let mut next_auto = 0;
+    let mut manual = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => return Err(invalid("single '}' in template")),
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or(invalid("unclosed '{' in template"))?;
+                let field = &rest[..end];
+                chars = rest[end + 1..].chars();
+
+                let (index, spec) = field.split_once(':').unwrap_or((field, ""));
+                let index = if index.is_empty() {
+                    if manual {
+                        return Err(invalid("cannot mix '{}' and '{index}' fields"));
+                    }
+                    next_auto += 1;
+                    next_auto - 1
+                } else {
+                    if next_auto > 0 {
+                        return Err(invalid("cannot mix '{}' and '{index}' fields"));
+                    }
+                    manual = true;
+                    index.parse().map_err(|_| invalid("field index must be a number"))?
+                };
+                let value = args.get(index).ok_or(PalladError::IndexOutOfRange {
+                    index: index as i64,
+                    len: args.len(),
+                })?;
+                if spec.is_empty() {
+                    out.push_str(&value.stringify());
+                    continue;
+                }
+                let spec = FormatSpec::parse(spec)
+                    .ok_or_else(|| PalladError::UnsupportedFormatSpec { spec: spec.to_string() })?;
+                let text = spec.apply(value, rounding).ok_or_else(|| PalladError::FormatMismatch {
+                    value: value.clone(),
+                    spec: spec.to_string(),
+                })?;
+                out.push_str(&text);
+            }
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Writes `f` in scientific notation with `precision` fractional digits, and a signed exponent of
+/// at least two digits (`1.5e+03`, where Rust writes `1.5e3`). `point` keeps the point when no
+/// digits follow it.
+fn scientific(f: f64, precision: usize, point: bool) -> String {
+    let text = format!("{:.*e}", precision, f);
+    match text.split_once('e') {
+        Some((mantissa, exp)) => {
+            let exp: i32 = exp.parse().unwrap_or(0);
+            let mantissa = if point && !mantissa.contains('.') { format!("{}.", mantissa) } else { mantissa.to_string() };
+            format!("{}e{}{:02}", mantissa, if exp < 0 { '-' } else { '+' }, exp.abs())
+        }
+        None => text,
+    }
+}
+
+/// Drops the trailing zeros of the fractional part of `number`, and the point if no digits are
+/// left, unless `keep_digit` keeps one digit (`1.0`).
+fn trim_fraction(number: &str, keep_digit: bool) -> String {
+    if !number.contains('.') {
+        return if keep_digit { format!("{}.0", number) } else { number.to_string() };
+    }
+    let trimmed = number.trim_end_matches('0');
+    match trimmed.strip_suffix('.') {
+        Some(integer) if keep_digit => format!("{}.0", integer),
+        Some(integer) => integer.to_string(),
+        None => trimmed.to_string(),
+    }
+}
+
+fn read_number(chars: &[char], pos: &mut usize) -> Option<usize> {
+    let start = *pos;
+    while chars.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+        *pos += 1;
+    }
+    chars[start..*pos].iter().collect::<String>().parse().ok()
+}
+
+/// Writes `n` in base 2, 8 or 16 (`kind` is `b`, `o`, `x`, or `X`), with a leading `-` if negative.
+fn to_radix(n: &BigInt, kind: char) -> String {
+    let radix = match kind {
+        'b' => 2,
+        'o' => 8,
+        _ => 16,
+    };
+    let mut digits = vec![];
+    let mut rest = n.abs();
+    let divisor = BigInt::from(radix);
+    while !rest.is_zero() {
+        let Some((quotient, remainder)) = rest.div_rem(&divisor) else { break };
+        let digit = remainder.to_i64().unwrap_or(0) as u32;
+        digits.push(char::from_digit(digit, radix as u32).unwrap_or('0'));
+        rest = quotient;
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    let text: String = digits.iter().rev().collect();
+    let text = if kind == 'X' { text.to_uppercase() } else { text };
+    if n.is_negative() { format!("-{}", text) } else { text }
+}
+
+/// Inserts `separator` between groups of three digits in the integer part of `digits`.
+fn group_digits(digits: &str, separator: char) -> String {
+    let (integer, rest) = match digits.find(|c: char| !c.is_ascii_digit()) {
+        Some(end) => digits.split_at(end),
+        None => (digits, ""),
+    };
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    grouped + rest
+}
@@ -1,20 +1,276 @@
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
+use crate::format::FormatSpec;
+use crate::value::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// A `case` value a switch dispatches on: integers (including enum members) and strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CaseKey {
+    Int(i64),
+    Str(String),
+}
+
+impl fmt::Display for CaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseKey::Int(n) => write!(f, "{}", n),
+            CaseKey::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// A parameter of a function or signal: its name, and the `Value::type_name` its argument must
+/// have if it is annotated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub expected: Option<String>,
+}
+
+/// The type a variable is declared with, as checked when it is given a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    /// Values of this `Value::type_name`, or instances of this class.
+    Type(String),
+    /// The values of the members of the named enum `name`.
+    Enum { name: String, members: Vec<i64> },
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Annotation::Type(name) | Annotation::Enum { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A function declared with `func`: its parameters, its return type (a `Value::type_name`, or
+/// `void` for none), and where its body starts in the program. Methods (and class initializers)
+/// name the class they are declared in as their `owner`, and are called with `self`. `module` is
+/// the index of the module declaring the function, whose globals its body uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub returns: Option<String>,
+    pub entry: usize,
+    pub owner: Option<String>,
+    pub module: usize,
+}
+
+/// A `match` pattern with its literals and enum members resolved to values.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Wildcard,
+    /// Matches any value, binding it to `name`.
+    Bind(String),
+    /// Matches values of the same type that are `==` to this one.
+    Value(Value),
+    /// Matches numbers from `start` to `end` inclusive.
+    Range { start: Value, end: Value },
+    Tuple(Vec<Pattern>),
+    Array(Vec<Pattern>),
+    /// Matches dicts having each key, with a value that matches its pattern.
+    Dict(Vec<(Value, Pattern)>),
+    Or(Vec<Pattern>),
+}
+
 #[derive(Debug, Clone)]
 pub enum Instr {
     LoadNone,
+    LoadBool(bool),
     LoadInt(i64),
+    LoadBigInt(BigInt),
     LoadFloat(f64),
+    LoadDecimal(Decimal),
     LoadStr(String),
     LoadVar(String),
+    /// Pops a value into the variable `name`, declaring it in the running call or module.
     StoreVar(String),
+    /// Pops a value into the existing variable `name`: an arm binding or local, a field of `self`,
+    /// a static of the method's class, or a global of the module. Fails if there is none.
+    AssignVar(String),
+    /// Pops the given number of values and pushes them as an array, in order.
+    BuildArray(usize),
+    /// Pops the given number of values and pushes them as a tuple, in order.
+    BuildTuple(usize),
+    /// Pops the given number of key-value pairs (each key pushed before its value) and pushes
+    /// them as a dict.
+    BuildDict(usize),
+    /// Pops an index and a string, array, tuple or dict, and pushes the element at that index.
+    Index,
     Add,
     Sub,
     Mul,
     Div,
     IntDiv,
     Mod,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    BitNot,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Replaces the top of the stack with its string form, formatted with the spec if given.
+    FormatValue(Option<FormatSpec>),
+    /// Pops the given number of strings and pushes them joined in order.
+    Concat(usize),
+    /// Pops the keyword argument values (named by `kwargs`, in order), then `argc` positional
+    /// arguments, and calls the builtin.
     CallBuiltin {
         name: String,
         argc: usize,
+        kwargs: Vec<String>,
+    },
+    /// Replaces the top of the stack with its attribute `name`.
+    GetAttr(String),
+    /// Pops a value and an instance, and sets the instance's field `name` to the value.
+    SetAttr(String),
+    /// Registers the top of the stack as the resource of a `with` block, leaving it in place.
+    EnterWith,
+    /// Closes the resource of the innermost `with` block.
+    ExitWith,
+    /// Pops `argc` arguments and the receiver below them, and calls the receiver's native method.
+    /// `line` is the call's source line, which `log` records.
+    CallMethod {
+        name: String,
+        argc: usize,
+        line: usize,
     },
     Pop,
+    /// Pushes a copy of the top of the stack.
+    Dup,
+    /// Continues at the given instruction.
+    Jump(usize),
+    /// Pops a value and continues at the given instruction if it is truthy.
+    JumpIfTrue(usize),
+    /// Pops a value and continues at the given instruction if it is falsy.
+    JumpIfFalse(usize),
+    /// Pops an integer and continues at `targets[value - min]`, or at `default` when the value is
+    /// out of range or not an integer. Used for switches over dense integer cases.
+    JumpTable {
+        min: i64,
+        targets: Vec<usize>,
+        default: usize,
+    },
+    /// Pops a value and continues at its case's target, or at `default` when no case matches.
+    /// Used for switches over sparse integer or string cases.
+    JumpMap {
+        targets: HashMap<CaseKey, usize>,
+        default: usize,
+    },
+    /// Tests the top of the stack against `pattern`, leaving it in place. On a match, opens a scope
+    /// for the arm holding the values the pattern binds; otherwise continues at `target`.
+    MatchPattern {
+        pattern: Pattern,
+        target: usize,
+    },
+    /// Closes the scope of the innermost `match` arm, dropping its bindings.
+    ExitScope,
+    /// Pushes the function as a value.
+    MakeFunction(Rc<Function>),
+    /// Pops the return value, leaves the current function's frame (dropping what it left on the
+    /// stack, its `with` resources and exception handlers), and pushes the value for the caller.
+    Return,
+    /// Pops the static constructor if there is one, then `static_methods`, `constructors` and
+    /// `methods` functions, the static initializer, the class initializer and, if `parent` is set,
+    /// the parent class, and pushes the class. `statics` declares its static variables and
+    /// functions, which start as `none` and the functions respectively.
+    MakeClass {
+        name: String,
+        parent: bool,
+        fields: Vec<Param>,
+        statics: Vec<Param>,
+        methods: usize,
+        constructors: usize,
+        static_methods: usize,
+        static_constructor: bool,
+    },
+    /// Pops a class, runs its static initializer, and pushes the result (`none`).
+    InitStatics,
+    /// Runs the initializer of the parent of the current method's class on `self` and pushes its
+    /// result, or pushes `none` when the class has no parent. Starts each class initializer.
+    InitParent,
+    /// Runs the initializer of the current constructor's class on `self` and pushes its result,
+    /// before the constructor's body.
+    InitFields,
+    /// Pops `argc` arguments and `self` below them, and calls the method `name` of the parent of
+    /// the current method's class, for `super.name(args)`.
+    CallSuper {
+        name: String,
+        argc: usize,
+    },
+    /// Pushes a new signal with no connections.
+    MakeSignal {
+        name: String,
+        params: Vec<Param>,
+    },
+    /// Installs an exception handler: an error before the matching `PopTry` unwinds the stack to
+    /// its current height, closes the `with` resources opened since, pushes the exception, and
+    /// continues at the given instruction.
+    SetupTry(usize),
+    /// Removes the innermost exception handler.
+    PopTry,
+    /// Continues at `target` unless the exception on top of the stack is an instance of one of
+    /// `classes`; leaves the exception in place.
+    JumpIfNotException {
+        classes: Vec<String>,
+        target: usize,
+    },
+    /// Pops a value and stores it like `StoreVar`, for `var name: type` declarations: fails unless
+    /// it is `none` or a value `expected` accepts, which later assignments to the variable must be
+    /// too.
+    StoreTyped {
+        name: String,
+        expected: Annotation,
+    },
+    /// Pops an exception (or a message string, raised as a `RuntimeError`) and raises it.
+    Raise,
+    /// Loads the module or folder at `path`, relative to the running module's file, running each
+    /// module not loaded yet. Without `names`, stores the module as `alias` (or its file name), or
+    /// each module of a folder by its file name; with `names`, stores each name of the module (or
+    /// module of the folder) as its binding, and all public ones when `names` is empty.
+    Import {
+        path: String,
+        alias: Option<String>,
+        names: Option<Vec<(String, String)>>,
+        line: usize,
+    },
+    /// Makes the running module's later imports skip the modules at these paths.
+    Exclude(Vec<String>),
+}
+
+/// Shifts the jump targets and function entries of `program` by `offset`, for code compiled on
+/// its own and placed after the program already loaded.
+pub fn relocate(program: &mut [Instr], offset: usize) {
+    for instr in program {
+        match instr {
+            Instr::Jump(target)
+            | Instr::JumpIfTrue(target)
+            | Instr::JumpIfFalse(target)
+            | Instr::SetupTry(target)
+            | Instr::MatchPattern { target, .. }
+            | Instr::JumpIfNotException { target, .. } => *target += offset,
+            Instr::JumpTable { targets, default, .. } => {
+                targets.iter_mut().for_each(|target| *target += offset);
+                *default += offset;
+            }
+            Instr::JumpMap { targets, default } => {
+                targets.values_mut().for_each(|target| *target += offset);
+                *default += offset;
+            }
+            Instr::MakeFunction(function) => Rc::make_mut(function).entry += offset,
+            _ => {}
+        }
+    }
 }
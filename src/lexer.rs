@@ -1,192 +1,731 @@
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
 use crate::error::PalladError;
+use crate::format::FormatSpec;
+use crate::tags::{self, CommentTag};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Var,          // 'var'
     None,         // 'none'
+    True,         // 'true'
+    False,        // 'false'
     Print,        // 'print'
+    With,         // 'with'
+    As,           // 'as'
+    Try,          // 'try'
+    Except,       // 'except'
+    Else,         // 'else'
+    Finally,      // 'finally'
+    Raise,        // 'raise'
+    Assert,       // 'assert'
+    Enum,         // 'enum'
+    Switch,       // 'switch'
+    Match,        // 'match'
+    Case,         // 'case'
+    Default,      // 'default'
+    Pass,         // 'pass'
+    If,           // 'if'
+    Elif,         // 'elif'
+    Func,         // 'func'
+    Return,       // 'return'
+    Signal,       // 'signal'
+    Class,        // 'class'
+    Extends,      // 'extends'
+    Constructor,  // 'constructor'
+    Static,       // 'static'
+    Import,       // 'import'
+    From,         // 'from'
+    Exclude,      // 'exclude'
     Ident(String),// variable names
     Int(i64),     // int numbers
+    BigInt(BigInt), // int numbers too large for 64 bits
     Float(f64),   // float numbers
+    Decimal(Decimal), // decimal numbers, 'd' suffix
     Str(String),  // strings
+    FStr(Vec<FStrPart>), // f-strings
     Plus,         // '+'
     Minus,        // '-'
     Star,         // '*'
     Slash,        // '/'
     IntDiv,       // '//'
     Mod,          // '%'
+    Pow,          // '**'
+    Amp,          // '&'
+    Pipe,         // '|'
+    Caret,        // '^'
+    Tilde,        // '~'
+    Shl,          // '<<'
+    Shr,          // '>>'
     Eq,           // '='
+    EqEq,         // '=='
+    NotEq,        // '!='
+    Lt,           // '<'
+    Le,           // '<='
+    Gt,           // '>'
+    Ge,           // '>='
     LParen,       // '('
     RParen,       // ')'
+    LBracket,     // '['
+    RBracket,     // ']'
+    LBrace,       // '{'
+    RBrace,       // '}'
     Comma,        // ','
+    Dot,          // '.'
+    DotDot,       // '..'
+    Colon,        // ':'
+    At,           // '@'
+    Arrow,        // '->'
     Eol,          // end of line
+    Indent,       // start of a more indented block
+    Dedent,       // end of an indented block
+}
+
+/// The reserved words, and the tokens they are read as.
+const KEYWORDS: &[(&str, Token)] = &[
+    ("var", Token::Var),
+    ("none", Token::None),
+    ("true", Token::True),
+    ("false", Token::False),
+    ("print", Token::Print),
+    ("with", Token::With),
+    ("as", Token::As),
+    ("try", Token::Try),
+    ("except", Token::Except),
+    ("else", Token::Else),
+    ("finally", Token::Finally),
+    ("raise", Token::Raise),
+    ("assert", Token::Assert),
+    ("enum", Token::Enum),
+    ("switch", Token::Switch),
+    ("match", Token::Match),
+    ("case", Token::Case),
+    ("default", Token::Default),
+    ("pass", Token::Pass),
+    ("if", Token::If),
+    ("elif", Token::Elif),
+    ("func", Token::Func),
+    ("return", Token::Return),
+    ("signal", Token::Signal),
+    ("class", Token::Class),
+    ("extends", Token::Extends),
+    ("constructor", Token::Constructor),
+    ("static", Token::Static),
+    ("import", Token::Import),
+    ("from", Token::From),
+    ("exclude", Token::Exclude),
+];
+
+/// The word a keyword token is read from, for places where a keyword is allowed as a name, such
+/// as the attribute in `error.class`.
+pub fn keyword_text(token: &Token) -> Option<&'static str> {
+    KEYWORDS.iter().find(|(_, keyword)| keyword == token).map(|(text, _)| *text)
+}
+
+/// A piece of an f-string: literal text or an embedded `{expression:spec}` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FStrPart {
+    Lit(String),
+    Expr { tokens: Vec<Spanned>, spec: Option<FormatSpec> },
+}
+
+/// A token together with the source line and column (in characters, counted from 1) it starts
+/// at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Source text the parser doesn't see as tokens, kept for documentation and tooling: a comment,
+/// a string literal alone on its lines, a tag in a comment, or a region.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    /// The text without its `#` or quotes. Comments on consecutive lines are merged into one,
+    /// joined by spaces.
+    pub text: String,
+    pub line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriviaKind {
+    /// `#` comments on lines of their own.
+    Comment,
+    /// A `#` comment after code on its line.
+    TrailingComment,
+    /// A string literal as a statement of its own, the docstring of a declaration right below it.
+    Docstring,
+    /// A keyword such as `FIXME` in a comment, with the text following it.
+    Tag(CommentTag),
+    /// The lines from `#region Name` to its `#endregion`, with the region's name as the text. A
+    /// region never closed runs to the end of the source.
+    Region,
+    /// A `#endregion` closing no region, or a `#region` never closed, with what is wrong as the
+    /// text. It is only reported by tooling, and doesn't stop the code from running.
+    UnbalancedRegion,
 }
 
 /// Convert source text into a sequence of lexical tokens for the language.
 ///
-/// Processes the input line-by-line, stripping `#` comments and emitting tokens for
-/// identifiers, reserved keywords, integer and floating numeric literals, string literals
-/// (with escape sequences: \n, \t, \r, \", \\, \'), operators (`+`, `-`, `*`, `/`, `//`, 
-/// `%`, `=`), parentheses, commas, and an end-of-line `Eol` token after each non-empty line.
+/// Scans the input as one stream of characters, skipping `#` comments (outside string literals,
+/// so `"Issue #42"` keeps its `#`) and emitting tokens for identifiers, reserved keywords,
+/// integer (big when beyond 64 bits), floating and decimal (`d` suffix, e.g. `0.10d`) numeric
+/// literals, string literals (with escape sequences: \n, \t, \r, \", \\, \'), triple-quoted
+/// strings spanning lines (`"""..."""` or `'''...'''`), f-strings with embedded `{expr}` /
+/// `{expr:spec}` fields, operators (`+`, `-`, `*`, `/`, `//`, `%`, `**`, `&`, `|`, `^`, `~`,
+/// `<<`, `>>`, `=`, `==`, `!=`, `<`, `<=`, `>`, `>=`, `->`), parentheses, brackets, braces,
+/// commas, dots, `..`, colons, an end-of-line `Eol` token after each non-empty line, and
+/// `Indent` / `Dedent` tokens where a line's leading whitespace opens or closes a block.
 ///
 /// # Returns
 ///
-/// `Ok(Vec<Token>)` with the token stream on success, or `Err(PalladError)` if a lexical
-/// error is encountered (for example `InvalidNumber` for malformed numeric literals or
-/// `UnknownCharacter` for unexpected characters), with the error carrying the line number.
+/// `Ok(Vec<Spanned>)` with the token stream (each token tagged with the line and column it starts
+/// at) on success, or `Err(PalladError)` if a lexical error is encountered (for example
+/// `InvalidNumber` for malformed numeric literals, `UnknownCharacter` for unexpected characters
+/// or `UnterminatedString` for a string missing its closing quote), with the error carrying the
+/// line number.
 ///
 /// # Examples
 ///
 /// ```
+/// # use pallad::lexer::{tokenize, Token};
 /// let src = "var x = 42\nprint x\n";
 /// let tokens = tokenize(src).unwrap();
 /// // starts with: Var, Ident("x"), Eq, Int(42), Eol, Print, Ident("x"), Eol
-/// assert!(matches!(tokens.get(0), Some(Token::Var)));
-/// assert!(matches!(tokens.get(3), Some(Token::Int(42))));
+/// assert!(matches!(tokens[0].token, Token::Var));
+/// assert!(matches!(tokens[3].token, Token::Int(42)));
+/// assert_eq!((tokens[3].line, tokens[3].column), (1, 9));
 /// ```
-pub fn tokenize(input: &str) -> Result<Vec<Token>, PalladError> {
-    let mut tokens = Vec::new();
-
-    for (line_no, line) in input.lines().enumerate() {
-        let line = line.split('#').next().unwrap_or("").trim();
-        if line.is_empty() { continue; }
-
-        let mut chars = line.chars().peekable();
-
-        while let Some(&ch) = chars.peek() {
-            match ch {
-                ' ' | '\t' => { chars.next(); }
-                '0'..='9' => {
-                    let mut num = String::new();
-                    let mut is_float = false;
-                    let mut dot_count = 0;
-                    while let Some(&c) = chars.peek() {
-                        if c.is_numeric() {
-                            num.push(c);
-                            chars.next();
-                        } else if c == '.' {
-                            dot_count += 1;
-                            if dot_count > 1 {
-                                return Err(PalladError::InvalidNumber {
-                                    value: num + ".",
-                                    line: line_no + 1,
-                                });
-                            }
-                            is_float = true;
-                            num.push(c);
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                    if is_float {
-                        tokens.push(Token::Float(num.parse().map_err(|_| {
-                            PalladError::InvalidNumber { value: num.clone(), line: line_no + 1 }
-                        })?));
-                    } else {
-                        tokens.push(Token::Int(num.parse().map_err(|_| {
-                            PalladError::InvalidNumber { value: num.clone(), line: line_no + 1 }
-                        })?));
+pub fn tokenize(input: &str) -> Result<Vec<Spanned>, PalladError> {
+    tokenize_with_trivia(input).map(|(tokens, _)| tokens)
+}
+
+/// Like `tokenize`, also returning the comments and standalone string literals of the source, in
+/// order, for `Parser::with_trivia` to attach to declarations as their docs.
+pub fn tokenize_with_trivia(input: &str) -> Result<(Vec<Spanned>, Vec<Trivia>), PalladError> {
+    Lexer::new(input, 1, 1).tokenize()
+}
+
+/// Scanner state: the source's characters and the position in them, as an index and as the line
+/// and column (both counted from 1) of the next character.
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    tokens: Vec<Spanned>,
+    trivia: Vec<Trivia>,
+    /// Indices into `trivia` of the regions not closed yet, innermost last.
+    regions: Vec<usize>,
+    /// Leading whitespace of each open block, outermost (`""`) first.
+    indents: Vec<String>,
+    /// Where the last line scanned ends, for the `Dedent` tokens closing the blocks still open.
+    end: (usize, usize),
+}
+
+impl Lexer {
+    /// A lexer for `source`, which starts at `line` and `column` (f-string fields are lexed from
+    /// the middle of a line).
+    fn new(source: &str, line: usize, column: usize) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            line,
+            column,
+            tokens: Vec::new(),
+            trivia: Vec::new(),
+            regions: Vec::new(),
+            indents: vec![String::new()],
+            end: (line, column),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// The character `ahead` characters after the next one.
+    fn peek_at(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.pos + ahead).copied()
+    }
+
+    /// Consumes the next character, moving to the next line after a line break.
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Consumes the next character if it is `expected`.
+    fn next_if_eq(&mut self, expected: char) -> bool {
+        let matched = self.peek() == Some(expected);
+        if matched {
+            self.next();
+        }
+        matched
+    }
+
+    fn push(&mut self, token: Token, line: usize, column: usize) {
+        self.tokens.push(Spanned { token, line, column });
+    }
+
+    fn tokenize(mut self) -> Result<(Vec<Spanned>, Vec<Trivia>), PalladError> {
+        while self.peek().is_some() {
+            self.scan_line()?;
+        }
+        let (line, column) = self.end;
+        while self.indents.len() > 1 {
+            self.indents.pop();
+            self.push(Token::Dedent, line, column);
+        }
+        while let Some(open) = self.regions.pop() {
+            let line = self.trivia[open].line;
+            self.trivia[open].end_line = line.max(self.end.0);
+            let text = "'#region' is never closed by '#endregion'".to_string();
+            self.trivia.push(Trivia { kind: TriviaKind::UnbalancedRegion, text, line, end_line: line });
+        }
+
+        Ok((self.tokens, self.trivia))
+    }
+
+    /// Scans a line (and any following lines a string literal spans) up to and including its
+    /// line break.
+    fn scan_line(&mut self) -> Result<(), PalladError> {
+        let (start_line, start) = (self.line, self.pos);
+        while self.peek().is_some_and(|c| c != '\n' && c.is_whitespace()) {
+            self.next();
+        }
+        let indent: String = self.chars[start..self.pos].iter().collect();
+        let indent_column = self.column;
+        let line_start = self.tokens.len();
+        let mut comment = None;
+        while let Some(c) = self.peek() {
+            match c {
+                '\n' => break,
+                '#' => {
+                    self.next();
+                    let mut text = String::new();
+                    while let Some(c) = self.peek().filter(|c| *c != '\n') {
+                        text.push(c);
+                        self.next();
                     }
+                    comment = Some(text);
                 }
-                'a'..='z' | 'A'..='Z' => {
-                    let mut ident = String::new();
-                    while let Some(&c) = chars.peek() {
-                        if c.is_alphanumeric() || c == '_' {
-                            ident.push(c);
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                    match ident.as_str() {
-                        "var" => tokens.push(Token::Var),
-                        "none" => tokens.push(Token::None),
-                        "print" => tokens.push(Token::Print),
-                        _ => tokens.push(Token::Ident(ident)),
+                c if c.is_whitespace() => { self.next(); }
+                _ => self.scan_token()?,
+            }
+        }
+
+        if let [Spanned { token: Token::Str(text), .. }] = &self.tokens[line_start..] {
+            let text = text.trim().to_string();
+            self.trivia.push(Trivia { kind: TriviaKind::Docstring, text, line: start_line, end_line: self.line });
+        }
+        if let Some(text) = comment {
+            self.comment(text, self.tokens.len() > line_start)?;
+        }
+        // Blank and comment-only lines don't affect indentation
+        if self.tokens.len() > line_start {
+            let dents = self.indentation(indent, start_line, indent_column)?;
+            self.tokens.splice(line_start..line_start, dents);
+            self.push(Token::Eol, self.line, self.column);
+        }
+        self.end = (self.line, self.column);
+        self.next();
+        Ok(())
+    }
+
+    /// Records the `#` comment ending the current line and the tags in it, merging it into a
+    /// comment on the line above when both are on lines of their own. On a line of its own,
+    /// `#region Name` opens a region and `#endregion` closes the innermost one instead.
+    fn comment(&mut self, text: String, trailing: bool) -> Result<(), PalladError> {
+        let line = self.line;
+        if !trailing {
+            if let Some(name) = after_marker(&text, "region") {
+                self.regions.push(self.trivia.len());
+                self.trivia.push(Trivia { kind: TriviaKind::Region, text: name.to_string(), line, end_line: line });
+                return Ok(());
+            }
+            if after_marker(&text, "endregion").is_some() {
+                match self.regions.pop() {
+                    Some(open) => self.trivia[open].end_line = line,
+                    None => {
+                        let text = "'#endregion' closes no '#region'".to_string();
+                        self.trivia.push(Trivia { kind: TriviaKind::UnbalancedRegion, text, line, end_line: line });
                     }
                 }
-                '"' => {
-                    chars.next(); // consume opening "
-                    let s = parse_string(&mut chars, '"', line_no)?;
-                    tokens.push(Token::Str(s));
+                return Ok(());
+            }
+        }
+
+        let text = text.trim();
+        let above = self.trivia.iter_mut().rev()
+            .find(|above| !matches!(above.kind, TriviaKind::Tag(_)))
+            .filter(|above| !trailing && above.kind == TriviaKind::Comment && above.end_line + 1 == line);
+        if let Some(above) = above {
+            if !text.is_empty() {
+                if !above.text.is_empty() {
+                    above.text.push(' ');
                 }
-                '\'' => {
-                    chars.next(); // consume opening '
-                    let s = parse_string(&mut chars, '\'', line_no)?;
-                    tokens.push(Token::Str(s));
+                above.text.push_str(text);
+            }
+            above.end_line = line;
+        } else {
+            let kind = if trailing { TriviaKind::TrailingComment } else { TriviaKind::Comment };
+            self.trivia.push(Trivia { kind, text: text.to_string(), line, end_line: line });
+        }
+        for (tag, text) in tags::find(text) {
+            self.trivia.push(Trivia { kind: TriviaKind::Tag(tag), text, line, end_line: line });
+        }
+        Ok(())
+    }
+
+    /// The `Indent` or `Dedent` tokens that open or close blocks before a line indented by `indent`,
+    /// whose code starts at `line` and `column`.
+    ///
+    /// A deeper line must extend the current indentation exactly (so tabs and spaces can't be
+    /// mixed between levels), and a shallower one must return to an enclosing level.
+    fn indentation(&mut self, indent: String, line: usize, column: usize) -> Result<Vec<Spanned>, PalladError> {
+        let current = self.indents.last().map_or("", String::as_str);
+        if indent == current {
+            return Ok(vec![]);
+        }
+        if indent.len() > current.len() {
+            if !indent.starts_with(current) {
+                return Err(PalladError::InconsistentIndent { line });
+            }
+            self.indents.push(indent);
+            return Ok(vec![Spanned { token: Token::Indent, line, column }]);
+        }
+
+        let mut dents = vec![];
+        while self.indents.last().is_some_and(|open| open.len() > indent.len()) {
+            self.indents.pop();
+            dents.push(Spanned { token: Token::Dedent, line, column });
+        }
+        if self.indents.last() != Some(&indent) {
+            return Err(PalladError::InconsistentIndent { line });
+        }
+        Ok(dents)
+    }
+
+    /// Emits the token starting at the next character (which isn't whitespace or a comment).
+    fn scan_token(&mut self) -> Result<(), PalladError> {
+        let (line, column) = (self.line, self.column);
+        let Some(ch) = self.peek() else {
+            return Ok(());
+        };
+        match ch {
+            '0'..='9' => {
+                let token = self.lex_number()?;
+                self.push(token, line, column);
+            }
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let mut ident = String::new();
+                while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                    self.next();
                 }
-                '/' => {
-                    chars.next();
-                    if let Some(&'/') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token::IntDiv);
-                    } else {
-                        tokens.push(Token::Slash);
+                let token = match ident.as_str() {
+                    "f" if matches!(self.peek(), Some('"' | '\'')) => {
+                        let quote = self.next().unwrap_or('"');
+                        let multiline = self.open_triple_quote(quote);
+                        Token::FStr(self.parse_fstring(quote, multiline, line)?)
                     }
+                    _ => match KEYWORDS.iter().find(|(keyword, _)| *keyword == ident) {
+                        Some((_, token)) => token.clone(),
+                        None => Token::Ident(ident),
+                    },
+                };
+                self.push(token, line, column);
+            }
+            '"' | '\'' => {
+                self.next(); // consume opening quote
+                let multiline = self.open_triple_quote(ch);
+                let s = self.parse_string(ch, multiline, line)?;
+                self.push(Token::Str(s), line, column);
+            }
+            _ => {
+                self.next();
+                let token = match ch {
+                    '/' if self.next_if_eq('/') => Token::IntDiv,
+                    '/' => Token::Slash,
+                    '*' if self.next_if_eq('*') => Token::Pow,
+                    '*' => Token::Star,
+                    '<' if self.next_if_eq('<') => Token::Shl,
+                    '<' if self.next_if_eq('=') => Token::Le,
+                    '<' => Token::Lt,
+                    '>' if self.next_if_eq('>') => Token::Shr,
+                    '>' if self.next_if_eq('=') => Token::Ge,
+                    '>' => Token::Gt,
+                    '=' if self.next_if_eq('=') => Token::EqEq,
+                    '=' => Token::Eq,
+                    '!' if self.next_if_eq('=') => Token::NotEq,
+                    '+' => Token::Plus,
+                    '-' if self.next_if_eq('>') => Token::Arrow,
+                    '-' => Token::Minus,
+                    '%' => Token::Mod,
+                    '&' => Token::Amp,
+                    '|' => Token::Pipe,
+                    '^' => Token::Caret,
+                    '~' => Token::Tilde,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    ',' => Token::Comma,
+                    '.' if self.next_if_eq('.') => Token::DotDot,
+                    '.' => Token::Dot,
+                    ':' => Token::Colon,
+                    '@' => Token::At,
+                    _ => {
+                        return Err(PalladError::UnknownCharacter {
+                            got: ch.to_string(),
+                            line,
+                        });
+                    }
+                };
+                self.push(token, line, column);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lexes an integer, big integer, float, or `d`-suffixed decimal literal.
+    fn lex_number(&mut self) -> Result<Token, PalladError> {
+        let line = self.line;
+        let mut num = String::new();
+        let mut is_float = false;
+        let mut dot_count = 0;
+        while let Some(c) = self.peek() {
+            if c.is_numeric() {
+                num.push(c);
+                self.next();
+            } else if c == '.' {
+                // `1..5` is a range, not a float
+                if self.peek_at(1) == Some('.') {
+                    break;
                 }
-                '+' => { chars.next(); tokens.push(Token::Plus); }
-                '-' => { chars.next(); tokens.push(Token::Minus); }
-                '*' => { chars.next(); tokens.push(Token::Star); }
-                '%' => { chars.next(); tokens.push(Token::Mod); }
-                '=' => { chars.next(); tokens.push(Token::Eq); }
-                '(' => { chars.next(); tokens.push(Token::LParen); }
-                ')' => { chars.next(); tokens.push(Token::RParen); }
-                ',' => { chars.next(); tokens.push(Token::Comma); }
-                _ => {
-                    return Err(PalladError::UnknownCharacter {
-                        got: ch.to_string(),
-                        line: line_no + 1,
+                dot_count += 1;
+                if dot_count > 1 {
+                    return Err(PalladError::InvalidNumber {
+                        value: num + ".",
+                        line,
                     });
-                },
+                }
+                is_float = true;
+                num.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+        if self.next_if_eq('d') {
+            num.push('d');
+            if self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                    num.push(c);
+                    self.next();
+                }
+                return Err(PalladError::InvalidNumber { value: num, line });
             }
+            Ok(Token::Decimal(Decimal::parse(&num[..num.len() - 1]).ok_or_else(|| {
+                PalladError::InvalidNumber { value: num.clone(), line }
+            })?))
+        } else if is_float {
+            Ok(Token::Float(num.parse().map_err(|_| {
+                PalladError::InvalidNumber { value: num.clone(), line }
+            })?))
+        } else if let Ok(n) = num.parse() {
+            Ok(Token::Int(n))
+        } else {
+            // Too large for 64 bits, kept exact as a big integer
+            Ok(Token::BigInt(BigInt::parse(&num).ok_or_else(|| {
+                PalladError::InvalidNumber { value: num.clone(), line }
+            })?))
         }
-        tokens.push(Token::Eol);
-    } 
+    }
 
-    Ok(tokens)
-}
+    /// After an opening quote, consumes two more `quote`s if they are there and reports whether
+    /// the literal is triple-quoted. `""` alone stays an empty string.
+    fn open_triple_quote(&mut self, quote: char) -> bool {
+        if self.peek() == Some(quote) && self.peek_at(1) == Some(quote) {
+            self.next();
+            self.next();
+            return true;
+        }
+        false
+    }
 
-fn parse_string(
-    chars: &mut std::iter::Peekable<std::str::Chars>,
-    quote: char,
-    line_no: usize,
-) -> Result<String, PalladError> {
-    let mut s = String::new();
-    let mut closed = false;
-
-    while let Some(c) = chars.next() {
-        match c {
-            '\\' => {
-                let escaped = match chars.next() {
-                    Some('n') => '\n',
-                    Some('t') => '\t',
-                    Some('r') => '\r',
-                    Some(q) if q == quote => q,
-                    Some('\\') => '\\',
-                    Some(other) => {
-                        return Err(PalladError::InvalidEscape {
-                            char: other,
-                            line: line_no + 1,
-                        });
-                    }
-                    None => {
-                        return Err(PalladError::UnterminatedString { line: line_no + 1 });
-                    }
-                };
-                s.push(escaped);
+    /// Consumes the closing quote(s) if the next characters close the literal.
+    fn close_quote(&mut self, quote: char, multiline: bool) -> bool {
+        if !multiline {
+            return self.next_if_eq(quote);
+        }
+        if (0..3).all(|ahead| self.peek_at(ahead) == Some(quote)) {
+            (0..3).for_each(|_| { self.next(); });
+            return true;
+        }
+        false
+    }
+
+    /// Next character of a string body. Only triple-quoted strings continue past a line break,
+    /// which reads as `'\n'`.
+    fn next_string_char(&mut self, multiline: bool) -> Option<char> {
+        let c = self.peek()?;
+        let line_break = c == '\n' || (c == '\r' && self.peek_at(1) == Some('\n'));
+        if line_break && !multiline {
+            return None;
+        }
+        if c == '\r' && line_break {
+            self.next();
+        }
+        self.next()
+    }
+
+    /// Reads the character after a `\` and returns the character it stands for.
+    fn parse_escape(&mut self, quote: char, start_line: usize) -> Result<char, PalladError> {
+        let escaped = self.peek().filter(|c| *c != '\n');
+        if escaped.is_some() {
+            self.next();
+        }
+        match escaped {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some(q) if q == quote => Ok(q),
+            Some('\\') => Ok('\\'),
+            Some(other) => Err(PalladError::InvalidEscape {
+                char: other,
+                line: self.line,
+            }),
+            None => Err(PalladError::UnterminatedString { line: start_line }),
+        }
+    }
+
+    /// Parses a string body after its opening quote(s), up to and including the closing quote(s).
+    ///
+    /// `start_line` is the line of the opening quote, reported for unterminated strings.
+    fn parse_string(&mut self, quote: char, multiline: bool, start_line: usize) -> Result<String, PalladError> {
+        let mut s = String::new();
+
+        loop {
+            if self.close_quote(quote, multiline) {
+                return Ok(s);
+            }
+            match self.next_string_char(multiline) {
+                Some('\\') => s.push(self.parse_escape(quote, start_line)?),
+                Some(c) => s.push(c),
+                None => return Err(PalladError::UnterminatedString { line: start_line }),
             }
-            c if c == quote => {
-                closed = true;
+        }
+    }
+
+    /// Parses an f-string body after its opening quote(s) into literal and `{expression:spec}` parts.
+    ///
+    /// `{{` and `}}` stand for literal braces. Each field's expression is lexed on its own, so any
+    /// expression is allowed, including strings in the other quote style.
+    fn parse_fstring(&mut self, quote: char, multiline: bool, start_line: usize) -> Result<Vec<FStrPart>, PalladError> {
+        let mut parts = vec![];
+        let mut literal = String::new();
+
+        loop {
+            if self.close_quote(quote, multiline) {
                 break;
             }
-            other => s.push(other),
+            match self.next_string_char(multiline) {
+                Some('\\') => literal.push(self.parse_escape(quote, start_line)?),
+                Some('{') if self.next_if_eq('{') => literal.push('{'),
+                Some('}') if self.next_if_eq('}') => literal.push('}'),
+                Some('}') => {
+                    return Err(PalladError::InvalidFString {
+                        reason: "single '}' is not allowed, use '}}'",
+                        line: self.line,
+                    });
+                }
+                Some('{') => {
+                    if !literal.is_empty() {
+                        parts.push(FStrPart::Lit(std::mem::take(&mut literal)));
+                    }
+                    parts.push(self.parse_fstring_field(quote, multiline, start_line)?);
+                }
+                Some(c) => literal.push(c),
+                None => return Err(PalladError::UnterminatedString { line: start_line }),
+            }
+        }
+        if !literal.is_empty() || parts.is_empty() {
+            parts.push(FStrPart::Lit(literal));
         }
+
+        Ok(parts)
     }
 
-    if !closed {
-        return Err(PalladError::UnterminatedString { line: line_no + 1 });
+    /// Parses an f-string field after its `{`, up to and including the closing `}`.
+    fn parse_fstring_field(&mut self, quote: char, multiline: bool, start_line: usize) -> Result<FStrPart, PalladError> {
+        let (field_line, field_column) = (self.line, self.column);
+        let mut source = String::new();
+        let mut depth = 0;
+        let mut nested_quote: Option<char> = None;
+
+        // Collect the expression source up to a top-level ':' or '}'
+        let ends_with_spec = loop {
+            let Some(c) = self.next_string_char(multiline) else {
+                return Err(PalladError::UnterminatedString { line: start_line });
+            };
+            match (nested_quote, c) {
+                (Some(q), c) if c == q => nested_quote = None,
+                (Some(_), _) => {}
+                (None, c) if c == quote => {
+                    return Err(PalladError::InvalidFString { reason: "expected '}'", line: self.line });
+                }
+                (None, '"' | '\'') => nested_quote = Some(c),
+                (None, '(' | '[' | '{') => depth += 1,
+                (None, ')' | ']') => depth -= 1,
+                (None, '}') if depth > 0 => depth -= 1,
+                (None, '}') => break false,
+                (None, ':') if depth == 0 => break true,
+                _ => {}
+            }
+            source.push(c);
+        };
+
+        let spec = if ends_with_spec {
+            let mut text = String::new();
+            loop {
+                match self.next_string_char(multiline) {
+                    Some('}') => break,
+                    Some(c) if c != quote && c != '{' => text.push(c),
+                    _ => return Err(PalladError::InvalidFString { reason: "expected '}'", line: self.line }),
+                }
+            }
+            Some(FormatSpec::parse(&text).ok_or(PalladError::InvalidFormatSpec { spec: text, line: self.line })?)
+        } else {
+            None
+        };
+
+        let (mut tokens, _) = Lexer::new(&source, field_line, field_column).tokenize()?;
+        tokens.retain(|t| !matches!(t.token, Token::Eol | Token::Indent | Token::Dedent));
+        if tokens.is_empty() {
+            return Err(PalladError::InvalidFString { reason: "empty expression", line: field_line });
+        }
+
+        Ok(FStrPart::Expr { tokens, spec })
     }
+}
 
-    Ok(s)
-}
\ No newline at end of file
+/// The rest of a comment's `text` after the `marker` starting it, as in `#region Name`.
+fn after_marker<'t>(text: &'t str, marker: &str) -> Option<&'t str> {
+    let rest = text.strip_prefix(marker)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
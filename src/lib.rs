@@ -0,0 +1,33 @@
+//! The Pallad language: a lexer, parser, compiler and stack-based VM.
+//!
+//! Source text goes through `lexer::tokenize`, `parser::Parser`, `compiler::compile` and
+//! `vm::VM::run`. Hosts embedding the VM can replace its input and output with `VM::set_input`
+//! and `VM::set_output` (see `output` for in-memory and callback sinks), and route script logs with
+//! `VM::set_log_sink`. Imports are loaded by the VM as they run (see `module`), relative to the
+//! file set with `VM::set_script_path`. `doc` writes references of a file's declarations from
+//! their docstrings and comments, kept by `lexer::tokenize_with_trivia`, and `tags` lists the
+//! tags and regions in its comments.
+
+pub mod ast;
+pub mod bigint;
+pub mod builtins;
+pub mod color;
+pub mod compiler;
+pub mod decimal;
+pub mod doc;
+pub mod error;
+pub mod exception;
+pub mod file;
+pub mod format;
+pub mod ir;
+pub mod lexer;
+pub mod log;
+pub mod methods;
+pub mod module;
+pub mod object;
+pub mod output;
+pub mod parser;
+pub mod signal;
+pub mod tags;
+pub mod value;
+pub mod vm;
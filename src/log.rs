@@ -0,0 +1,123 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::color::Color;
+
+/// How important a log record is; records below the logger's level are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    /// Looks up a level by name (`debug`, `info`, `warning`, or `error`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    /// Terminal color of the level's records, unless the call gives its own.
+    fn ansi(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "\x1b[90m",
+            LogLevel::Info => "",
+            LogLevel::Warning => "\x1b[33m",
+            LogLevel::Error => "\x1b[31m",
+        }
+    }
+}
+
+/// One call to a `log` method.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    /// Source line of the `log` call.
+    pub line: usize,
+    pub timestamp: SystemTime,
+    /// Color passed by the script, e.g. `log.message("Red!", Color.RED)`.
+    pub color: Option<Color>,
+}
+
+impl LogRecord {
+    /// The record as one line of text, e.g. `2026-01-31T12:00:00Z ERROR line 4: Failed`.
+    pub fn format(&self) -> String {
+        format!("{} {} line {}: {}", format_timestamp(self.timestamp), self.level.name(), self.line, self.message)
+    }
+}
+
+/// Receives the log records of a VM (see `VM::set_log_sink`).
+pub type LogSink = Box<dyn FnMut(&LogRecord)>;
+
+/// The state behind a VM's `log` namespace: the minimum level and where records go.
+pub struct Logger {
+    pub level: LogLevel,
+    /// `None` writes records to standard error, colored when it is a terminal.
+    pub sink: Option<LogSink>,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self { level: LogLevel::Info, sink: None }
+    }
+}
+
+impl Logger {
+    /// Passes the record on, unless it is below the logger's level.
+    pub fn log(&mut self, record: LogRecord) -> io::Result<()> {
+        if record.level < self.level {
+            return Ok(());
+        }
+        if let Some(sink) = &mut self.sink {
+            sink(&record);
+            return Ok(());
+        }
+
+        let mut stderr = io::stderr();
+        let text = record.format();
+        let color = record.color.map_or(record.level.ansi().to_string(), |color| color.ansi());
+        if stderr.is_terminal() && !color.is_empty() {
+            writeln!(stderr, "{}{}\x1b[0m", color, text)
+        } else {
+            writeln!(stderr, "{}", text)
+        }
+    }
+}
+
+/// Formats a time as an ISO 8601 UTC timestamp with whole seconds.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60,
+    )
+}
@@ -1,23 +1,28 @@
 use std::env;
-mod ast;
-mod lexer;
-mod parser;
-mod ir;
-mod vm;
-mod value;
-mod compiler;
-pub mod error;
-
 use std::fs;
-use crate::lexer::tokenize;
-use crate::parser::Parser;
-use crate::compiler::compile;
-use crate::vm::VM;
+use std::io::BufReader;
+use std::path::Path;
+use pallad::doc::{self, Format};
+use pallad::lexer::{tokenize, tokenize_with_trivia};
+use pallad::parser::Parser;
+use pallad::tags;
+use pallad::compiler::compile;
+use pallad::vm::{VM, OverflowPolicy};
+use pallad::decimal::{self, DecimalContext, RoundingMode};
 
 /// Entry point for the Pallad toolchain: reads a source file, tokenizes and parses it, compiles the AST, and executes the resulting program on the VM while printing any errors to standard error.
 ///
 /// On success this runs the compiled program; on failure it prints a descriptive error message to stderr and exits early for that stage (file read, tokenization, parsing, or compilation). The default input path is "examples/example.pd" when no command-line argument is provided.
 ///
+/// `--overflow=<error|wrap|saturate|promote>` selects how integer overflow is handled (default `error`).
+/// `--decimal-precision=<digits>` and `--decimal-rounding=<half-even|half-up|half-down|up|down|ceiling|floor>`
+/// configure decimal arithmetic (default 28 fractional digits, at most 1000, `half-even`).
+/// `--input=<path>` makes `input()` read lines from a file instead of standard input.
+///
+/// `pallad doc [--format=<markdown|html>] <path>` prints a reference of the file's declarations
+/// instead of running it (see `doc`), and `pallad tags <path>...` prints the comment tags and
+/// regions of files, and of the `.pd` files of folders, as JSON (see `tags`).
+///
 /// # Examples
 ///
 /// ```no_run
@@ -26,12 +31,67 @@ use crate::vm::VM;
 ///
 /// // Run against a specific source file:
 /// // $ cargo run --release -- path/to/program.pd
+///
+/// // Promote overflowing integers to big integers:
+/// // $ cargo run --release -- --overflow=promote path/to/program.pd
+///
+/// // Write an HTML reference of a file:
+/// // $ cargo run --release -- doc --format=html path/to/program.pd > program.html
 /// ```
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let filename = args.get(1).map(|s| s.as_str()).unwrap_or("examples/example.pd");
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "doc") {
+        document(&args[1..]);
+        return;
+    }
+    if args.first().is_some_and(|arg| arg == "tags") {
+        list_tags(&args[1..]);
+        return;
+    }
 
-    let code = match fs::read_to_string(filename) {
+    let mut filename = "examples/example.pd".to_string();
+    let mut overflow = OverflowPolicy::default();
+    let mut decimal = DecimalContext::default();
+    let mut input = None;
+    for arg in args {
+        if let Some(name) = arg.strip_prefix("--overflow=") {
+            overflow = match OverflowPolicy::from_name(name) {
+                Some(policy) => policy,
+                None => {
+                    eprintln!("Unknown overflow policy '{}', expected error, wrap, saturate, or promote", name);
+                    return;
+                }
+            };
+        } else if let Some(digits) = arg.strip_prefix("--decimal-precision=") {
+            decimal.precision = match digits.parse() {
+                Ok(precision) if precision <= decimal::MAX_PRECISION => precision,
+                _ => {
+                    eprintln!("Invalid decimal precision '{}', expected a number of digits up to {}", digits, decimal::MAX_PRECISION);
+                    return;
+                }
+            };
+        } else if let Some(name) = arg.strip_prefix("--decimal-rounding=") {
+            decimal.rounding = match RoundingMode::from_name(name) {
+                Some(mode) => mode,
+                None => {
+                    eprintln!("Unknown rounding mode '{}', expected half-even, half-up, half-down, up, down, ceiling, or floor", name);
+                    return;
+                }
+            };
+        } else if let Some(path) = arg.strip_prefix("--input=") {
+            input = match fs::File::open(path) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    eprintln!("Failed to open the input file '{}': {}", path, e);
+                    return;
+                }
+            };
+        } else {
+            filename = arg;
+        }
+    }
+
+    let code = match fs::read_to_string(&filename) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Failed to read the Pallad source file '{}': {}", filename, e);
@@ -47,8 +107,9 @@ fn main() {
         }
     };
 
+    let name = Path::new(&filename).file_stem().map(|stem| stem.to_string_lossy().into_owned());
     let mut parser = Parser::new(tokens);
-    let stmts = match parser.parse() {
+    let stmts = match parser.parse_module(name.as_deref()) {
         Ok(s) => s,
         Err(err) => {
             eprintln!("Parse error: {}", err);
@@ -57,7 +118,12 @@ fn main() {
     };
 
     let program = match compile(stmts) {
-        Ok(s) => s,
+        Ok((program, warnings)) => {
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            program
+        }
         Err(err) => {
             eprintln!("Compile error: {}", err);
             return;
@@ -65,7 +131,106 @@ fn main() {
     };
 
     let mut vm = VM::new();
-    if let Err(err) = vm.run(program) {
+    vm.set_script_path(&filename);
+    vm.set_overflow_policy(overflow);
+    vm.set_decimal_context(decimal);
+    if let Some(file) = input {
+        vm.set_input(Box::new(BufReader::new(file)));
+    }
+    let result = vm.run(program);
+    for (path, warning) in vm.warnings() {
+        eprintln!("Warning: {}: {}", path, warning);
+    }
+    if let Err(err) = result {
         eprintln!("Runtime error: {}", err);
     }
-}
\ No newline at end of file
+}
+/// Prints the reference of a source file for `pallad doc`, Markdown unless `--format=html`.
+fn document(args: &[String]) {
+    let mut format = Format::Markdown;
+    let mut filename = None;
+    for arg in args {
+        if let Some(name) = arg.strip_prefix("--format=") {
+            format = match Format::from_name(name) {
+                Some(format) => format,
+                None => {
+                    eprintln!("Unknown documentation format '{}', expected markdown or html", name);
+                    return;
+                }
+            };
+        } else {
+            filename = Some(arg);
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("Usage: pallad doc [--format=markdown|html] <file>");
+        return;
+    };
+
+    let code = match fs::read_to_string(filename) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read the Pallad source file '{}': {}", filename, e);
+            return;
+        }
+    };
+
+    let (tokens, trivia) = match tokenize_with_trivia(&code) {
+        Ok(scanned) => scanned,
+        Err(err) => {
+            eprintln!("Tokenizer error: {}", err);
+            return;
+        }
+    };
+
+    let name = Path::new(filename).file_stem().map(|stem| stem.to_string_lossy().into_owned());
+    let stmts = match Parser::with_trivia(tokens, trivia).parse_module(name.as_deref()) {
+        Ok(s) => s,
+        Err(err) => {
+            eprintln!("Parse error: {}", err);
+            return;
+        }
+    };
+
+    print!("{}", doc::render(name.as_deref().unwrap_or(filename), &stmts, format));
+}
+
+/// Prints the comment tags and regions of the files (or folders' `.pd` files) in `args` as JSON,
+/// for `pallad tags`. A file that can't be read or tokenized is listed with its error.
+fn list_tags(args: &[String]) {
+    if args.is_empty() {
+        eprintln!("Usage: pallad tags <file or folder>...");
+        return;
+    }
+    let mut files = vec![];
+    for arg in args {
+        let path = Path::new(arg);
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            continue;
+        }
+        let mut sources: Vec<_> = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.is_file() && file.extension().is_some_and(|extension| extension == "pd"))
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to read the folder '{}': {}", arg, e);
+                return;
+            }
+        };
+        sources.sort();
+        files.extend(sources);
+    }
+
+    let listed: Vec<_> = files.iter()
+        .map(|file| {
+            let trivia = match fs::read_to_string(file) {
+                Ok(code) => tokenize_with_trivia(&code).map(|(_, trivia)| trivia).map_err(|err| err.to_string()),
+                Err(e) => Err(format!("Failed to read the file: {}", e)),
+            };
+            (file.display().to_string(), trivia)
+        })
+        .collect();
+    print!("{}", tags::report(&listed));
+}
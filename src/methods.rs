@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use crate::decimal::DecimalContext;
+use crate::error::PalladError;
+use crate::file::FileRef;
+use crate::format;
+use crate::value::Value;
+
+/// A method implemented in Rust: called with the receiver, the call arguments, and the VM's
+/// decimal context (used when formatting decimals).
+pub type NativeMethod = fn(&Value, &[Value], &DecimalContext) -> Result<Value, PalladError>;
+
+const STRING_METHODS: &[(&str, NativeMethod)] = &[
+    ("len", string_len),
+    ("upper", upper),
+    ("lower", lower),
+    ("strip", strip),
+    ("lstrip", lstrip),
+    ("rstrip", rstrip),
+    ("split", split),
+    ("join", join),
+    ("replace", replace),
+    ("find", find),
+    ("starts_with", starts_with),
+    ("ends_with", ends_with),
+    ("format", format),
+];
+
+const ARRAY_METHODS: &[(&str, NativeMethod)] = &[
+    ("len", array_len),
+];
+
+const TUPLE_METHODS: &[(&str, NativeMethod)] = &[
+    ("len", array_len),
+];
+
+const DICT_METHODS: &[(&str, NativeMethod)] = &[
+    ("len", dict_len),
+    ("keys", dict_keys),
+    ("values", dict_values),
+];
+
+const FILE_METHODS: &[(&str, NativeMethod)] = &[
+    ("read", file_read),
+    ("write", file_write),
+    ("close", file_close),
+];
+
+/// Native methods behind `value.name(args)` calls, looked up by the receiver's type name and the
+/// method name.
+pub struct MethodTable {
+    methods: HashMap<(&'static str, &'static str), NativeMethod>,
+}
+
+impl MethodTable {
+    /// Creates a table holding the standard string, array, tuple, dict and file methods.
+    pub fn standard() -> Self {
+        let mut table = Self { methods: HashMap::new() };
+        for (name, method) in STRING_METHODS {
+            table.register("string", name, *method);
+        }
+        for (name, method) in ARRAY_METHODS {
+            table.register("array", name, *method);
+        }
+        for (name, method) in TUPLE_METHODS {
+            table.register("tuple", name, *method);
+        }
+        for (name, method) in DICT_METHODS {
+            table.register("dict", name, *method);
+        }
+        for (name, method) in FILE_METHODS {
+            table.register("file", name, *method);
+        }
+        table
+    }
+
+    /// Adds (or replaces) the method `name` for values whose `Value::type_name` is `type_name`.
+    pub fn register(&mut self, type_name: &'static str, name: &'static str, method: NativeMethod) {
+        self.methods.insert((type_name, name), method);
+    }
+
+    /// Finds the method `name` for the type of `receiver`.
+    pub fn lookup(&self, receiver: &Value, name: &str) -> Option<NativeMethod> {
+        self.methods.get(&(receiver.type_name(), name)).copied()
+    }
+}
+
+/// Fails unless `min..=max` arguments were passed to `function`.
+pub fn check_args(function: &str, args: &[Value], min: usize, max: usize) -> Result<(), PalladError> {
+    if args.len() < min || args.len() > max {
+        return Err(PalladError::ArgumentCount {
+            function: function.to_string(),
+            min,
+            max,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+fn str_arg<'a>(function: &str, args: &'a [Value], index: usize) -> Result<&'a str, PalladError> {
+    match &args[index] {
+        Value::Str(s) => Ok(s),
+        other => Err(PalladError::ArgumentType {
+            function: function.to_string(),
+            expected: "a string",
+            got: other.clone(),
+        }),
+    }
+}
+
+fn int_arg(function: &str, args: &[Value], index: usize) -> Result<i64, PalladError> {
+    match &args[index] {
+        Value::Int(n) => Ok(*n),
+        other => Err(PalladError::ArgumentType {
+            function: function.to_string(),
+            expected: "an integer",
+            got: other.clone(),
+        }),
+    }
+}
+
+/// The receiver of a string method (always a string, as the table is keyed by type).
+fn receiver_str<'a>(receiver: &'a Value, function: &'static str) -> Result<&'a str, PalladError> {
+    match receiver {
+        Value::Str(s) => Ok(s),
+        other => Err(PalladError::InvalidOperand { value: other.clone(), operation: function }),
+    }
+}
+
+/// Byte offset of the character at `index` in `s`, or `s.len()` past the end.
+fn byte_offset(s: &str, index: usize) -> usize {
+    s.char_indices().nth(index).map_or(s.len(), |(offset, _)| offset)
+}
+
+/// Number of characters (not bytes) in the string.
+fn string_len(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("len", args, 0, 0)?;
+    Ok(Value::Int(receiver_str(receiver, "len")?.chars().count() as i64))
+}
+
+fn upper(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("upper", args, 0, 0)?;
+    Ok(Value::Str(receiver_str(receiver, "upper")?.to_uppercase()))
+}
+
+fn lower(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("lower", args, 0, 0)?;
+    Ok(Value::Str(receiver_str(receiver, "lower")?.to_lowercase()))
+}
+
+/// Removes leading and trailing whitespace, or any of the characters in the optional argument.
+fn strip(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("strip", args, 0, 1)?;
+    let s = receiver_str(receiver, "strip")?;
+    Ok(Value::Str(match args.first() {
+        Some(_) => {
+            let chars = str_arg("strip", args, 0)?;
+            s.trim_matches(|c| chars.contains(c)).to_string()
+        }
+        None => s.trim().to_string(),
+    }))
+}
+
+fn lstrip(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("lstrip", args, 0, 1)?;
+    let s = receiver_str(receiver, "lstrip")?;
+    Ok(Value::Str(match args.first() {
+        Some(_) => {
+            let chars = str_arg("lstrip", args, 0)?;
+            s.trim_start_matches(|c| chars.contains(c)).to_string()
+        }
+        None => s.trim_start().to_string(),
+    }))
+}
+
+fn rstrip(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("rstrip", args, 0, 1)?;
+    let s = receiver_str(receiver, "rstrip")?;
+    Ok(Value::Str(match args.first() {
+        Some(_) => {
+            let chars = str_arg("rstrip", args, 0)?;
+            s.trim_end_matches(|c| chars.contains(c)).to_string()
+        }
+        None => s.trim_end().to_string(),
+    }))
+}
+
+/// Splits on a separator into an array; without one, splits on runs of whitespace.
+fn split(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("split", args, 0, 1)?;
+    let s = receiver_str(receiver, "split")?;
+    let parts: Vec<&str> = match args.first() {
+        Some(_) => {
+            let separator = str_arg("split", args, 0)?;
+            if separator.is_empty() {
+                return Err(PalladError::InvalidArgument {
+                    function: "split".to_string(),
+                    reason: "empty separator",
+                });
+            }
+            s.split(separator).collect()
+        }
+        None => s.split_whitespace().collect(),
+    };
+    Ok(Value::Array(parts.into_iter().map(|part| Value::Str(part.to_string())).collect()))
+}
+
+/// Joins the items of an array with the receiver between them (`", ".join(items)`).
+fn join(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("join", args, 1, 1)?;
+    let separator = receiver_str(receiver, "join")?;
+    let Value::Array(items) = &args[0] else {
+        return Err(PalladError::ArgumentType {
+            function: "join".to_string(),
+            expected: "an array",
+            got: args[0].clone(),
+        });
+    };
+    let items: Vec<String> = items.iter().map(Value::stringify).collect();
+    Ok(Value::Str(items.join(separator)))
+}
+
+/// Replaces occurrences of `old` with `new`; an optional count limits how many, from the start.
+fn replace(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("replace", args, 2, 3)?;
+    let s = receiver_str(receiver, "replace")?;
+    let old = str_arg("replace", args, 0)?;
+    let new = str_arg("replace", args, 1)?;
+    Ok(Value::Str(match args.get(2) {
+        Some(_) => {
+            let count = int_arg("replace", args, 2)?;
+            s.replacen(old, new, usize::try_from(count).unwrap_or(0))
+        }
+        None => s.replace(old, new),
+    }))
+}
+
+/// Character index of the first occurrence of the argument, searching from an optional start
+/// index (negative counts from the end), or `-1` if it isn't found.
+fn find(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("find", args, 1, 2)?;
+    let s = receiver_str(receiver, "find")?;
+    let needle = str_arg("find", args, 0)?;
+    let start = match args.get(1) {
+        Some(_) => {
+            let start = int_arg("find", args, 1)?;
+            let len = s.chars().count() as i64;
+            (if start < 0 { len + start } else { start }).clamp(0, len) as usize
+        }
+        None => 0,
+    };
+    let offset = byte_offset(s, start);
+    Ok(Value::Int(match s[offset..].find(needle) {
+        Some(found) => (start + s[offset..offset + found].chars().count()) as i64,
+        None => -1,
+    }))
+}
+
+fn starts_with(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("starts_with", args, 1, 1)?;
+    let s = receiver_str(receiver, "starts_with")?;
+    Ok(Value::Bool(s.starts_with(str_arg("starts_with", args, 0)?)))
+}
+
+fn ends_with(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("ends_with", args, 1, 1)?;
+    let s = receiver_str(receiver, "ends_with")?;
+    Ok(Value::Bool(s.ends_with(str_arg("ends_with", args, 0)?)))
+}
+
+/// `"{} costs {:.2f}".format(name, price)`, see `format::format_template`.
+fn format(receiver: &Value, args: &[Value], decimal: &DecimalContext) -> Result<Value, PalladError> {
+    let template = receiver_str(receiver, "format")?;
+    Ok(Value::Str(format::format_template(template, args, decimal.rounding)?))
+}
+
+fn array_len(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("len", args, 0, 0)?;
+    match receiver {
+        Value::Array(items) | Value::Tuple(items) => Ok(Value::Int(items.len() as i64)),
+        other => Err(PalladError::InvalidOperand { value: other.clone(), operation: "len" }),
+    }
+}
+
+/// The entries of a dict receiver.
+fn receiver_dict<'a>(receiver: &'a Value, function: &'static str) -> Result<&'a [(Value, Value)], PalladError> {
+    match receiver {
+        Value::Dict(entries) => Ok(entries),
+        other => Err(PalladError::InvalidOperand { value: other.clone(), operation: function }),
+    }
+}
+
+fn dict_len(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("len", args, 0, 0)?;
+    Ok(Value::Int(receiver_dict(receiver, "len")?.len() as i64))
+}
+
+/// The keys of a dict as an array, in insertion order.
+fn dict_keys(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("keys", args, 0, 0)?;
+    Ok(Value::Array(receiver_dict(receiver, "keys")?.iter().map(|(key, _)| key.clone()).collect()))
+}
+
+/// The values of a dict as an array, in insertion order.
+fn dict_values(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("values", args, 0, 0)?;
+    Ok(Value::Array(receiver_dict(receiver, "values")?.iter().map(|(_, value)| value.clone()).collect()))
+}
+
+/// The receiver of a file method.
+fn receiver_file<'a>(receiver: &'a Value, function: &'static str) -> Result<&'a FileRef, PalladError> {
+    match receiver {
+        Value::File(file) => Ok(file),
+        other => Err(PalladError::InvalidOperand { value: other.clone(), operation: function }),
+    }
+}
+
+fn file_read(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("read", args, 0, 0)?;
+    receiver_file(receiver, "read")?.read()
+}
+
+fn file_write(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("write", args, 1, 1)?;
+    receiver_file(receiver, "write")?.write(&args[0])
+}
+
+fn file_close(receiver: &Value, args: &[Value], _: &DecimalContext) -> Result<Value, PalladError> {
+    check_args("close", args, 0, 0)?;
+    receiver_file(receiver, "close")?.close()?;
+    Ok(Value::None)
+}
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use crate::ast::Stmt;
+use crate::compiler;
+use crate::error::{PalladError, Warning};
+use crate::ir::{Annotation, Instr};
+use crate::lexer;
+use crate::object;
+use crate::parser::Parser;
+use crate::value::Value;
+
+/// A module: a source file run once, on its first import, and the global variables its code
+/// declared. The program given to `VM::run` is a module too. A class module's file declares one
+/// class, which importing the module gives instead of the module.
+///
+/// Clones refer to the same module, so its globals are shared by everything importing it.
+#[derive(Clone)]
+pub struct ModuleRef(Rc<RefCell<Module>>);
+
+struct Module {
+    name: String,
+    path: Option<PathBuf>,
+    /// The name of the class a class module declares.
+    class: Option<String>,
+    globals: HashMap<String, Value>,
+    /// The types of the globals declared with one, which assignments to them must fit.
+    annotations: HashMap<String, Annotation>,
+    /// Files the module's imports skip, from `exclude`.
+    excluded: Vec<PathBuf>,
+}
+
+impl ModuleRef {
+    pub fn new(name: String, path: Option<PathBuf>, class: Option<String>) -> Self {
+        ModuleRef(Rc::new(RefCell::new(Module { name, path, class, globals: HashMap::new(), annotations: HashMap::new(), excluded: vec![] })))
+    }
+
+    pub fn name(&self) -> String {
+        self.0.borrow().name.clone()
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.0.borrow().path.clone()
+    }
+
+    /// The folder the module's imports are relative to: its file's folder, or the current
+    /// directory for a program that isn't read from a file.
+    pub fn dir(&self) -> PathBuf {
+        self.path()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// The module's file name, as shown in import errors.
+    pub fn file_name(&self) -> String {
+        self.path()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| self.name())
+    }
+
+    /// What importing the module gives: the class of a class module, or else the module.
+    pub fn export(&self) -> Value {
+        let class = self.0.borrow().class.clone();
+        class.and_then(|class| self.get(&class))
+            .unwrap_or_else(|| Value::Module(self.clone()))
+    }
+
+    /// The global variable `name`, if the module has it.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.0.borrow().globals.get(name).cloned()
+    }
+
+    pub fn set(&self, name: String, value: Value) {
+        self.0.borrow_mut().globals.insert(name, value);
+    }
+
+    /// The type the global `name` was declared with, if any.
+    pub fn annotation(&self, name: &str) -> Option<Annotation> {
+        self.0.borrow().annotations.get(name).cloned()
+    }
+
+    /// Declares the global `name` with the type `expected`, or with none.
+    pub fn annotate(&self, name: String, expected: Option<Annotation>) {
+        let annotations = &mut self.0.borrow_mut().annotations;
+        match expected {
+            Some(expected) => annotations.insert(name, expected),
+            None => annotations.remove(&name),
+        };
+    }
+
+    /// The module's public globals, by name, for `from module import *`.
+    pub fn public_globals(&self) -> Vec<(String, Value)> {
+        let mut globals: Vec<(String, Value)> = self.0.borrow().globals.iter()
+            .filter(|(name, _)| !object::is_private(name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        globals.sort_by(|(a, _), (b, _)| a.cmp(b));
+        globals
+    }
+
+    pub fn exclude(&self, path: PathBuf) {
+        self.0.borrow_mut().excluded.push(path);
+    }
+
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.0.borrow().excluded.iter().any(|excluded| excluded == path)
+    }
+}
+
+impl PartialEq for ModuleRef {
+    /// Modules are equal only when they are the same module.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for ModuleRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Globals may refer back to the module, so only its name is shown
+        write!(f, "ModuleRef({:?})", self.0.borrow().name)
+    }
+}
+
+/// What an import's path refers to: one module file, or the module files of a folder.
+pub enum Target {
+    File(PathBuf),
+    Folder(Vec<PathBuf>),
+}
+
+/// Finds the file or folder `path` of an import at `line` refers to, relative to `dir` unless it
+/// is absolute. A folder's path ends with `/`, and its modules are its `.pd` files in name order;
+/// a module's `.pd` extension is optional. Paths are canonical, so each file has one.
+pub fn resolve(dir: &Path, path: &str, line: usize) -> Result<Target, PalladError> {
+    let not_found = || PalladError::ModuleNotFound { path: path.to_string(), line };
+    let target = dir.join(path);
+    if path.ends_with('/') {
+        let entries = fs::read_dir(&target).map_err(|_| not_found())?;
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.is_file() && file.extension().is_some_and(|extension| extension == "pd"))
+            .filter_map(|file| file.canonicalize().ok())
+            .collect();
+        files.sort();
+        return Ok(Target::Folder(files));
+    }
+    let with_extension = PathBuf::from(format!("{}.pd", target.display()));
+    [target, with_extension].into_iter()
+        .find(|file| file.is_file())
+        .and_then(|file| file.canonicalize().ok())
+        .map(Target::File)
+        .ok_or_else(not_found)
+}
+
+/// The name a module at `path` is imported as: its file name without the `.pd` extension.
+pub fn module_name(path: &str) -> String {
+    let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    file.strip_suffix(".pd").unwrap_or(file).to_string()
+}
+
+/// A module file compiled by `load`.
+pub struct Compiled {
+    pub program: Vec<Instr>,
+    pub warnings: Vec<Warning>,
+    /// The name of the class of a class module.
+    pub class: Option<String>,
+}
+
+/// Reads and compiles the module file at `path` as the VM's module `index`, named `name`. Its
+/// errors are wrapped in `ModuleError`, naming the file.
+pub fn load(path: &Path, name: &str, index: usize) -> Result<Compiled, PalladError> {
+    let wrap = |error: PalladError| PalladError::ModuleError { path: path.display().to_string(), error: Box::new(error) };
+    let source = fs::read_to_string(path)
+        .map_err(|e| wrap(PalladError::Io { operation: "import", message: e.to_string() }))?;
+    let tokens = lexer::tokenize(&source).map_err(wrap)?;
+    let stmts = Parser::new(tokens).parse_module(Some(name)).map_err(wrap)?;
+    let class = stmts.iter().find_map(|stmt| match stmt {
+        Stmt::Class(decl) if decl.header => Some(decl.name.clone()),
+        _ => None,
+    });
+    let (program, warnings) = compiler::compile_module(stmts, index).map_err(wrap)?;
+    Ok(Compiled { program, warnings, class })
+}
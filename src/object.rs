@@ -0,0 +1,151 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use crate::ir::{Function, Param};
+use crate::value::Value;
+
+/// Whether the member or module-level name `name` is private: it starts with `_`. Private class
+/// members can only be used by the code of the class declaring them, and private module-level
+/// names only inside their module.
+pub fn is_private(name: &str) -> bool {
+    name.starts_with('_')
+}
+
+/// A class declared with `class Name [extends Parent]:`.
+pub struct Class {
+    pub name: String,
+    pub parent: Option<Rc<Class>>,
+    /// Fields declared by this class, with their types; inherited fields are the parents'.
+    pub fields: Vec<Param>,
+    /// Sets the fields of a new instance to their initial values, after the parent's initializer.
+    pub init: Rc<Function>,
+    /// Methods by name, inherited ones included unless overridden.
+    pub vtable: HashMap<String, Rc<Function>>,
+    /// Constructors of this class, in declaration order; they aren't inherited.
+    pub constructors: Vec<Rc<Function>>,
+    /// Runs the free code of the class body once, when the class is declared.
+    pub static_init: Rc<Function>,
+    /// Runs before the first instance of the class (or of one extending it) is created, and
+    /// must return `true`.
+    pub static_constructor: Option<Rc<Function>>,
+    /// Whether the static constructor has returned `true`.
+    pub ready: Cell<bool>,
+    /// Static variables and functions declared by this class, with their types.
+    pub static_fields: Vec<Param>,
+    /// Values of the static variables and functions, shared by every instance.
+    pub statics: RefCell<HashMap<String, Value>>,
+}
+
+impl Class {
+    /// The class itself or the ancestor named `name`.
+    pub fn ancestor(self: &Rc<Self>, name: &str) -> Option<Rc<Class>> {
+        let mut current = Some(Rc::clone(self));
+        while let Some(class) = current {
+            if class.name == name {
+                return Some(class);
+            }
+            current = class.parent.clone();
+        }
+        None
+    }
+
+    /// The class declaring the field `name` (this class or an ancestor) and its declaration.
+    pub fn field(&self, name: &str) -> Option<(&Class, &Param)> {
+        match self.fields.iter().find(|field| field.name == name) {
+            Some(field) => Some((self, field)),
+            None => self.parent.as_ref()?.field(name),
+        }
+    }
+
+    /// The class declaring the static `name` (this class or an ancestor) and its declaration.
+    pub fn static_field(&self, name: &str) -> Option<(&Class, &Param)> {
+        match self.static_fields.iter().find(|field| field.name == name) {
+            Some(field) => Some((self, field)),
+            None => self.parent.as_ref()?.static_field(name),
+        }
+    }
+
+    /// The value of the static `name`, of this class or an ancestor.
+    pub fn get_static(&self, name: &str) -> Option<Value> {
+        let (class, _) = self.static_field(name)?;
+        class.statics.borrow().get(name).cloned()
+    }
+
+    /// Every field of an instance, the root ancestor's first.
+    fn all_fields(&self) -> Vec<String> {
+        let mut fields = self.parent.as_ref().map_or(vec![], |parent| parent.all_fields());
+        fields.extend(self.fields.iter().map(|field| field.name.clone()));
+        fields
+    }
+}
+
+impl fmt::Debug for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Statics may refer back to the class, so only its name is shown
+        write!(f, "Class({:?})", self.name)
+    }
+}
+
+impl PartialEq for Class {
+    /// Classes are equal only when they are the same class.
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// An instance of a class.
+///
+/// Clones refer to the same instance, so a change through one is seen by all.
+#[derive(Clone)]
+pub struct ObjectRef(Rc<RefCell<Object>>);
+
+struct Object {
+    class: Rc<Class>,
+    /// Field values in declaration order.
+    fields: Vec<(String, Value)>,
+}
+
+impl ObjectRef {
+    /// Creates an instance of `class` with every field `none`.
+    pub fn new(class: Rc<Class>) -> Self {
+        let fields = class.all_fields().into_iter().map(|name| (name, Value::None)).collect();
+        ObjectRef(Rc::new(RefCell::new(Object { class, fields })))
+    }
+
+    pub fn class(&self) -> Rc<Class> {
+        Rc::clone(&self.0.borrow().class)
+    }
+
+    /// The value of the field `name`, if the instance has that field.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.0.borrow().fields.iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Sets the field `name` and returns whether the instance has that field.
+    pub fn set(&self, name: &str, value: Value) -> bool {
+        match self.0.borrow_mut().fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, slot)) => {
+                *slot = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl PartialEq for ObjectRef {
+    /// Instances are equal only when they are the same instance.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Fields may refer back to the instance, so only the class is shown
+        write!(f, "ObjectRef({:?})", self.0.borrow().class.name)
+    }
+}
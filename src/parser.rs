@@ -1,17 +1,34 @@
-use crate::ast::{Expr, Stmt, BinOp};
-use crate::lexer::Token;
+use crate::ast::{ClassDecl, EnumMember, Expr, ExceptClause, FStringPart, FuncDecl, Import, KeywordArgs, MatchArm, Param, Pattern, Stmt, SwitchCase, BinOp, UnaryOp};
+use crate::lexer::{self, FStrPart, Spanned, Token, Trivia, TriviaKind};
 use crate::error::PalladError;
+use crate::exception;
 
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<Spanned>,
     pos: usize,
+    /// Whether the statements being parsed are inside an `except` block, where a bare `raise`
+    /// re-raises the exception being handled.
+    in_except: bool,
+    /// Whether the statements being parsed are inside a function body, where `return` is allowed.
+    in_function: bool,
+    /// Whether the statements being parsed are inside a constructor, whose `return` can't have a
+    /// value.
+    in_constructor: bool,
+    /// Comments and docstrings of the source, for the docs of declarations.
+    trivia: Vec<Trivia>,
+}
+
+/// The `class Name` and `extends Parent` headers starting a class module.
+struct ClassHeader {
+    name: Option<String>,
+    parent: Option<String>,
     line: usize,
 }
 
 impl Parser {
     /// Create a new `Parser` for the given token stream.
     ///
-    /// Initializes the parser with the provided tokens, sets the current position to 0, and starts the line counter at 1 for error reporting.
+    /// Initializes the parser with the provided tokens and sets the current position to 0.
     ///
     /// # Examples
     ///
@@ -20,8 +37,22 @@ impl Parser {
     /// // empty input produces no statements
     /// assert_eq!(parser.parse().unwrap().len(), 0);
     /// ```
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0, line: 1 }
+    pub fn new(tokens: Vec<Spanned>) -> Self {
+        Self { tokens, pos: 0, in_except: false, in_function: false, in_constructor: false, trivia: vec![] }
+    }
+
+    /// Create a `Parser` that attaches the docstring or `#` comments right above each declaration
+    /// to it as its `doc`, from the `trivia` of `lexer::tokenize_with_trivia`.
+    pub fn with_trivia(tokens: Vec<Spanned>, trivia: Vec<Trivia>) -> Self {
+        Self { trivia, ..Self::new(tokens) }
+    }
+
+    /// The doc of a declaration starting at `line`: the docstring or `#` comment lines ending on
+    /// the line above it.
+    fn doc(&self, line: usize) -> Option<String> {
+        self.trivia.iter()
+            .find(|trivia| matches!(trivia.kind, TriviaKind::Comment | TriviaKind::Docstring) && trivia.end_line + 1 == line)
+            .map(|trivia| trivia.text.clone())
     }
 
     /// Get a reference to the token at the parser's current position, if one exists.
@@ -32,29 +63,23 @@ impl Parser {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// let parser = Parser::new(vec![]);
     /// assert!(parser.current().is_none());
     /// ```
     fn current(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    /// Source line of the current token (or of the last token once the input is exhausted), for error reporting.
+    fn line(&self) -> usize {
         self.tokens.get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |t| t.line)
     }
 
-    /// Advance the parser to the next token, incrementing `line` when the current token is `Token::Eol`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// let mut p = Parser::new(vec![Token::Eol, Token::Int(1)]);
-    /// assert_eq!(p.line, 1);
-    /// p.advance();
-    /// assert_eq!(p.line, 2);
-    /// assert_eq!(p.current(), Some(&Token::Int(1)));
-    /// ```
+    /// Advance the parser to the next token.
     fn advance(&mut self) {
-        if let Some(Token::Eol) = self.current() {
-            self.line += 1;
-        }
         self.pos += 1;
     }
 
@@ -62,8 +87,25 @@ impl Parser {
     ///
     /// The parser consumes tokens until the end of input and produces a vector of `Stmt`:
     /// 
-    /// - `var <ident> = <expr>` produces `Stmt::Let { name, expr }`
-    /// - `print(...)` produces `Stmt::Expr(Expr::Call { name: "print", args })`
+    /// - `var <ident>[: <type>] = <expr>` produces `Stmt::Let { name, ty, expr, line, doc }`
+    /// - `print(...)` produces `Stmt::Expr(Expr::Call { name: "print", args, kwargs })`
+    /// - `with <expr> [as <ident>]:` and an indented block produces `Stmt::With { expr, name, body }`
+    /// - `try:` with `except`, `else` and `finally` blocks produces `Stmt::Try { .. }`
+    /// - `raise [<expr>]` produces `Stmt::Raise(expr)`
+    /// - `assert(<cond>[, <message>])` produces `Stmt::Assert { condition, message }`
+    /// - `switch <expr>:` with `case` and `default` blocks produces `Stmt::Switch { .. }`
+    /// - `match <expr>:` with `case <pattern> [if <guard>]:` and `default` blocks produces `Stmt::Match { .. }`
+    /// - `func <ident>(<params>) [-> <type>]:` and an indented block produces `Stmt::Func(..)`
+    /// - `return [<expr>]` produces `Stmt::Return(expr)`
+    /// - `signal <ident>(<params>)` produces `Stmt::Signal { name, params, line, doc }`
+    /// - `class <ident> [extends <ident>]:` and an indented block of fields, methods and constructors produces `Stmt::Class(..)`
+    /// - `<target> = <expr>`, for a variable or an attribute target, produces `Stmt::Assign { .. }`
+    /// - `if <expr>:`, `elif <expr>:` and `else:` blocks produce `Stmt::If { branches, else_body }`
+    /// - `import <module> [as <ident>]`, `import *` and `from <module> import <names>` produce `Stmt::Import(..)`
+    /// - `exclude <module>, ...` produces `Stmt::Exclude { paths, line }`
+    /// - `pass` does nothing, for empty blocks
+    /// - `[@anonymous_shadow] enum [<ident>]:` and an indented list of members produces `Stmt::Enum { .. }`
+    /// - any other expression (e.g. `f.write("text")`) produces `Stmt::Expr(expr)`
     /// 
     /// Empty lines (Eol) are skipped. Syntax errors and premature end-of-input produce `PalladError`.
     ///
@@ -74,11 +116,10 @@ impl Parser {
     /// # Examples
     ///
     /// ```
-    /// use crate::lexer::Token;
-    /// use crate::parser::Parser;
+    /// use pallad::lexer::tokenize;
+    /// use pallad::parser::Parser;
     ///
-    /// // tokens for: var x = 42
-    /// let tokens = vec![Token::Var, Token::Ident("x".to_string()), Token::Eq, Token::Int(42), Token::Eol];
+    /// let tokens = tokenize("var x = 42").unwrap();
     /// let mut parser = Parser::new(tokens);
     /// let stmts = parser.parse().unwrap();
     /// assert_eq!(stmts.len(), 1);
@@ -86,114 +127,869 @@ impl Parser {
     pub fn parse(&mut self) -> Result<Vec<Stmt>, PalladError> {
         let mut stmts = vec![];
 
-        while let Some(tok) = self.current() {
-            match tok {
-                Token::Var => {
+        while self.current().is_some() {
+            if let Some(stmt) = self.parse_statement()? {
+                stmts.push(stmt);
+            }
+        }
+
+        Ok(stmts)
+    }
+
+    /// Parses a module's file, like `parse`. A file starting with a `class Name` header (no `:`)
+    /// or an `extends Parent` header is a class module: the rest of the file is the body of the
+    /// class, named `name` (the file's name) unless the header names it. Its imports run first,
+    /// as the module's own.
+    pub fn parse_module(&mut self, name: Option<&str>) -> Result<Vec<Stmt>, PalladError> {
+        let Some(ClassHeader { name: class_name, parent, line }) = self.parse_class_headers()? else {
+            return self.parse();
+        };
+        let mut stmts = vec![];
+        while self.current().is_some() {
+            if let Some(stmt) = self.parse_member()? {
+                stmts.push(stmt);
+            }
+        }
+        let name = class_name.or_else(|| name.map(str::to_string)).ok_or(PalladError::InvalidClassHeader {
+            reason: "needs a class name when the source isn't read from a file",
+            line,
+        })?;
+        let (mut imports, body): (Vec<Stmt>, Vec<Stmt>) = stmts.into_iter()
+            .partition(|stmt| matches!(stmt, Stmt::Import(_) | Stmt::Exclude { .. }));
+        imports.push(Stmt::Class(ClassDecl { name, parent, body, line, header: true, doc: self.doc(line) }));
+        Ok(imports)
+    }
+
+    /// Parses the headers starting a class module, if any. The line is the first header's.
+    fn parse_class_headers(&mut self) -> Result<Option<ClassHeader>, PalladError> {
+        let (mut name, mut parent, mut line) = (None, None, None);
+        loop {
+            match self.current() {
+                Some(Token::Eol) => {
                     self.advance();
-                    let var_name = match self.current() {
-                        Some(Token::Ident(name)) => {
-                            let n = name.clone();
-                            self.advance();
-                            n
-                        }
-                        Some(other) => {
-                            return Err(PalladError::UnexpectedToken {
-                                got: format!("{:?}", other),
-                                expected: "identifier".to_string(),
-                                line: self.line,
-                            });
-                        }
-                        None => {
-                            return Err(PalladError::EndOfInput {
-                                expected: "identifier".to_string(),
-                                line: self.line,
-                            });
+                    continue;
+                }
+                // `class Name:` declares a class in the file instead
+                Some(Token::Class) if name.is_none() && !self.line_has(&Token::Colon) => {
+                    line.get_or_insert(self.line());
+                    self.advance();
+                    name = Some(self.expect_ident("class name")?);
+                    if self.current() == Some(&Token::Extends) {
+                        self.advance();
+                        parent = Some(self.parse_parent()?);
+                    }
+                }
+                Some(Token::Extends) if parent.is_none() => {
+                    line.get_or_insert(self.line());
+                    self.advance();
+                    parent = Some(self.parse_parent()?);
+                }
+                _ => break,
+            }
+            if let Some(other) = self.current().filter(|token| **token != Token::Eol) {
+                return Err(PalladError::UnexpectedToken {
+                    got: format!("{:?}", other),
+                    expected: "end of line".to_string(),
+                    line: self.line(),
+                });
+            }
+        }
+        Ok(line.map(|line| ClassHeader { name, parent, line }))
+    }
+
+    /// Whether `token` comes before the end of the current line.
+    fn line_has(&self, token: &Token) -> bool {
+        self.tokens[self.pos..].iter()
+            .map(|spanned| &spanned.token)
+            .take_while(|current| **current != Token::Eol)
+            .any(|current| current == token)
+    }
+
+    /// Parses the parent class's name after `extends`, qualified for an inner class of another
+    /// class (`extends Utils.Counter`).
+    fn parse_parent(&mut self) -> Result<String, PalladError> {
+        let mut parent = self.expect_ident("parent class name")?;
+        while let Some(Token::Dot) = self.current() {
+            self.advance();
+            parent = format!("{}.{}", parent, self.expect_ident("inner class name")?);
+        }
+        Ok(parent)
+    }
+
+    /// Parses one statement, or skips an empty line and returns `None`.
+    fn parse_statement(&mut self) -> Result<Option<Stmt>, PalladError> {
+        let Some(tok) = self.current() else {
+            return Err(PalladError::EndOfInput {
+                expected: "statement".to_string(),
+                line: self.line(),
+            });
+        };
+
+        match tok {
+            Token::Var => {
+                let line = self.line();
+                self.advance();
+                let var_name = match self.current() {
+                    Some(Token::Ident(name)) => {
+                        let n = name.clone();
+                        self.advance();
+                        n
+                    }
+                    Some(other) => {
+                        return Err(PalladError::UnexpectedToken {
+                            got: format!("{:?}", other),
+                            expected: "identifier".to_string(),
+                            line: self.line(),
+                        });
+                    }
+                    None => {
+                        return Err(PalladError::EndOfInput {
+                            expected: "identifier".to_string(),
+                            line: self.line(),
+                        });
+                    }
+                };
+
+                let ty = match self.current() {
+                    Some(Token::Colon) => {
+                        self.advance();
+                        Some(self.expect_ident("type name")?)
+                    }
+                    _ => None,
+                };
+
+                let expr = match self.current() {
+                    Some(Token::Eq) => {
+                        self.advance();
+                        self.parse_expr()?
+                    }
+                    Some(Token::Eol) => { Expr::None }
+                    Some(other) => {
+                        return Err(PalladError::UnexpectedToken {
+                            got: format!("{:?}", other),
+                            expected: "'=' or end of line".to_string(),
+                            line: self.line(),
+                        });
+                    }
+                    None => {
+                        return Err(PalladError::EndOfInput {
+                            expected: "'=' or end of line".to_string(),
+                            line: self.line(),
+                        });
+                    }
+                };
+                Ok(Some(Stmt::Let { name: var_name, ty, expr, line, doc: self.doc(line) }))
+            }
+
+            Token::Print => {
+                self.advance();
+                self.expect(Token::LParen, "'('")?;
+                let (args, kwargs) = self.parse_call_args()?;
+                Ok(Some(Stmt::Expr(Expr::Call { name: "print".to_string(), args, kwargs })))
+            }
+
+            Token::With => {
+                self.advance();
+                let expr = self.parse_expr()?;
+                let name = match self.current() {
+                    Some(Token::As) => {
+                        self.advance();
+                        Some(self.expect_ident("identifier")?)
+                    }
+                    _ => None,
+                };
+                let body = self.parse_block()?;
+                Ok(Some(Stmt::With { expr, name, body }))
+            }
+
+            Token::Switch => {
+                self.advance();
+                self.parse_switch().map(Some)
+            }
+
+            Token::Match => {
+                self.advance();
+                self.parse_match().map(Some)
+            }
+
+            Token::Pass => { self.advance(); Ok(None) }
+
+            Token::If => {
+                self.advance();
+                self.parse_if().map(Some)
+            }
+
+            Token::Import => {
+                let line = self.line();
+                self.advance();
+                if self.current() == Some(&Token::Star) {
+                    self.advance();
+                    return Ok(Some(Stmt::Import(Import { path: "./".to_string(), alias: None, names: None, line })));
+                }
+                let path = self.parse_module_path()?;
+                let alias = match self.current() {
+                    Some(Token::As) if path.ends_with('/') => {
+                        return Err(PalladError::UnexpectedToken {
+                            got: "As".to_string(),
+                            expected: "end of line (a folder's modules keep their names)".to_string(),
+                            line: self.line(),
+                        });
+                    }
+                    Some(Token::As) => {
+                        self.advance();
+                        Some(self.expect_ident("module alias")?)
+                    }
+                    _ => None,
+                };
+                Ok(Some(Stmt::Import(Import { path, alias, names: None, line })))
+            }
+
+            Token::From => {
+                let line = self.line();
+                self.advance();
+                let path = self.parse_module_path()?;
+                self.expect(Token::Import, "'import'")?;
+                let mut names = vec![];
+                if self.current() == Some(&Token::Star) {
+                    self.advance();
+                } else {
+                    loop {
+                        let name = self.expect_ident("name to import")?;
+                        let alias = match self.current() {
+                            Some(Token::As) => {
+                                self.advance();
+                                Some(self.expect_ident("alias")?)
+                            }
+                            _ => None,
+                        };
+                        names.push((name, alias));
+                        if self.current() != Some(&Token::Comma) {
+                            break;
                         }
-                    };
+                        self.advance();
+                    }
+                }
+                Ok(Some(Stmt::Import(Import { path, alias: None, names: Some(names), line })))
+            }
+
+            Token::Exclude => {
+                let line = self.line();
+                self.advance();
+                let mut paths = vec![self.parse_module_path()?];
+                while self.current() == Some(&Token::Comma) {
+                    self.advance();
+                    paths.push(self.parse_module_path()?);
+                }
+                Ok(Some(Stmt::Exclude { paths, line }))
+            }
+
+            Token::Try => {
+                self.advance();
+                self.parse_try().map(Some)
+            }
+
+            Token::Raise => {
+                let line = self.line();
+                self.advance();
+                if matches!(self.current(), Some(Token::Eol | Token::Dedent) | None) {
+                    if !self.in_except {
+                        return Err(PalladError::BareRaise { line });
+                    }
+                    return Ok(Some(Stmt::Raise(None)));
+                }
+                Ok(Some(Stmt::Raise(Some(self.parse_expr()?))))
+            }
+
+            Token::Func => {
+                let line = self.line();
+                self.advance();
+                let name = self.expect_ident("function name")?;
+                let params = self.parse_params()?;
+                let returns = match self.current() {
+                    Some(Token::Arrow) => {
+                        self.advance();
+                        Some(self.expect_ident("return type")?)
+                    }
+                    _ => None,
+                };
+                let outer = (self.in_except, self.in_function, self.in_constructor);
+                (self.in_except, self.in_function, self.in_constructor) = (false, true, false);
+                let body = self.parse_block();
+                (self.in_except, self.in_function, self.in_constructor) = outer;
+                Ok(Some(Stmt::Func(FuncDecl { name, params, returns, body: body?, line, doc: self.doc(line) })))
+            }
+
+            Token::Return => {
+                let line = self.line();
+                self.advance();
+                if !self.in_function {
+                    return Err(PalladError::ReturnOutsideFunction { line });
+                }
+                if matches!(self.current(), Some(Token::Eol | Token::Dedent) | None) {
+                    return Ok(Some(Stmt::Return(None)));
+                }
+                if self.in_constructor {
+                    return Err(PalladError::ReturnInConstructor { line });
+                }
+                Ok(Some(Stmt::Return(Some(self.parse_expr()?))))
+            }
+
+            Token::Signal => {
+                let line = self.line();
+                self.advance();
+                let name = self.expect_ident("signal name")?;
+                let params = match self.current() {
+                    Some(Token::LParen) => self.parse_params()?,
+                    _ => vec![],
+                };
+                Ok(Some(Stmt::Signal { name, params, line, doc: self.doc(line) }))
+            }
+
+            Token::Class => {
+                let line = self.line();
+                self.advance();
+                let name = self.expect_ident("class name")?;
+                let parent = match self.current() {
+                    Some(Token::Extends) => {
+                        self.advance();
+                        Some(self.parse_parent()?)
+                    }
+                    _ => None,
+                };
+                if matches!(self.current(), Some(Token::Eol) | None) {
+                    return Err(PalladError::InvalidClassHeader { reason: "must start the file, which declares one class", line });
+                }
+                let body = self.parse_class_body()?;
+                Ok(Some(Stmt::Class(ClassDecl { name, parent, body, line, header: false, doc: self.doc(line) })))
+            }
+
+            Token::Extends => Err(PalladError::InvalidClassHeader {
+                reason: "must start the file, which declares one class",
+                line: self.line(),
+            }),
+
+            Token::Assert => {
+                let line = self.line();
+                self.advance();
+                self.expect(Token::LParen, "'('")?;
+                let mut args = self.parse_list(Token::RParen, "')'")?.into_iter();
+                match (args.next(), args.next(), args.next()) {
+                    (Some(condition), message, None) => Ok(Some(Stmt::Assert { condition, message })),
+                    _ => Err(PalladError::UnexpectedToken {
+                        got: "a different number of arguments".to_string(),
+                        expected: "a condition and an optional message".to_string(),
+                        line,
+                    }),
+                }
+            }
+
+            Token::At => {
+                let line = self.line();
+                self.advance();
+                let annotation = self.expect_ident("annotation name")?;
+                if annotation != "anonymous_shadow" {
+                    return Err(PalladError::InvalidAnnotation { name: annotation, reason: "is not a known annotation", line });
+                }
+                while self.current() == Some(&Token::Eol) {
+                    self.advance();
+                }
+                match self.parse_statement()? {
+                    Some(Stmt::Enum { name: Some(name), members, line: enum_line, doc, .. }) => {
+                        let doc = doc.or_else(|| self.doc(line));
+                        Ok(Some(Stmt::Enum { name: Some(name), members, shadow: true, line: enum_line, doc }))
+                    }
+                    _ => Err(PalladError::InvalidAnnotation { name: annotation, reason: "only applies to named enums", line }),
+                }
+            }
+
+            Token::Enum => {
+                let line = self.line();
+                self.advance();
+                let name = match self.current() {
+                    Some(Token::Ident(_)) => Some(self.expect_ident("enum name")?),
+                    _ => None,
+                };
+                let members = self.parse_enum_members()?;
+                Ok(Some(Stmt::Enum { name, members, shadow: false, line, doc: self.doc(line) }))
+            }
+
+            Token::Eol => { self.advance(); Ok(None) }
+
+            _ => {
+                let line = self.line();
+                let expr = self.parse_expr()?;
+                match self.current() {
+                    Some(Token::Eol) | None => Ok(Some(Stmt::Expr(expr))),
+                    Some(Token::Eq) if matches!(expr, Expr::Var(_) | Expr::Attribute { .. }) => {
+                        self.advance();
+                        let value = self.parse_expr()?;
+                        Ok(Some(Stmt::Assign { target: expr, expr: value, line }))
+                    }
+                    Some(other) => Err(PalladError::UnexpectedToken {
+                        got: format!("{:?}", other),
+                        expected: "end of line".to_string(),
+                        line: self.line(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Parses the condition and block of an `if` (after the keyword), then any `elif` branches and
+    /// an `else` block.
+    fn parse_if(&mut self) -> Result<Stmt, PalladError> {
+        let mut branches = vec![];
+        loop {
+            let condition = self.parse_expr()?;
+            let body = self.parse_block()?;
+            branches.push((condition, body));
+            if self.current() != Some(&Token::Elif) {
+                break;
+            }
+            self.advance();
+        }
+        let else_body = match self.current() {
+            Some(Token::Else) => {
+                self.advance();
+                Some(self.parse_block()?)
+            }
+            _ => None,
+        };
+        Ok(Stmt::If { branches, else_body })
+    }
+
+    /// Parses the module of an `import`, `from` or `exclude`: a name (`Utils`) or a quoted path.
+    fn parse_module_path(&mut self) -> Result<String, PalladError> {
+        match self.current() {
+            Some(Token::Str(path)) => {
+                let path = path.clone();
+                self.advance();
+                Ok(path)
+            }
+            _ => self.expect_ident("module name or path"),
+        }
+    }
+
+    /// Parses `:`, a line break, and the indented body of a class: fields (`var`), methods (`func`),
+    /// constructors, static members (`static var`, `static func`, `static constructor -> bool:`),
+    /// `static:` blocks, inner classes, and any other statements as free code.
+    fn parse_class_body(&mut self) -> Result<Vec<Stmt>, PalladError> {
+        self.expect(Token::Colon, "':'")?;
+        self.expect(Token::Eol, "end of line")?;
+        self.expect(Token::Indent, "indented class body")?;
+        let mut body = vec![];
+
+        loop {
+            match self.current() {
+                Some(Token::Dedent) => { self.advance(); break; }
+                None => break,
+                Some(_) => {
+                    if let Some(stmt) = self.parse_member()? {
+                        body.push(stmt);
+                    }
+                }
+            }
+        }
+
+        Ok(body)
+    }
+
+    /// Parses a statement of a class body, where constructors and static members are allowed too.
+    fn parse_member(&mut self) -> Result<Option<Stmt>, PalladError> {
+        match self.current() {
+            Some(Token::Constructor) => self.parse_constructor().map(Some),
+            Some(Token::Static) => self.parse_static().map(Some),
+            _ => self.parse_statement(),
+        }
+    }
+
+    /// Parses a static member of a class: `static var`, `static func`, `static constructor -> bool:`
+    /// and its body, or a `static:` block.
+    fn parse_static(&mut self) -> Result<Stmt, PalladError> {
+        let line = self.line();
+        self.advance();
+        match self.current() {
+            Some(Token::Var | Token::Func) => {
+                let member = self.parse_statement()?
+                    .ok_or(PalladError::EndOfInput { expected: "static member".to_string(), line })?;
+                Ok(Stmt::Static(Box::new(member)))
+            }
+            Some(Token::Constructor) => {
+                self.advance();
+                if let Some(Token::Arrow) = self.current() {
+                    self.advance();
+                    let ty = self.expect_ident("return type")?;
+                    if ty != "bool" {
+                        return Err(PalladError::UnexpectedToken { got: ty, expected: "bool".to_string(), line });
+                    }
+                }
+                let outer = (self.in_except, self.in_function, self.in_constructor);
+                (self.in_except, self.in_function, self.in_constructor) = (false, true, false);
+                let body = self.parse_block();
+                (self.in_except, self.in_function, self.in_constructor) = outer;
+                let decl = FuncDecl { name: "constructor".to_string(), params: vec![], returns: Some("bool".to_string()), body: body?, line, doc: self.doc(line) };
+                Ok(Stmt::Static(Box::new(Stmt::Constructor(decl))))
+            }
+            Some(Token::Colon) => Ok(Stmt::StaticBlock(self.parse_block()?)),
+            Some(other) => Err(PalladError::UnexpectedToken {
+                got: format!("{:?}", other),
+                expected: "'var', 'func', 'constructor' or ':' after 'static'".to_string(),
+                line: self.line(),
+            }),
+            None => Err(PalladError::EndOfInput { expected: "static member".to_string(), line }),
+        }
+    }
+
+    /// Parses `constructor(<params>):` and its body.
+    fn parse_constructor(&mut self) -> Result<Stmt, PalladError> {
+        let line = self.line();
+        self.advance();
+        let params = self.parse_params()?;
+        let outer = (self.in_except, self.in_function, self.in_constructor);
+        (self.in_except, self.in_function, self.in_constructor) = (false, true, true);
+        let body = self.parse_block();
+        (self.in_except, self.in_function, self.in_constructor) = outer;
+        Ok(Stmt::Constructor(FuncDecl { name: "constructor".to_string(), params, returns: None, body: body?, line, doc: self.doc(line) }))
+    }
+
+    /// Parses a parenthesized, comma-separated list of parameters, each `name` or `name: type`.
+    fn parse_params(&mut self) -> Result<Vec<Param>, PalladError> {
+        self.expect(Token::LParen, "'('")?;
+        let mut params: Vec<Param> = vec![];
+        loop {
+            if self.current() == Some(&Token::RParen) {
+                self.advance();
+                return Ok(params);
+            }
+            let line = self.line();
+            let name = self.expect_ident("parameter name")?;
+            if params.iter().any(|param| param.name == name) {
+                return Err(PalladError::DuplicateName { name, line });
+            }
+            let ty = match self.current() {
+                Some(Token::Colon) => {
+                    self.advance();
+                    Some(self.expect_ident("type name")?)
+                }
+                _ => None,
+            };
+            params.push(Param { name, ty });
+            if self.end_of_item(&Token::RParen, "')'")? {
+                return Ok(params);
+            }
+        }
+    }
 
-                    let expr = match self.current() {
+    /// Parses `:`, a line break, and the indented, comma-separated members of an enum, each with an
+    /// optional `= value`. The comma after the last member is optional.
+    fn parse_enum_members(&mut self) -> Result<Vec<EnumMember>, PalladError> {
+        self.expect(Token::Colon, "':'")?;
+        self.expect(Token::Eol, "end of line")?;
+        self.expect(Token::Indent, "indented enum members")?;
+        let mut members = vec![];
+
+        loop {
+            match self.current() {
+                Some(Token::Dedent) => { self.advance(); break; }
+                None => break,
+                Some(Token::Eol) => { self.advance(); }
+                _ => {
+                    let line = self.line();
+                    let name = self.expect_ident("enum member")?;
+                    let value = match self.current() {
                         Some(Token::Eq) => {
                             self.advance();
-                            self.parse_expr()?
-                        }
-                        Some(Token::Eol) => { Expr::None }
-                        Some(other) => {
-                            return Err(PalladError::UnexpectedToken {
-                                got: format!("{:?}", other),
-                                expected: "'=' or end of line".to_string(),
-                                line: self.line,
-                            });
-                        }
-                        None => {
-                            return Err(PalladError::EndOfInput {
-                                expected: "'=' or end of line".to_string(),
-                                line: self.line,
-                            });
+                            Some(self.parse_expr()?)
                         }
+                        _ => None,
                     };
-                    stmts.push(Stmt::Let { name: var_name, expr });
+                    members.push(EnumMember { name, value, line });
+                    match self.current() {
+                        Some(Token::Comma) => self.advance(),
+                        Some(Token::Eol | Token::Dedent) | None => {}
+                        Some(other) => return Err(PalladError::UnexpectedToken {
+                            got: format!("{:?}", other),
+                            expected: "',' or end of line".to_string(),
+                            line: self.line(),
+                        }),
+                    }
                 }
+            }
+        }
 
-                Token::Print => {
+        Ok(members)
+    }
+
+    /// Parses a `switch` statement after its keyword: the subject, then an indented list of
+    /// `case value, ...:` blocks and at most one `default:` block.
+    fn parse_switch(&mut self) -> Result<Stmt, PalladError> {
+        let subject = self.parse_expr()?;
+        self.expect(Token::Colon, "':'")?;
+        self.expect(Token::Eol, "end of line")?;
+        self.expect(Token::Indent, "indented 'case' blocks")?;
+        let mut cases = vec![];
+        let mut default = None;
+
+        loop {
+            match self.current() {
+                Some(Token::Dedent) => { self.advance(); break; }
+                None => break,
+                Some(Token::Eol) => self.advance(),
+                Some(Token::Case) => {
+                    let line = self.line();
                     self.advance();
-                    match self.current() {
-                        Some(Token::LParen) => self.advance(),
-                        Some(other) => {
-                            return Err(PalladError::UnexpectedToken {
-                                got: format!("{:?}", other),
-                                expected: "'('".to_string(),
-                                line: self.line,
-                            });
-                        }
-                        None => {
-                            return Err(PalladError::EndOfInput {
-                                expected: "'('".to_string(),
-                                line: self.line,
-                            });
-                        }
+                    let mut values = vec![self.parse_expr()?];
+                    while self.current() == Some(&Token::Comma) {
+                        self.advance();
+                        values.push(self.parse_expr()?);
                     }
+                    let body = self.parse_block()?;
+                    cases.push(SwitchCase { values, body, line });
+                }
+                Some(Token::Default) if default.is_none() => {
+                    self.advance();
+                    default = Some(self.parse_block()?);
+                }
+                Some(other) => return Err(PalladError::UnexpectedToken {
+                    got: format!("{:?}", other),
+                    expected: if default.is_none() { "'case' or 'default'" } else { "'case'" }.to_string(),
+                    line: self.line(),
+                }),
+            }
+        }
+
+        Ok(Stmt::Switch { subject, cases, default })
+    }
 
-                    let mut args = vec![];
-                    if let Some(Token::RParen) = self.current() {
+    /// Parses a `match` statement after its keyword: the subject, then an indented list of
+    /// `case pattern [if guard]:` blocks and at most one `default:` block.
+    fn parse_match(&mut self) -> Result<Stmt, PalladError> {
+        let line = self.line();
+        let subject = self.parse_expr()?;
+        self.expect(Token::Colon, "':'")?;
+        self.expect(Token::Eol, "end of line")?;
+        self.expect(Token::Indent, "indented 'case' blocks")?;
+        let mut arms = vec![];
+        let mut default = None;
+
+        loop {
+            match self.current() {
+                Some(Token::Dedent) => { self.advance(); break; }
+                None => break,
+                Some(Token::Eol) => self.advance(),
+                Some(Token::Case) => {
+                    let line = self.line();
+                    self.advance();
+                    let mut alternatives = vec![self.parse_or_pattern()?];
+                    while self.current() == Some(&Token::Comma) {
                         self.advance();
-                    } else {
-                        loop {
-                            if let Some(Token::RParen) = self.current() {
-                                self.advance();
-                                break;
-                            }
-                            args.push(self.parse_expr()?);
-                            match self.current() {
-                                Some(Token::Comma) => { self.advance(); }
-                                Some(Token::RParen) => { self.advance(); break; }
-                                Some(other) => {
-                                    return Err(PalladError::UnexpectedToken {
-                                        got: format!("{:?}", other),
-                                        expected: "',' or ')'".to_string(),
-                                        line: self.line,
-                                    });
-                                }
-                                None => {
-                                    return Err(PalladError::EndOfInput {
-                                        expected: "',' or ')'".to_string(),
-                                        line: self.line,
-                                    });
-                                }
-                            }
+                        alternatives.push(self.parse_or_pattern()?);
+                    }
+                    let pattern = match alternatives.len() {
+                        1 => alternatives.remove(0),
+                        _ => Pattern::Or(alternatives),
+                    };
+                    let guard = match self.current() {
+                        Some(Token::If) => {
+                            self.advance();
+                            Some(self.parse_expr()?)
                         }
+                        _ => None,
+                    };
+                    let body = self.parse_block()?;
+                    arms.push(MatchArm { pattern, guard, body, line });
+                }
+                Some(Token::Default) if default.is_none() => {
+                    self.advance();
+                    default = Some(self.parse_block()?);
+                }
+                Some(other) => return Err(PalladError::UnexpectedToken {
+                    got: format!("{:?}", other),
+                    expected: if default.is_none() { "'case' or 'default'" } else { "'case'" }.to_string(),
+                    line: self.line(),
+                }),
+            }
+        }
+
+        Ok(Stmt::Match { subject, arms, default, line })
+    }
+
+    /// Parses patterns separated by `|`.
+    fn parse_or_pattern(&mut self) -> Result<Pattern, PalladError> {
+        let mut alternatives = vec![self.parse_pattern()?];
+        while self.current() == Some(&Token::Pipe) {
+            self.advance();
+            alternatives.push(self.parse_pattern()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Pattern::Or(alternatives),
+        })
+    }
+
+    /// Parses one pattern: `_`, a name, an enum member, a literal or `start..end` range, or a
+    /// tuple, array or dict of patterns. `(p)` without a comma is just `p`.
+    fn parse_pattern(&mut self) -> Result<Pattern, PalladError> {
+        match self.current().cloned() {
+            Some(Token::Ident(name)) if name == "_" => { self.advance(); Ok(Pattern::Wildcard) }
+            Some(Token::Ident(name)) => {
+                let line = self.line();
+                self.advance();
+                if self.current() != Some(&Token::Dot) {
+                    return Ok(Pattern::Bind(name));
+                }
+                self.advance();
+                let member = self.expect_ident("enum member")?;
+                Ok(Pattern::Value(Expr::Attribute { target: Box::new(Expr::Var(name)), name: member, line }))
+            }
+            Some(Token::LParen) => {
+                self.advance();
+                let mut items = vec![];
+                loop {
+                    if self.current() == Some(&Token::RParen) {
+                        self.advance();
+                        return Ok(Pattern::Tuple(items));
+                    }
+                    items.push(self.parse_or_pattern()?);
+                    if self.end_of_item(&Token::RParen, "')'")? {
+                        return Ok(match items.len() {
+                            1 => items.remove(0),
+                            _ => Pattern::Tuple(items),
+                        });
+                    }
+                }
+            }
+            Some(Token::LBracket) => {
+                self.advance();
+                let mut items = vec![];
+                loop {
+                    if self.current() == Some(&Token::RBracket) {
+                        self.advance();
+                        return Ok(Pattern::Array(items));
                     }
+                    items.push(self.parse_or_pattern()?);
+                    if self.end_of_item(&Token::RBracket, "']'")? {
+                        return Ok(Pattern::Array(items));
+                    }
+                }
+            }
+            Some(Token::LBrace) => {
+                self.advance();
+                let mut entries = vec![];
+                loop {
+                    if self.current() == Some(&Token::RBrace) {
+                        self.advance();
+                        return Ok(Pattern::Dict(entries));
+                    }
+                    let key = self.parse_primary()?;
+                    self.expect(Token::Colon, "':'")?;
+                    entries.push((key, self.parse_or_pattern()?));
+                    if self.end_of_item(&Token::RBrace, "'}'")? {
+                        return Ok(Pattern::Dict(entries));
+                    }
+                }
+            }
+            _ => {
+                let start = self.parse_factor()?;
+                if self.current() != Some(&Token::DotDot) {
+                    return Ok(Pattern::Value(start));
+                }
+                self.advance();
+                let end = self.parse_factor()?;
+                Ok(Pattern::Range { start, end })
+            }
+        }
+    }
 
-                    stmts.push(Stmt::Expr(Expr::Call { name: "print".to_string(), args }));
+    /// Parses the blocks of a `try` statement after the `try` keyword: the body, then any `except`
+    /// clauses, an `else` block, and a `finally` block. At least one `except` or `finally` is
+    /// required, and `else` only follows `except` clauses.
+    fn parse_try(&mut self) -> Result<Stmt, PalladError> {
+        let body = self.parse_block()?;
+
+        let mut handlers = vec![];
+        while self.current() == Some(&Token::Except) {
+            self.advance();
+            let mut classes = vec![];
+            if !matches!(self.current(), Some(Token::Colon | Token::As)) {
+                loop {
+                    let line = self.line();
+                    let class = self.expect_ident("exception class")?;
+                    if !exception::is_class(&class) {
+                        return Err(PalladError::UnknownException { name: class, line });
+                    }
+                    classes.push(class);
+                    if self.current() != Some(&Token::Comma) {
+                        break;
+                    }
+                    self.advance();
+                }
+            }
+            let name = match self.current() {
+                Some(Token::As) => {
+                    self.advance();
+                    Some(self.expect_ident("identifier")?)
                 }
+                _ => None,
+            };
+            let body = self.parse_nested(true)?;
+            handlers.push(ExceptClause { classes, name, body });
+        }
 
-                Token::Eol => { self.advance(); }
+        let else_body = match self.current() {
+            Some(Token::Else) if !handlers.is_empty() => {
+                self.advance();
+                self.parse_block()?
+            }
+            _ => vec![],
+        };
+        let finally_body = match self.current() {
+            Some(Token::Finally) => {
+                self.advance();
+                Some(self.parse_nested(false)?)
+            }
+            _ => None,
+        };
 
-                other => {
-                    return Err(PalladError::UnexpectedToken {
-                        got: format!("{:?}", other),
-                        expected: "'var', 'print', or end of line".to_string(),
-                        line: self.line,
-                    });
+        if handlers.is_empty() && finally_body.is_none() {
+            return Err(match self.current() {
+                Some(other) => PalladError::UnexpectedToken {
+                    got: format!("{:?}", other),
+                    expected: "'except' or 'finally'".to_string(),
+                    line: self.line(),
+                },
+                None => PalladError::EndOfInput {
+                    expected: "'except' or 'finally'".to_string(),
+                    line: self.line(),
+                },
+            });
+        }
+        Ok(Stmt::Try { body, handlers, else_body, finally_body })
+    }
+
+    /// Parses a block, with bare `raise` allowed in it only if `in_except`.
+    fn parse_nested(&mut self, in_except: bool) -> Result<Vec<Stmt>, PalladError> {
+        let outer = std::mem::replace(&mut self.in_except, in_except);
+        let body = self.parse_block();
+        self.in_except = outer;
+        body
+    }
+
+    /// Parses `:`, a line break, and the indented statements after it, up to the matching dedent.
+    fn parse_block(&mut self) -> Result<Vec<Stmt>, PalladError> {
+        self.expect(Token::Colon, "':'")?;
+        self.expect(Token::Eol, "end of line")?;
+        self.expect(Token::Indent, "indented block")?;
+        let mut stmts = vec![];
+
+        loop {
+            match self.current() {
+                Some(Token::Dedent) => { self.advance(); break; }
+                None => break,
+                _ => {
+                    if let Some(stmt) = self.parse_statement()? {
+                        stmts.push(stmt);
+                    }
                 }
             }
         }
@@ -210,12 +1006,101 @@ impl Parser {
     /// # Examples
     ///
     /// ```
-    /// let mut parser = Parser::new(vec![Token::Int(42)]);
+    /// # use pallad::ast::Expr;
+    /// # use pallad::lexer::tokenize;
+    /// # use pallad::parser::Parser;
+    /// let mut parser = Parser::new(tokenize("42").unwrap());
     /// let expr = parser.parse_expr().unwrap();
-    /// assert_eq!(expr, Expr::Int(42));
+    /// assert!(matches!(expr, Expr::Int(42)));
     /// ```
     pub fn parse_expr(&mut self) -> Result<Expr, PalladError> {
-        self.parse_add_sub()
+        self.parse_comparison()
+    }
+
+    /// Parses a left-associative chain of comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`).
+    ///
+    /// Comparisons bind looser than every arithmetic and bitwise operator, so `a + 1 == b | c`
+    /// compares `a + 1` with `b | c`.
+    fn parse_comparison(&mut self) -> Result<Expr, PalladError> {
+        let mut left = self.parse_bit_or()?;
+
+        while let Some(tok) = self.current() {
+            let op = match tok {
+                Token::EqEq => BinOp::Eq,
+                Token::NotEq => BinOp::NotEq,
+                Token::Lt => BinOp::Lt,
+                Token::Le => BinOp::Le,
+                Token::Gt => BinOp::Gt,
+                Token::Ge => BinOp::Ge,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_bit_or()?;
+            left = Expr::Binary { left: Box::new(left), op, right: Box::new(right) };
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a left-associative chain of bitwise OR (`|`) expressions.
+    ///
+    /// `|` has the lowest precedence of the bitwise operators, so each operand is a bitwise XOR chain.
+    fn parse_bit_or(&mut self) -> Result<Expr, PalladError> {
+        let mut left = self.parse_bit_xor()?;
+
+        while let Some(Token::Pipe) = self.current() {
+            self.advance();
+            let right = self.parse_bit_xor()?;
+            left = Expr::Binary { left: Box::new(left), op: BinOp::BitOr, right: Box::new(right) };
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a left-associative chain of bitwise XOR (`^`) expressions whose operands are bitwise AND chains.
+    fn parse_bit_xor(&mut self) -> Result<Expr, PalladError> {
+        let mut left = self.parse_bit_and()?;
+
+        while let Some(Token::Caret) = self.current() {
+            self.advance();
+            let right = self.parse_bit_and()?;
+            left = Expr::Binary { left: Box::new(left), op: BinOp::BitXor, right: Box::new(right) };
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a left-associative chain of bitwise AND (`&`) expressions whose operands are shift chains.
+    fn parse_bit_and(&mut self) -> Result<Expr, PalladError> {
+        let mut left = self.parse_shift()?;
+
+        while let Some(Token::Amp) = self.current() {
+            self.advance();
+            let right = self.parse_shift()?;
+            left = Expr::Binary { left: Box::new(left), op: BinOp::BitAnd, right: Box::new(right) };
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a left-associative chain of shift (`<<`, `>>`) expressions.
+    ///
+    /// Shifts bind looser than additive operators, so `1 << 2 + 1` is parsed as `1 << (2 + 1)`.
+    fn parse_shift(&mut self) -> Result<Expr, PalladError> {
+        let mut left = self.parse_add_sub()?;
+
+        while let Some(tok) = self.current() {
+            let op = match tok {
+                Token::Shl => BinOp::Shl,
+                Token::Shr => BinOp::Shr,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_add_sub()?;
+            left = Expr::Binary { left: Box::new(left), op, right: Box::new(right) };
+        }
+
+        Ok(left)
     }
 
     /// Parses a left-associative chain of addition and subtraction expressions.
@@ -225,7 +1110,7 @@ impl Parser {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// use crate::parser::Parser;
     /// use crate::lexer::Token;
     ///
@@ -263,7 +1148,7 @@ impl Parser {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// // Parse `2 * 3`
     /// let mut parser = Parser::new(vec![Token::Int(2), Token::Star, Token::Int(3)]);
     /// let expr = parser.parse_mul_div().unwrap();
@@ -304,9 +1189,9 @@ impl Parser {
         Ok(left)
     }
 
-    /// Parses and returns a single factor: an integer, float, identifier, or a parenthesized expression.
+    /// Parses a unary expression: `-factor`, `~factor`, or a power expression.
     ///
-    /// This handles one atomic expression unit used by higher-precedence parsing (numbers, variables, or `(expr)`).
+    /// Unary operators bind looser than `**`, so `-2 ** 2` is parsed as `-(2 ** 2)`.
     ///
     /// # Returns
     ///
@@ -314,7 +1199,7 @@ impl Parser {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// use crate::parser::Parser;
     /// use crate::lexer::Token;
     /// use crate::ast::Expr;
@@ -324,7 +1209,7 @@ impl Parser {
     /// assert!(matches!(expr, Expr::Int(42)));
     /// ```
     fn parse_factor(&mut self) -> Result<Expr, PalladError> {
-        match self.current().cloned() {
+        match self.current() {
             Some(Token::Minus) => {
                 self.advance();
                 let operand = self.parse_factor()?;
@@ -334,36 +1219,286 @@ impl Parser {
                     right: Box::new(operand),
                 })
             }
+            Some(Token::Tilde) => {
+                self.advance();
+                let operand = self.parse_factor()?;
+                Ok(Expr::Unary { op: UnaryOp::BitNot, operand: Box::new(operand) })
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    /// Parses a right-associative power (`**`) expression.
+    ///
+    /// The exponent is parsed as a full factor, so `2 ** 3 ** 2` is `2 ** (3 ** 2)` and `2 ** -1` is valid.
+    fn parse_power(&mut self) -> Result<Expr, PalladError> {
+        let base = self.parse_postfix()?;
+
+        if let Some(Token::Pow) = self.current() {
+            self.advance();
+            let exponent = self.parse_factor()?;
+            return Ok(Expr::Binary { left: Box::new(base), op: BinOp::Pow, right: Box::new(exponent) });
+        }
+
+        Ok(base)
+    }
+
+    /// Parses a primary followed by any method calls (`.name(args)`), attributes (`.name`), and
+    /// indexes (`[index]`).
+    ///
+    /// These bind tightest of all, so `-s.len()` is `-(s.len())` and `s[0] ** 2` is `(s[0]) ** 2`.
+    fn parse_postfix(&mut self) -> Result<Expr, PalladError> {
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            match self.current() {
+                Some(Token::Dot) => {
+                    let line = self.line();
+                    self.advance();
+                    let name = self.expect_member_name()?;
+                    if let Some(Token::LParen) = self.current() {
+                        self.advance();
+                        let args = self.parse_list(Token::RParen, "')'")?;
+                        expr = Expr::MethodCall { receiver: Box::new(expr), name, args, line };
+                    } else {
+                        expr = Expr::Attribute { target: Box::new(expr), name, line };
+                    }
+                }
+                Some(Token::LBracket) => {
+                    self.advance();
+                    let index = self.parse_expr()?;
+                    self.expect(Token::RBracket, "']'")?;
+                    expr = Expr::Index { target: Box::new(expr), index: Box::new(index) };
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// Parses comma-separated expressions up to and including `close`, after the opening bracket
+    /// has been consumed. A trailing comma is allowed.
+    fn parse_list(&mut self, close: Token, close_text: &str) -> Result<Vec<Expr>, PalladError> {
+        let mut items = vec![];
+
+        loop {
+            if self.current() == Some(&close) {
+                self.advance();
+                return Ok(items);
+            }
+            items.push(self.parse_expr()?);
+            if self.end_of_item(&close, close_text)? {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Parses builtin call arguments up to and including `)`, after `(` has been consumed:
+    /// positional arguments followed by any `name=value` keyword arguments.
+    fn parse_call_args(&mut self) -> Result<(Vec<Expr>, KeywordArgs), PalladError> {
+        let mut args = vec![];
+        let mut kwargs: KeywordArgs = vec![];
+
+        loop {
+            if let Some(Token::RParen) = self.current() {
+                self.advance();
+                return Ok((args, kwargs));
+            }
+            let keyword = match (self.current(), self.tokens.get(self.pos + 1).map(|t| &t.token)) {
+                (Some(Token::Ident(name)), Some(Token::Eq)) => Some(name.clone()),
+                _ => None,
+            };
+            match keyword {
+                Some(name) => {
+                    if kwargs.iter().any(|(existing, _)| *existing == name) {
+                        return Err(PalladError::DuplicateKeyword { name, line: self.line() });
+                    }
+                    self.advance();
+                    self.advance();
+                    kwargs.push((name, self.parse_expr()?));
+                }
+                None if !kwargs.is_empty() => {
+                    return Err(PalladError::UnexpectedToken {
+                        got: format!("{:?}", self.current().unwrap_or(&Token::Eol)),
+                        expected: "keyword argument".to_string(),
+                        line: self.line(),
+                    });
+                }
+                None => args.push(self.parse_expr()?),
+            }
+            if self.end_of_item(&Token::RParen, "')'")? {
+                return Ok((args, kwargs));
+            }
+        }
+    }
+
+    /// Consumes the `,` after a list item and returns `false`, or consumes `close` and returns `true`.
+    fn end_of_item(&mut self, close: &Token, close_text: &str) -> Result<bool, PalladError> {
+        match self.current() {
+            Some(Token::Comma) => { self.advance(); Ok(false) }
+            Some(tok) if tok == close => { self.advance(); Ok(true) }
+            Some(other) => Err(PalladError::UnexpectedToken {
+                got: format!("{:?}", other),
+                expected: format!("',' or {}", close_text),
+                line: self.line(),
+            }),
+            None => Err(PalladError::EndOfInput {
+                expected: format!("',' or {}", close_text),
+                line: self.line(),
+            }),
+        }
+    }
+
+    /// Consumes `expected`, or fails naming it as `text`.
+    fn expect(&mut self, expected: Token, text: &str) -> Result<(), PalladError> {
+        match self.current() {
+            Some(tok) if *tok == expected => { self.advance(); Ok(()) }
+            Some(other) => Err(PalladError::UnexpectedToken {
+                got: format!("{:?}", other),
+                expected: text.to_string(),
+                line: self.line(),
+            }),
+            None => Err(PalladError::EndOfInput {
+                expected: text.to_string(),
+                line: self.line(),
+            }),
+        }
+    }
+
+    /// Consumes an identifier and returns its name, or fails naming it as `text`.
+    /// Consumes the name of a method or attribute after `.`, which may be a keyword
+    /// (`error.class`).
+    fn expect_member_name(&mut self) -> Result<String, PalladError> {
+        if let Some(name) = self.current().and_then(lexer::keyword_text) {
+            self.advance();
+            return Ok(name.to_string());
+        }
+        self.expect_ident("method or attribute name")
+    }
+
+    fn expect_ident(&mut self, text: &str) -> Result<String, PalladError> {
+        match self.current() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            Some(other) => Err(PalladError::UnexpectedToken {
+                got: format!("{:?}", other),
+                expected: text.to_string(),
+                line: self.line(),
+            }),
+            None => Err(PalladError::EndOfInput {
+                expected: text.to_string(),
+                line: self.line(),
+            }),
+        }
+    }
+
+    /// Parses and returns a single primary: a literal, identifier, builtin call, array, tuple or dict
+    /// literal, or a parenthesized expression.
+    ///
+    /// This handles one atomic expression unit used by higher-precedence parsing (numbers, variables, or `(expr)`).
+    fn parse_primary(&mut self) -> Result<Expr, PalladError> {
+        match self.current().cloned() {
             Some(Token::None) => { self.advance(); Ok(Expr::None) }
+            Some(Token::True) => { self.advance(); Ok(Expr::Bool(true)) }
+            Some(Token::False) => { self.advance(); Ok(Expr::Bool(false)) }
             Some(Token::Int(n)) => { self.advance(); Ok(Expr::Int(n)) }
+            Some(Token::BigInt(n)) => { self.advance(); Ok(Expr::BigInt(n)) }
             Some(Token::Float(f)) => { self.advance(); Ok(Expr::Float(f)) }
+            Some(Token::Decimal(d)) => { self.advance(); Ok(Expr::Decimal(d)) }
             Some(Token::Str(s)) => { self.advance(); Ok(Expr::Str(s)) }
-            Some(Token::Ident(name)) => { self.advance(); Ok(Expr::Var(name)) }
+            Some(Token::FStr(parts)) => { self.advance(); self.parse_fstring(parts) }
+            Some(Token::Ident(name)) => {
+                self.advance();
+                if let Some(Token::LParen) = self.current() {
+                    self.advance();
+                    let (args, kwargs) = self.parse_call_args()?;
+                    return Ok(Expr::Call { name, args, kwargs });
+                }
+                Ok(Expr::Var(name))
+            }
+            Some(Token::LBracket) => {
+                self.advance();
+                Ok(Expr::Array(self.parse_list(Token::RBracket, "']'")?))
+            }
+            Some(Token::LBrace) => {
+                self.advance();
+                let mut entries = vec![];
+                loop {
+                    if self.current() == Some(&Token::RBrace) {
+                        self.advance();
+                        return Ok(Expr::Dict(entries));
+                    }
+                    let key = self.parse_expr()?;
+                    self.expect(Token::Colon, "':'")?;
+                    entries.push((key, self.parse_expr()?));
+                    if self.end_of_item(&Token::RBrace, "'}'")? {
+                        return Ok(Expr::Dict(entries));
+                    }
+                }
+            }
             Some(Token::LParen) => {
                 self.advance();
+                if self.current() == Some(&Token::RParen) {
+                    self.advance();
+                    return Ok(Expr::Tuple(vec![]));
+                }
                 let expr = self.parse_expr()?;
                 match self.current() {
                     Some(Token::RParen) => { self.advance(); Ok(expr) }
+                    Some(Token::Comma) => {
+                        self.advance();
+                        let mut items = vec![expr];
+                        items.extend(self.parse_list(Token::RParen, "')'")?);
+                        Ok(Expr::Tuple(items))
+                    }
                     Some(other) => Err(PalladError::UnexpectedToken {
                         got: format!("{:?}", other),
                         expected: "')'".to_string(),
-                        line: self.line,
+                        line: self.line(),
                     }),
                     None => Err(PalladError::EndOfInput {
                         expected: "')'".to_string(),
-                        line: self.line,
+                        line: self.line(),
                     }),
                 }
             }
             Some(tok) => Err(PalladError::UnexpectedToken {
                 got: format!("{:?}", tok),
-                expected: "value, variable, or '('".to_string(),
-                line: self.line,
+                expected: "value, variable, '[', '{', or '('".to_string(),
+                line: self.line(),
             }),
             None => Err(PalladError::EndOfInput {
-                expected: "value, variable, or '('".to_string(),
-                line: self.line,
+                expected: "value, variable, '[', '{', or '('".to_string(),
+                line: self.line(),
             }),
         }
     }
-}
\ No newline at end of file
+
+    /// Parses the fields of an f-string token into an `Expr::FString`.
+    ///
+    /// Each field's tokens must form exactly one expression, so `f"{1 2}"` is an error.
+    fn parse_fstring(&self, parts: Vec<FStrPart>) -> Result<Expr, PalladError> {
+        let mut fields = vec![];
+        for part in parts {
+            match part {
+                FStrPart::Lit(text) => fields.push(FStringPart::Lit(text)),
+                FStrPart::Expr { tokens, spec } => {
+                    let mut parser = Parser::new(tokens);
+                    let expr = parser.parse_expr()?;
+                    if let Some(tok) = parser.current() {
+                        return Err(PalladError::UnexpectedToken {
+                            got: format!("{:?}", tok),
+                            expected: "'}' or ':' after f-string expression".to_string(),
+                            line: parser.line(),
+                        });
+                    }
+                    fields.push(FStringPart::Expr { expr, spec });
+                }
+            }
+        }
+
+        Ok(Expr::FString(fields))
+    }
+}
@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use crate::error::PalladError;
+use crate::ir::Param;
+use crate::value::Value;
+
+/// `Signal.ONE_SHOT`: the connection is removed the first time the signal calls it.
+pub const ONE_SHOT: i64 = 1;
+/// `Signal.DEFERRED`: the handler runs after the outermost `emit` in progress has called every
+/// other handler, instead of during it.
+pub const DEFERRED: i64 = 2;
+
+/// The connection flag `Signal.<name>`.
+pub fn flag(name: &str) -> Option<i64> {
+    match name {
+        "ONE_SHOT" => Some(ONE_SHOT),
+        "DEFERRED" => Some(DEFERRED),
+        _ => None,
+    }
+}
+
+/// A signal declared with `signal name(params)`: the parameters `emit` is checked against, and
+/// the connected handlers in connection order.
+///
+/// Clones refer to the same signal, so connecting through one is seen by all.
+#[derive(Clone)]
+pub struct SignalRef(Rc<RefCell<Signal>>);
+
+struct Signal {
+    name: String,
+    params: Vec<Param>,
+    connections: Vec<Connection>,
+}
+
+/// A handler connected to a signal, with its flags.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub callable: Value,
+    pub one_shot: bool,
+    pub deferred: bool,
+}
+
+impl SignalRef {
+    pub fn new(name: String, params: Vec<Param>) -> Self {
+        SignalRef(Rc::new(RefCell::new(Signal { name, params, connections: vec![] })))
+    }
+
+    pub fn name(&self) -> String {
+        self.0.borrow().name.clone()
+    }
+
+    pub fn params(&self) -> Vec<Param> {
+        self.0.borrow().params.clone()
+    }
+
+    /// The current connections, in the order they were made.
+    pub fn connections(&self) -> Vec<Connection> {
+        self.0.borrow().connections.clone()
+    }
+
+    pub fn is_connected(&self, callable: &Value) -> bool {
+        self.0.borrow().connections.iter().any(|connection| connection.callable == *callable)
+    }
+
+    /// Connects `callable` with `flags` (a combination of `ONE_SHOT` and `DEFERRED`). The
+    /// callable must be a function taking as many parameters as the signal passes, with
+    /// annotated types that agree with the signal's, and must not be connected already.
+    pub fn connect(&self, callable: Value, flags: i64) -> Result<(), PalladError> {
+        let fail = |reason: String| PalladError::SignalConnection { signal: self.name(), reason };
+        let Value::Function(function) = &callable else {
+            return Err(PalladError::NotCallable { value: callable });
+        };
+        if flags & !(ONE_SHOT | DEFERRED) != 0 {
+            return Err(fail(format!("unknown connection flags {}", flags)));
+        }
+        let params = self.params();
+        if function.params.len() != params.len() {
+            return Err(fail(format!(
+                "'{}' takes {} parameter(s), but the signal passes {}",
+                function.name, function.params.len(), params.len(),
+            )));
+        }
+        for (param, passed) in function.params.iter().zip(&params) {
+            if let (Some(expected), Some(passed_type)) = (&param.expected, &passed.expected)
+                && expected != passed_type
+            {
+                return Err(fail(format!(
+                    "parameter '{}' of '{}' expects {}, but the signal passes {}",
+                    param.name, function.name, expected, passed_type,
+                )));
+            }
+        }
+        if self.is_connected(&callable) {
+            return Err(fail(format!("'{}' is already connected", function.name)));
+        }
+
+        self.0.borrow_mut().connections.push(Connection {
+            callable,
+            one_shot: flags & ONE_SHOT != 0,
+            deferred: flags & DEFERRED != 0,
+        });
+        Ok(())
+    }
+
+    /// Removes the connection of `callable`, which must be connected.
+    pub fn disconnect(&self, callable: &Value) -> Result<(), PalladError> {
+        let mut signal = self.0.borrow_mut();
+        let Some(position) = signal.connections.iter().position(|connection| connection.callable == *callable) else {
+            return Err(PalladError::SignalConnection {
+                signal: signal.name.clone(),
+                reason: format!("{} is not connected", callable.repr()),
+            });
+        };
+        signal.connections.remove(position);
+        Ok(())
+    }
+}
+
+impl PartialEq for SignalRef {
+    /// Signals are equal only when they are the same signal.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for SignalRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let signal = self.0.borrow();
+        write!(f, "SignalRef({:?}, {} connection(s))", signal.name, signal.connections.len())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::lexer::{Trivia, TriviaKind};
+
+/// A keyword highlighted in comments (`# FIXME: reset the timer`), colored by how urgent it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentTag {
+    Fixme,
+    Caution,
+    Debug,
+    Note,
+    Refactor,
+    Optimize,
+    Deadline,
+    Priority,
+    AssignedTo,
+    Review,
+    LastUpdate,
+    Category,
+}
+
+const TAGS: &[(&str, CommentTag)] = &[
+    ("FIXME", CommentTag::Fixme),
+    ("CAUTION", CommentTag::Caution),
+    ("DEBUG", CommentTag::Debug),
+    ("NOTE", CommentTag::Note),
+    ("REFACTOR", CommentTag::Refactor),
+    ("OPTIMIZE", CommentTag::Optimize),
+    ("DEADLINE", CommentTag::Deadline),
+    ("PRIORITY", CommentTag::Priority),
+    ("ASSIGNED_TO", CommentTag::AssignedTo),
+    ("REVIEW", CommentTag::Review),
+    ("LAST_UPDATE", CommentTag::LastUpdate),
+    ("CATEGORY", CommentTag::Category),
+];
+
+impl CommentTag {
+    /// Looks up a tag by its keyword, which is uppercase (`FIXME`, `ASSIGNED_TO`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        TAGS.iter().find(|(keyword, _)| *keyword == name).map(|(_, tag)| *tag)
+    }
+
+    pub fn name(self) -> &'static str {
+        TAGS.iter().find(|(_, tag)| *tag == self).map_or("", |(keyword, _)| keyword)
+    }
+
+    /// The color the tag is highlighted with: `red` for problems, `yellow` for things to act on,
+    /// `green` for bookkeeping.
+    pub fn color(self) -> &'static str {
+        match self {
+            CommentTag::Fixme | CommentTag::Caution | CommentTag::Debug => "red",
+            CommentTag::Note | CommentTag::Refactor | CommentTag::Optimize | CommentTag::Deadline
+            | CommentTag::Priority => "yellow",
+            CommentTag::AssignedTo | CommentTag::Review | CommentTag::LastUpdate | CommentTag::Category => "green",
+        }
+    }
+}
+
+/// The tags of the comment `text`, each with the text following it up to the next tag, without
+/// the punctuation around it (`FIXME: reset it` gives `reset it`). Keywords are whole words.
+pub fn find(text: &str) -> Vec<(CommentTag, String)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut found: Vec<(CommentTag, usize, usize)> = vec![];
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (is_word(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(word)) => {
+                if let Some(tag) = CommentTag::from_name(&text[word..i]) {
+                    found.push((tag, word, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    let punctuation: &[char] = &[':', ',', ';', '-', ' ', '\t'];
+    found.iter().enumerate()
+        .map(|(n, &(tag, _, end))| {
+            let until = found.get(n + 1).map_or(text.len(), |&(_, next, _)| next);
+            (tag, text[end..until].trim_matches(punctuation).to_string())
+        })
+        .collect()
+}
+
+/// Lists the tags and regions of source files as JSON, for `pallad tags`: an object with each
+/// file's path as a key, whose value has `tags` (`tag`, `color`, `text` and `line` of each),
+/// `regions` (`name`, `line` and `end_line` of each) and `warnings` (`message` and `line` of each
+/// unbalanced `#region` or `#endregion`), or the message of the `error` that stopped the file from
+/// being read or tokenized.
+pub fn report(files: &[(String, Result<Vec<Trivia>, String>)]) -> String {
+    let mut out = String::from("{");
+    for (i, (path, trivia)) in files.iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        out.push_str(&format!("  {}: ", quote(path)));
+        let trivia = match trivia {
+            Ok(trivia) => trivia,
+            Err(error) => {
+                out.push_str(&format!("{{ \"error\": {} }}", quote(error)));
+                continue;
+            }
+        };
+        let tags: Vec<String> = trivia.iter()
+            .filter_map(|trivia| match trivia.kind {
+                TriviaKind::Tag(tag) => Some(format!(
+                    "      {{ \"tag\": {}, \"color\": {}, \"text\": {}, \"line\": {} }}",
+                    quote(tag.name()), quote(tag.color()), quote(&trivia.text), trivia.line,
+                )),
+                _ => None,
+            })
+            .collect();
+        let regions: Vec<String> = trivia.iter()
+            .filter(|trivia| trivia.kind == TriviaKind::Region)
+            .map(|trivia| format!(
+                "      {{ \"name\": {}, \"line\": {}, \"end_line\": {} }}",
+                quote(&trivia.text), trivia.line, trivia.end_line,
+            ))
+            .collect();
+        let warnings: Vec<String> = trivia.iter()
+            .filter(|trivia| trivia.kind == TriviaKind::UnbalancedRegion)
+            .map(|trivia| format!("      {{ \"message\": {}, \"line\": {} }}", quote(&trivia.text), trivia.line))
+            .collect();
+        out.push_str(&format!(
+            "{{\n    \"tags\": {},\n    \"regions\": {},\n    \"warnings\": {}\n  }}",
+            list(&tags), list(&regions), list(&warnings),
+        ));
+    }
+    out.push_str(if files.is_empty() { "}\n" } else { "\n}\n" });
+    out
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n    ]", items.join(",\n"))
+}
+
+/// `text` as a JSON string.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -1,21 +1,149 @@
 use std::fmt;
+use std::rc::Rc;
+use crate::bigint::BigInt;
+use crate::color::Color;
+use crate::decimal::Decimal;
+use crate::exception::Exception;
+use crate::file::FileRef;
+use crate::ir::Function;
+use crate::module::ModuleRef;
+use crate::object::{Class, ObjectRef};
+use crate::signal::SignalRef;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     None,
+    Bool(bool),
     Int(i64),
+    BigInt(BigInt),
     Float(f64),
+    Decimal(Decimal),
     Str(String),
+    Array(Vec<Value>),
+    /// A fixed-size group of values, e.g. `(1, "a")`.
+    Tuple(Vec<Value>),
+    /// Key-value pairs in insertion order, e.g. `{"x": 1}`. Keys are unique under `==`.
+    Dict(Vec<(Value, Value)>),
+    File(FileRef),
+    Exception(Exception),
+    Color(Color),
+    /// A builtin namespace such as `log` or `Color`.
+    Namespace(&'static str),
+    Function(Rc<Function>),
+    Signal(SignalRef),
+    Class(Rc<Class>),
+    Object(ObjectRef),
+    Module(ModuleRef),
+}
+
+impl Value {
+    /// Text of the value as `print` and f-strings show it (`<none>` for none).
+    pub fn stringify(&self) -> String {
+        match self {
+            Value::None => "<none>".to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Int(n) => n.to_string(),
+            Value::BigInt(n) => n.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Decimal(d) => d.to_string(),
+            Value::Str(s) => s.clone(),
+            Value::Array(items) => {
+                let items: Vec<String> = items.iter().map(Value::repr).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::Tuple(items) if items.len() == 1 => format!("({},)", items[0].repr()),
+            Value::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(Value::repr).collect();
+                format!("({})", items.join(", "))
+            }
+            Value::Dict(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key.repr(), value.repr()))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            Value::File(file) => format!("<file {:?} mode {:?}>", file.path(), file.mode()),
+            Value::Exception(exception) => exception.message.clone(),
+            Value::Color(c) => format!("Color({}, {}, {}, {})", c.r, c.g, c.b, c.a),
+            Value::Namespace(name) => format!("<namespace {}>", name),
+            Value::Function(function) => format!("<function {}>", function.name),
+            Value::Signal(signal) => format!("<signal {}>", signal.name()),
+            Value::Class(class) => format!("<class {}>", class.name),
+            Value::Object(object) => format!("<{} instance>", object.class().name),
+            Value::Module(module) => format!("<module {}>", module.name()),
+        }
+    }
+
+    /// Like `stringify`, but strings are quoted, so `["a, b"]` and `["a", "b"]` print differently.
+    pub fn repr(&self) -> String {
+        match self {
+            Value::Str(s) => format!("{:?}", s),
+            other => other.stringify(),
+        }
+    }
+
+    /// Whether the value counts as true: everything except `none`, `false`, zero, and empty
+    /// strings, arrays, tuples and dicts.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::None => false,
+            Value::Bool(b) => *b,
+            Value::Int(n) => *n != 0,
+            Value::BigInt(n) => !n.is_zero(),
+            Value::Float(f) => *f != 0.0,
+            Value::Decimal(d) => !d.is_zero(),
+            Value::Str(s) => !s.is_empty(),
+            Value::Array(items) | Value::Tuple(items) => !items.is_empty(),
+            Value::Dict(entries) => !entries.is_empty(),
+            Value::File(_)
+            | Value::Exception(_)
+            | Value::Color(_)
+            | Value::Namespace(_)
+            | Value::Function(_)
+            | Value::Signal(_)
+            | Value::Class(_)
+            | Value::Object(_)
+            | Value::Module(_) => true,
+        }
+    }
+
+    /// Name of the value's type, as shown in error messages and used to look up native methods.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::None => "none",
+            Value::Bool(_) => "bool",
+            Value::Int(_) | Value::BigInt(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Decimal(_) => "decimal",
+            Value::Str(_) => "string",
+            Value::Array(_) => "array",
+            Value::Tuple(_) => "tuple",
+            Value::Dict(_) => "dict",
+            Value::File(_) => "file",
+            Value::Exception(_) => "exception",
+            Value::Color(_) => "color",
+            Value::Namespace(_) => "namespace",
+            Value::Function(_) => "function",
+            Value::Signal(_) => "signal",
+            Value::Class(_) => "class",
+            Value::Object(_) => "object",
+            Value::Module(_) => "module",
+        }
+    }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Value::None => "none",
-            Value::Int(_i) => "integer",
-            Value::Float(_f) => "float",
-            Value::Str(_s) => "string",
-        };
-        write!(f, "{name}")
+        write!(f, "{}", self.type_name())
     }
-}
\ No newline at end of file
+}
+impl From<BigInt> for Value {
+    /// Wraps an arbitrary-precision result, narrowing it back to `Value::Int` when it fits in 64 bits.
+    fn from(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(n) => Value::Int(n),
+            None => Value::BigInt(n),
+        }
+    }
+}
@@ -1,7 +1,29 @@
-use std::collections::HashMap;
-use crate::error::PalladError;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+use crate::bigint::{self, BigInt};
+use crate::builtins;
+use crate::color::Color;
+use crate::decimal::{self, Decimal, DecimalContext, DecimalError};
+use crate::error::{PalladError, Warning};
+use crate::exception::{self, Exception};
 use crate::value::Value;
-use crate::ir::Instr;
+use crate::ir::{self, Annotation, CaseKey, Function, Instr, Param, Pattern};
+use crate::log::{LogLevel, LogRecord, LogSink, Logger};
+use crate::methods::{self, MethodTable};
+use crate::module::{self, ModuleRef, Target};
+use crate::object::{self, Class, ObjectRef};
+use crate::signal::{self, SignalRef};
+
+/// How deeply function calls may nest before `RecursionLimit` is raised.
+const MAX_CALL_DEPTH: usize = 500;
+
+/// How deeply `emit` calls may nest (through handlers emitting signals) before `RecursionLimit`
+/// is raised. Each level runs its handlers in a nested `execute`, using the host's stack.
+const MAX_EMIT_DEPTH: usize = 64;
 
 enum Op {
     Add,
@@ -10,6 +32,12 @@ enum Op {
     Div,
     IntDiv,
     Mod,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
 }
 
 impl Op {
@@ -21,34 +49,265 @@ impl Op {
             Op::Div => "divide",
             Op::IntDiv => "integer-divide",
             Op::Mod => "mod",
+            Op::Pow => "power",
+            Op::BitAnd => "bitwise-and",
+            Op::BitOr => "bitwise-or",
+            Op::BitXor => "bitwise-xor",
+            Op::Shl => "shift-left",
+            Op::Shr => "shift-right",
+        }
+    }
+}
+
+enum Comparison {
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// How integer operations behave when the exact result doesn't fit in 64 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Raise an overflow error (`IntOverflow`, `IntDivOverflow`, `PowOverflow`, `ShiftOverflow`).
+    #[default]
+    Error,
+    /// Keep the low 64 bits of the two's complement result.
+    Wrap,
+    /// Clamp the result to `i64::MIN` or `i64::MAX`.
+    Saturate,
+    /// Continue with an arbitrary-precision integer.
+    Promote,
+}
+
+impl OverflowPolicy {
+    /// Looks up a policy by its command-line name (`error`, `wrap`, `saturate`, or `promote`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(OverflowPolicy::Error),
+            "wrap" => Some(OverflowPolicy::Wrap),
+            "saturate" => Some(OverflowPolicy::Saturate),
+            "promote" => Some(OverflowPolicy::Promote),
+            _ => None,
         }
     }
 }
 
 pub struct VM {
     stack: Vec<Value>,
-    globals: HashMap<String, Value>,
+    /// The modules loaded, by index; the first is the program given to `run`, and holds its
+    /// globals.
+    modules: Vec<ModuleRef>,
+    /// The index of each module file loaded, so that each runs once.
+    module_ids: HashMap<PathBuf, usize>,
+    /// The modules whose code is running, the program's first, then each module it is importing.
+    importing: Vec<usize>,
+    /// Warnings from compiling imported modules, with the module's path.
+    warnings: Vec<(String, Warning)>,
+    overflow: OverflowPolicy,
+    decimal: DecimalContext,
+    methods: MethodTable,
+    /// Where `input()` reads lines from.
+    input: Box<dyn BufRead>,
+    /// Where `print` and `input` prompts write to.
+    output: Box<dyn Write>,
+    /// Resources of the `with` blocks being executed, innermost last.
+    with_stack: Vec<Value>,
+    /// Exception handlers of the `try` blocks being executed, innermost last.
+    handlers: Vec<Handler>,
+    /// Where the `log` namespace sends records.
+    logger: Logger,
+    /// The program being run, shared with the calls it makes.
+    program: Rc<[Instr]>,
+    /// Calls in progress, innermost last.
+    frames: Vec<Frame>,
+    /// How many `emit` calls are in progress.
+    emit_depth: usize,
+    /// Calls of `Signal.DEFERRED` handlers waiting for the outermost `emit` to finish.
+    deferred: VecDeque<(Value, Vec<Value>)>,
+}
+
+/// Where execution resumes when an error reaches a `try` block, and the state to unwind to.
+struct Handler {
+    target: usize,
+    stack_len: usize,
+    with_len: usize,
+    frames_len: usize,
+    /// How many `match` arm scopes the frame at `frames_len` had.
+    scopes_len: usize,
+}
+
+/// A function call in progress: its local variables, and the VM state to restore on return.
+struct Frame {
+    function: Rc<Function>,
+    locals: HashMap<String, Value>,
+    /// The types of the locals declared with one, which assignments to them must fit.
+    annotations: HashMap<String, Annotation>,
+    /// The bindings of the `match` arms running in the call, innermost last; they shadow its other
+    /// variables.
+    scopes: Vec<HashMap<String, Value>>,
+    /// The class a method or static function is declared in, whose statics it can use by name.
+    class: Option<Rc<Class>>,
+    /// Where the caller continues, or `None` for calls made by the VM itself (e.g. signal
+    /// handlers), whose `execute` ends when they return.
+    return_pc: Option<usize>,
+    stack_len: usize,
+    with_len: usize,
+    handlers_len: usize,
+    /// Whether the call runs a module's code on its import, whose variables are the module's
+    /// globals.
+    module_code: bool,
+}
+
+impl Default for VM {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VM {
-    /// Constructs a new VM with an empty operand stack and an empty global variable store.
+    /// Constructs a new VM with an empty operand stack, an empty global variable store, the
+    /// standard native methods, and script I/O on standard input and output.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use pallad::vm::VM;
     /// let _vm = VM::new();
     /// ```
     pub fn new() -> Self {
         Self {
             stack: vec![],
-            globals: HashMap::new(),
+            modules: vec![ModuleRef::new("main".to_string(), None, None)],
+            module_ids: HashMap::new(),
+            importing: vec![],
+            warnings: vec![],
+            overflow: OverflowPolicy::default(),
+            decimal: DecimalContext::default(),
+            methods: MethodTable::standard(),
+            input: Box::new(io::BufReader::new(io::stdin())),
+            output: Box::new(io::stdout()),
+            with_stack: vec![],
+            handlers: vec![],
+            logger: Logger::default(),
+            program: Rc::new([]),
+            frames: vec![],
+            emit_depth: 0,
+            deferred: VecDeque::new(),
         }
     }
 
+    /// Selects how integer operations on this VM handle results that don't fit in 64 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::vm::{OverflowPolicy, VM};
+    /// let mut vm = VM::new();
+    /// vm.set_overflow_policy(OverflowPolicy::Promote);
+    /// ```
+    pub fn set_overflow_policy(&mut self, overflow: OverflowPolicy) {
+        self.overflow = overflow;
+    }
+
+    /// Selects the precision and rounding mode used by decimal arithmetic on this VM. A precision
+    /// above `decimal::MAX_PRECISION` is lowered to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::decimal::{DecimalContext, RoundingMode};
+    /// # use pallad::vm::VM;
+    /// let mut vm = VM::new();
+    /// vm.set_decimal_context(DecimalContext { precision: 2, rounding: RoundingMode::HalfUp });
+    /// ```
+    pub fn set_decimal_context(&mut self, decimal: DecimalContext) {
+        self.decimal = DecimalContext { precision: decimal.precision.min(decimal::MAX_PRECISION), ..decimal };
+    }
+
+    /// Makes `input()` read lines from `input` instead of standard input, e.g. to feed a script
+    /// prepared answers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::vm::VM;
+    /// let mut vm = VM::new();
+    /// vm.set_input(Box::new(std::io::Cursor::new("Ada\n")));
+    /// ```
+    pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+        self.input = input;
+    }
+
+    /// Sends `print` output and `input()` prompts to `output` instead of standard output.
+    ///
+    /// `output::SharedBuffer` collects output in memory, and `output::CallbackWriter` passes it
+    /// to a host function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::output::SharedBuffer;
+    /// # use pallad::vm::VM;
+    /// let buffer = SharedBuffer::new();
+    /// let mut vm = VM::new();
+    /// vm.set_output(Box::new(buffer.clone()));
+    /// ```
+    pub fn set_output(&mut self, output: Box<dyn Write>) {
+        self.output = output;
+    }
+
+    /// Sends the script's `log` records to `sink` instead of standard error, e.g. to route them
+    /// into the host's own logging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::log::LogRecord;
+    /// # use pallad::vm::VM;
+    /// let mut vm = VM::new();
+    /// vm.set_log_sink(Box::new(|record: &LogRecord| eprintln!("[script] {}", record.format())));
+    /// ```
+    pub fn set_log_sink(&mut self, sink: LogSink) {
+        self.logger.sink = Some(sink);
+    }
+
+    /// Drops `log` records below `level` (`LogLevel::Info` by default); scripts can change it
+    /// with `log.set_level`.
+    pub fn set_log_level(&mut self, level: LogLevel) {
+        self.logger.level = level;
+    }
+
+    /// Sets the file the program given to `run` was read from, which its imports are relative to
+    /// (the current directory otherwise). Its globals start empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pallad::vm::VM;
+    /// let mut vm = VM::new();
+    /// vm.set_script_path("examples/example.pd");
+    /// ```
+    pub fn set_script_path(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let name = path.file_stem().map_or_else(|| "main".to_string(), |stem| stem.to_string_lossy().into_owned());
+        let path = path.canonicalize().unwrap_or(path);
+        self.modules[0] = ModuleRef::new(name, Some(path), None);
+    }
+
+    /// The warnings found compiling the modules the last `run` imported, each with the module's
+    /// path.
+    pub fn warnings(&self) -> &[(String, Warning)] {
+        &self.warnings
+    }
+
     /// Executes a sequence of bytecode-like instructions on the virtual machine, updating the stack and globals.
     ///
     /// The VM processes each `Instr` in order, manipulating the operand stack and global variable store,
-    /// performing arithmetic, variable access, built-in calls (currently `print`), and stack operations.
+    /// performing arithmetic, variable access, built-in calls (`print`, `input`, and the functions in `builtins`), and stack operations.
+    /// Errors raised inside a `try` block are caught by its handler instead of ending the run.
     ///
     /// # Errors
     ///
@@ -62,78 +321,644 @@ impl VM {
     /// # Examples
     ///
     /// ```
-    /// use crate::{VM, Instr, Value, PalladError};
+    /// use pallad::ir::Instr;
+    /// use pallad::vm::VM;
     ///
     /// let mut vm = VM::new();
     /// let program = vec![
     ///     Instr::LoadInt(2),
     ///     Instr::LoadInt(3),
     ///     Instr::Add,
-    ///     Instr::CallBuiltin { name: "print".to_string(), argc: 1 },
+    ///     Instr::CallBuiltin { name: "print".to_string(), argc: 1, kwargs: vec![] },
     /// ];
     ///
     /// assert!(vm.run(program).is_ok());
     /// ```
-    pub fn run(&mut self, program: Vec<Instr>) -> Result<(), PalladError> {
-        for instr in program {
-            match instr {
-                Instr::LoadNone => self.stack.push(Value::None),
-                Instr::LoadInt(n) => self.stack.push(Value::Int(n)),
-                Instr::LoadFloat(f) => self.stack.push(Value::Float(f)),
-                Instr::LoadStr(s) => self.stack.push(Value::Str(s)),
-                Instr::LoadVar(name) => {
-                    let val = self.globals.get(&name)
-                        .cloned()
-                        .ok_or(PalladError::UndefinedVariable { name: name.clone() })?;
-                    self.stack.push(val);
-                }
-                Instr::StoreVar(name) => {
-                    let val = self.stack.pop()
-                        .ok_or(PalladError::StackUnderflow { operation: "store variable" })?;
-                    self.globals.insert(name, val);
-                }
-                Instr::Add => {
-                    self.execute_arithmetic(Op::Add)?;
-                }
-                Instr::Sub => {
-                    self.execute_arithmetic(Op::Sub)?;
-                }
-                Instr::Mul => {
-                    self.execute_arithmetic(Op::Mul)?;
-                }
-                Instr::Div => {
-                    self.execute_arithmetic(Op::Div)?;
-                }
-                Instr::IntDiv => {
-                    self.execute_arithmetic(Op::IntDiv)?;
-                }
-                Instr::Mod => {
-                    self.execute_arithmetic(Op::Mod)?;
-                }
-                Instr::CallBuiltin { name, argc } => {
-                    if name == "print" {
-                        let mut args = Vec::with_capacity(argc);
-                        for _ in 0..argc {
-                            args.push(self.stack.pop()
-                                .ok_or(PalladError::StackUnderflow { operation: "print" })?);
+    pub fn run(&mut self, mut program: Vec<Instr>) -> Result<(), PalladError> {
+        // The program runs like an imported module's code, so that it ends before the code of the
+        // modules it imports, which follows it
+        program.extend([Instr::LoadNone, Instr::Return]);
+        self.program = program.into();
+        // Modules imported by an earlier run have their code in its program, so they load again
+        self.modules.truncate(1);
+        self.module_ids.clear();
+        if let Some(path) = self.modules[0].path() {
+            self.module_ids.insert(path, 0);
+        }
+        self.importing = vec![0];
+        self.warnings.clear();
+        let code = Rc::new(Function { name: self.modules[0].name(), params: vec![], returns: None, entry: 0, owner: None, module: 0 });
+        let result = self.run_module(code);
+        // Files opened by `with` are closed even when the block failed; the original error wins
+        // over any error while closing
+        while let Some(resource) = self.with_stack.pop() {
+            if let Value::File(file) = resource {
+                let _ = file.close();
+            }
+        }
+        // An uncaught error leaves the calls it went through behind
+        self.frames.clear();
+        self.handlers.clear();
+        self.deferred.clear();
+        self.emit_depth = 0;
+        result?;
+        self.output.flush()
+            .map_err(|e| PalladError::Io { operation: "print", message: e.to_string() })
+    }
+
+    /// Executes the program from `pc` until it ends, or, for a call with `depth` frames, until
+    /// that call returns.
+    fn execute(&mut self, mut pc: usize, depth: usize) -> Result<(), PalladError> {
+        let mut program = Rc::clone(&self.program);
+        while self.frames.len() >= depth
+            && let Some(instr) = program.get(pc)
+        {
+            pc += 1;
+            if let Err(error) = self.step(instr, &mut pc) {
+                pc = self.unwind(error, depth)?;
+            }
+            // Imports add the code of the modules they load to the program
+            if !Rc::ptr_eq(&program, &self.program) {
+                program = Rc::clone(&self.program);
+            }
+        }
+        Ok(())
+    }
+
+    /// Hands `error` to the innermost exception handler and returns where to continue, or returns
+    /// the error if no handler is left. A handler outside the call at `depth` is left for the
+    /// caller's own `execute` to use, once the error reaches it.
+    fn unwind(&mut self, error: PalladError, depth: usize) -> Result<usize, PalladError> {
+        if self.handlers.last().is_none_or(|handler| handler.frames_len < depth) {
+            return Err(error);
+        }
+        let Some(handler) = self.handlers.pop() else {
+            return Err(error);
+        };
+        self.frames.truncate(handler.frames_len);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.scopes.truncate(handler.scopes_len);
+        }
+        self.stack.truncate(handler.stack_len);
+        while self.with_stack.len() > handler.with_len {
+            if let Some(Value::File(file)) = self.with_stack.pop() {
+                let _ = file.close();
+            }
+        }
+        self.stack.push(Value::Exception(Exception::from_error(error)));
+        Ok(handler.target)
+    }
+
+    /// Executes one instruction; jumps move `pc` to their target.
+    fn step(&mut self, instr: &Instr, pc: &mut usize) -> Result<(), PalladError> {
+        match instr {
+            Instr::LoadNone => self.stack.push(Value::None),
+            Instr::LoadBool(b) => self.stack.push(Value::Bool(*b)),
+            Instr::LoadInt(n) => self.stack.push(Value::Int(*n)),
+            Instr::LoadBigInt(n) => self.stack.push(Value::BigInt(n.clone())),
+            Instr::LoadFloat(f) => self.stack.push(Value::Float(*f)),
+            Instr::LoadDecimal(d) => self.stack.push(Value::Decimal(d.clone())),
+            Instr::LoadStr(s) => self.stack.push(Value::Str(s.clone())),
+            Instr::LoadVar(name) => {
+                let val = self.variable(name)
+                    .or_else(|| builtins::namespace(name).map(Value::Namespace))
+                    .ok_or(PalladError::UndefinedVariable { name: name.clone() })?;
+                self.stack.push(val);
+            }
+            Instr::StoreVar(name) => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "store variable" })?;
+                self.store(name.clone(), val, None)?;
+            }
+            Instr::StoreTyped { name, expected } => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "store variable" })?;
+                check_annotation(name, expected, &val)?;
+                self.store(name.clone(), val, Some(expected.clone()))?;
+            }
+            Instr::AssignVar(name) => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "assignment" })?;
+                self.assign(name, val)?;
+            }
+            Instr::BuildArray(count) => {
+                let count = *count;
+                if count > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "build array" });
+                }
+                let items = self.stack.split_off(self.stack.len() - count);
+                self.stack.push(Value::Array(items));
+            }
+            Instr::BuildTuple(count) => {
+                let count = *count;
+                if count > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "build tuple" });
+                }
+                let items = self.stack.split_off(self.stack.len() - count);
+                self.stack.push(Value::Tuple(items));
+            }
+            Instr::BuildDict(count) => {
+                let count = *count;
+                if 2 * count > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "build dict" });
+                }
+                let mut flat = self.stack.split_off(self.stack.len() - 2 * count).into_iter();
+                let mut entries: Vec<(Value, Value)> = Vec::with_capacity(count);
+                while let (Some(key), Some(value)) = (flat.next(), flat.next()) {
+                    // A repeated key keeps its first position and its last value
+                    match entries.iter_mut().find(|(k, _)| values_equal(k, &key)) {
+                        Some(entry) => entry.1 = value,
+                        None => entries.push((key, value)),
+                    }
+                }
+                self.stack.push(Value::Dict(entries));
+            }
+            Instr::Index => {
+                let index = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "index" })?;
+                let target = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "index" })?;
+                self.stack.push(index_value(target, index)?);
+            }
+            Instr::Add => {
+                self.execute_arithmetic(Op::Add)?;
+            }
+            Instr::Sub => {
+                self.execute_arithmetic(Op::Sub)?;
+            }
+            Instr::Mul => {
+                self.execute_arithmetic(Op::Mul)?;
+            }
+            Instr::Div => {
+                self.execute_arithmetic(Op::Div)?;
+            }
+            Instr::IntDiv => {
+                self.execute_arithmetic(Op::IntDiv)?;
+            }
+            Instr::Mod => {
+                self.execute_arithmetic(Op::Mod)?;
+            }
+            Instr::Pow => {
+                self.execute_arithmetic(Op::Pow)?;
+            }
+            Instr::BitAnd => {
+                self.execute_arithmetic(Op::BitAnd)?;
+            }
+            Instr::BitOr => {
+                self.execute_arithmetic(Op::BitOr)?;
+            }
+            Instr::BitXor => {
+                self.execute_arithmetic(Op::BitXor)?;
+            }
+            Instr::Shl => {
+                self.execute_arithmetic(Op::Shl)?;
+            }
+            Instr::Shr => {
+                self.execute_arithmetic(Op::Shr)?;
+            }
+            Instr::BitNot => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "bitwise-not" })?;
+                match val {
+                    Value::Int(n) => self.stack.push(Value::Int(!n)),
+                    Value::BigInt(n) => self.stack.push(Value::from(!&n)),
+                    other => return Err(PalladError::InvalidOperand { value: other, operation: "bitwise-not" }),
+                }
+            }
+            Instr::Eq => {
+                self.execute_comparison(Comparison::Eq)?;
+            }
+            Instr::NotEq => {
+                self.execute_comparison(Comparison::NotEq)?;
+            }
+            Instr::Lt => {
+                self.execute_comparison(Comparison::Lt)?;
+            }
+            Instr::Le => {
+                self.execute_comparison(Comparison::Le)?;
+            }
+            Instr::Gt => {
+                self.execute_comparison(Comparison::Gt)?;
+            }
+            Instr::Ge => {
+                self.execute_comparison(Comparison::Ge)?;
+            }
+            Instr::FormatValue(spec) => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "format" })?;
+                let text = match spec {
+                    Some(spec) => match spec.apply(&val, self.decimal.rounding) {
+                        Some(text) => text,
+                        None => return Err(PalladError::FormatMismatch { value: val, spec: spec.to_string() }),
+                    },
+                    None => val.stringify(),
+                };
+                self.stack.push(Value::Str(text));
+            }
+            Instr::Concat(count) => {
+                let count = *count;
+                if count > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "concat" });
+                }
+                let parts = self.stack.split_off(self.stack.len() - count);
+                self.stack.push(Value::Str(parts.iter().map(Value::stringify).collect()));
+            }
+            Instr::CallBuiltin { name, argc, kwargs } => {
+                let (name, argc) = (name.clone(), *argc);
+                if argc + kwargs.len() > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "builtin call" });
+                }
+                let values = self.stack.split_off(self.stack.len() - kwargs.len());
+                let kwargs: Vec<(String, Value)> = kwargs.iter().cloned().zip(values).collect();
+                let args = self.stack.split_off(self.stack.len() - argc);
+
+                if name == "print" {
+                    self.print(&args, kwargs)?;
+                    self.stack.push(Value::None);
+                    return Ok(());
+                }
+                if let Some((keyword, _)) = kwargs.into_iter().next() {
+                    return Err(PalladError::UnexpectedKeyword { function: name, name: keyword });
+                }
+                // Functions and classes declared in the script hide builtins of the same name
+                let callee = self.variable(&name);
+                if let Some(Value::Function(function)) = callee {
+                    // Static functions called by name from their class keep its statics
+                    let receiver = function.owner.as_ref()
+                        .and_then(|_| self.frames.last()?.class.clone())
+                        .map(Value::Class);
+                    *pc = self.enter(function, args, receiver, Some(*pc))?;
+                } else if let Some(Value::Class(class)) = callee {
+                    *pc = self.instantiate(class, args, *pc)?;
+                } else if name == "call" {
+                    let mut args = args.into_iter();
+                    match args.next() {
+                        Some(Value::Function(function)) => *pc = self.enter(function, args.collect(), None, Some(*pc))?,
+                        Some(other) => return Err(PalladError::NotCallable { value: other }),
+                        None => return Err(PalladError::InvalidArgument { function: name, reason: "expected a function to call" }),
+                    }
+                } else if name == "input" {
+                    methods::check_args("input", &args, 0, 1)?;
+                    let line = self.read_input(args.first())?;
+                    self.stack.push(line);
+                } else if exception::is_class(&name) {
+                    self.stack.push(exception::construct(&name, &args)?);
+                } else if let Some(function) = builtins::lookup(&name) {
+                    self.stack.push(function(&args)?);
+                } else {
+                    return Err(PalladError::UnknownBuiltin { name });
+                }
+            }
+            Instr::CallMethod { name, argc, line } => {
+                let (name, argc) = (name.clone(), *argc);
+                if argc >= self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "method call" });
+                }
+                let args = self.stack.split_off(self.stack.len() - argc);
+                let receiver = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "method call" })?;
+                if receiver == Value::Namespace("log") {
+                    self.log(name, &args, *line)?;
+                    self.stack.push(Value::None);
+                    return Ok(());
+                }
+                if let Value::Signal(signal) = receiver {
+                    let result = self.signal_method(&signal, name, args)?;
+                    self.stack.push(result);
+                    return Ok(());
+                }
+                if let Value::Object(object) = &receiver {
+                    let class = object.class();
+                    let method = class.vtable.get(&name)
+                        .ok_or_else(|| PalladError::UnknownMember { class: class.name.clone(), name: name.clone() })?;
+                    self.check_method_access(&class, method)?;
+                    *pc = self.enter(Rc::clone(method), args, Some(receiver), Some(*pc))?;
+                    return Ok(());
+                }
+                if let Value::Class(class) = &receiver {
+                    if let Some((declarer, _)) = class.static_field(&name) {
+                        self.check_access(declarer, &name)?;
+                    }
+                    match class.get_static(&name) {
+                        Some(Value::Function(function)) => *pc = self.enter(function, args, Some(receiver), Some(*pc))?,
+                        // An inner class: `Utils.Counter()`
+                        Some(Value::Class(inner)) => *pc = self.instantiate(inner, args, *pc)?,
+                        _ => return Err(PalladError::UnknownMember { class: class.name.clone(), name }),
+                    }
+                    return Ok(());
+                }
+                if let Value::Module(module) = &receiver {
+                    self.check_module_access(module, &name)?;
+                    match module.get(&name) {
+                        Some(Value::Function(function)) => *pc = self.enter(function, args, None, Some(*pc))?,
+                        Some(Value::Class(class)) => *pc = self.instantiate(class, args, *pc)?,
+                        Some(other) => return Err(PalladError::NotCallable { value: other }),
+                        None => return Err(PalladError::UnknownImport { module: module.name(), name }),
+                    }
+                    return Ok(());
+                }
+                let method = self.methods.lookup(&receiver, &name)
+                    .ok_or(PalladError::UnknownMethod { type_name: receiver.type_name(), name })?;
+                self.stack.push(method(&receiver, &args, &self.decimal)?);
+            }
+            Instr::GetAttr(name) => {
+                let target = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "attribute" })?;
+                match &target {
+                    Value::Object(object) => {
+                        if let Some((declarer, _)) = object.class().field(name) {
+                            self.check_access(declarer, name)?;
                         }
-                        for arg in args.into_iter().rev() {
-                            match arg {
-                                Value::None => println!("<none>"),
-                                Value::Int(n) => println!("{}", n),
-                                Value::Float(f) => println!("{}", f),
-                                Value::Str(s) => println!("{}", s),
-                            }
+                    }
+                    Value::Class(class) => {
+                        if let Some((declarer, _)) = class.static_field(name) {
+                            self.check_access(declarer, name)?;
                         }
-                    } else {
-                        return Err(PalladError::UnknownBuiltin { name });
                     }
+                    Value::Module(module) => self.check_module_access(module, name)?,
+                    _ => {}
                 }
-                Instr::Pop => {
-                    self.stack.pop()
-                        .ok_or(PalladError::StackUnderflow { operation: "Pop" })?;
+                self.stack.push(get_attribute(target, name.clone())?);
+            }
+            Instr::SetAttr(name) => {
+                let value = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "attribute assignment" })?;
+                let target = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "attribute assignment" })?;
+                if let Value::Class(class) = &target {
+                    if let Some((declarer, _)) = class.static_field(name) {
+                        self.check_access(declarer, name)?;
+                    }
+                    if !set_static(class, name, value)? {
+                        return Err(PalladError::UnknownMember { class: class.name.clone(), name: name.clone() });
+                    }
+                    return Ok(());
+                }
+                if let Value::Module(module) = &target {
+                    self.check_module_access(module, name)?;
+                    if module.get(name).is_none() {
+                        return Err(PalladError::UnknownImport { module: module.name(), name: name.clone() });
+                    }
+                    module.set(name.clone(), value);
+                    return Ok(());
+                }
+                let Value::Object(object) = &target else {
+                    return Err(PalladError::UnknownAttribute { type_name: target.type_name(), name: name.clone() });
+                };
+                let class = object.class();
+                let (declarer, field) = class.field(name)
+                    .ok_or_else(|| PalladError::UnknownMember { class: class.name.clone(), name: name.clone() })?;
+                self.check_access(declarer, name)?;
+                if let Some(expected) = &field.expected
+                    && !has_type(&value, expected)
+                {
+                    return Err(PalladError::FieldType {
+                        class: class.name.clone(),
+                        name: name.clone(),
+                        expected: expected.clone(),
+                        got: value,
+                    });
+                }
+                object.set(name, value);
+            }
+            Instr::EnterWith => {
+                let resource = self.stack.last()
+                    .ok_or(PalladError::StackUnderflow { operation: "with" })?;
+                if !matches!(resource, Value::File(_)) {
+                    return Err(PalladError::InvalidOperand { value: resource.clone(), operation: "open a 'with' block on" });
+                }
+                self.with_stack.push(resource.clone());
+            }
+            Instr::ExitWith => {
+                if let Some(Value::File(file)) = self.with_stack.pop() {
+                    file.close()?;
+                }
+            }
+            Instr::Pop => {
+                self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "Pop" })?;
+            }
+            Instr::Dup => {
+                let val = self.stack.last()
+                    .ok_or(PalladError::StackUnderflow { operation: "Dup" })?;
+                self.stack.push(val.clone());
+            }
+            Instr::Jump(target) => *pc = *target,
+            Instr::JumpIfTrue(target) => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "jump" })?;
+                if val.is_truthy() {
+                    *pc = *target;
+                }
+            }
+            Instr::JumpIfFalse(target) => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "jump" })?;
+                if !val.is_truthy() {
+                    *pc = *target;
+                }
+            }
+            Instr::MatchPattern { pattern, target } => {
+                let subject = self.stack.last()
+                    .ok_or(PalladError::StackUnderflow { operation: "match" })?;
+                let mut bindings = vec![];
+                if match_pattern(pattern, subject, &mut bindings) {
+                    let frame = self.frames.last_mut()
+                        .ok_or(PalladError::StackUnderflow { operation: "match" })?;
+                    frame.scopes.push(bindings.into_iter().collect());
+                } else {
+                    *pc = *target;
+                }
+            }
+            Instr::ExitScope => {
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.scopes.pop();
+                }
+            }
+            Instr::JumpTable { min, targets, default } => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "switch" })?;
+                *pc = match val {
+                    Value::Int(n) => n.checked_sub(*min)
+                        .and_then(|offset| usize::try_from(offset).ok())
+                        .and_then(|offset| targets.get(offset).copied())
+                        .unwrap_or(*default),
+                    _ => *default,
+                };
+            }
+            Instr::JumpMap { targets, default } => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "switch" })?;
+                let key = match val {
+                    Value::Int(n) => Some(CaseKey::Int(n)),
+                    Value::Str(s) => Some(CaseKey::Str(s)),
+                    _ => None,
+                };
+                *pc = key.and_then(|key| targets.get(&key).copied()).unwrap_or(*default);
+            }
+            Instr::SetupTry(target) => {
+                self.handlers.push(Handler {
+                    target: *target,
+                    stack_len: self.stack.len(),
+                    with_len: self.with_stack.len(),
+                    frames_len: self.frames.len(),
+                    scopes_len: self.frames.last().map_or(0, |frame| frame.scopes.len()),
+                });
+            }
+            Instr::PopTry => {
+                self.handlers.pop();
+            }
+            Instr::JumpIfNotException { classes, target } => {
+                let caught = match self.stack.last() {
+                    Some(Value::Exception(exception)) => classes.iter().any(|class| exception.is_instance(class)),
+                    _ => false,
+                };
+                if !caught {
+                    *pc = *target;
                 }
             }
+            Instr::MakeFunction(function) => self.stack.push(Value::Function(Rc::clone(function))),
+            Instr::Return => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "return" })?;
+                let frame = self.frames.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "return" })?;
+                self.stack.truncate(frame.stack_len);
+                self.handlers.truncate(frame.handlers_len);
+                while self.with_stack.len() > frame.with_len {
+                    if let Some(Value::File(file)) = self.with_stack.pop() {
+                        file.close()?;
+                    }
+                }
+
+                let function = &frame.function;
+                let valid = match function.returns.as_deref() {
+                    None => true,
+                    Some("void") => val == Value::None,
+                    Some(expected) => has_type(&val, expected),
+                };
+                if !valid {
+                    return Err(PalladError::ReturnType {
+                        function: function.name.clone(),
+                        expected: function.returns.clone().unwrap_or_default(),
+                        got: val,
+                    });
+                }
+                self.stack.push(val);
+                if let Some(return_pc) = frame.return_pc {
+                    *pc = return_pc;
+                }
+            }
+            Instr::MakeClass { name, parent, fields, statics, methods, constructors, static_methods, static_constructor } => {
+                let count = *static_constructor as usize + static_methods + constructors + methods + 2 + *parent as usize;
+                if count > self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "class" });
+                }
+                let function = |value: Option<Value>| match value {
+                    Some(Value::Function(function)) => Some(function),
+                    _ => None,
+                };
+                let static_constructor = static_constructor.then(|| self.stack.pop()).and_then(function);
+                let static_methods = self.stack.split_off(self.stack.len() - static_methods);
+                let constructors = self.stack.split_off(self.stack.len() - constructors).into_iter().map(Some).filter_map(function).collect();
+                let methods = self.stack.split_off(self.stack.len() - methods);
+                let (Some(static_init), Some(init)) = (function(self.stack.pop()), function(self.stack.pop())) else {
+                    return Err(PalladError::StackUnderflow { operation: "class" });
+                };
+                let parent = match parent.then(|| self.stack.pop()).flatten() {
+                    Some(Value::Class(parent)) => Some(parent),
+                    Some(other) => return Err(PalladError::InvalidOperand { value: other, operation: "extend" }),
+                    None => None,
+                };
+
+                // Methods override the inherited ones of the same name
+                let mut vtable = parent.as_ref().map_or_else(HashMap::new, |parent| parent.vtable.clone());
+                for method in methods {
+                    if let Value::Function(method) = method {
+                        vtable.insert(method.name.clone(), method);
+                    }
+                }
+                // Static variables start as `none`, static functions as themselves
+                let mut values: HashMap<String, Value> = statics.iter().map(|field| (field.name.clone(), Value::None)).collect();
+                for value in static_methods {
+                    if let Value::Function(function) = &value {
+                        values.insert(function.name.clone(), value.clone());
+                    }
+                }
+                let class = Class {
+                    name: name.clone(),
+                    parent,
+                    fields: fields.clone(),
+                    init,
+                    vtable,
+                    constructors,
+                    static_init,
+                    static_constructor,
+                    ready: Cell::new(false),
+                    static_fields: statics.clone(),
+                    statics: RefCell::new(values),
+                };
+                self.stack.push(Value::Class(Rc::new(class)));
+            }
+            Instr::InitStatics => {
+                let Some(Value::Class(class)) = self.stack.pop() else {
+                    return Err(PalladError::StackUnderflow { operation: "class" });
+                };
+                let static_init = Rc::clone(&class.static_init);
+                *pc = self.enter(static_init, vec![], Some(Value::Class(class)), Some(*pc))?;
+            }
+            Instr::InitFields => {
+                let receiver = self.variable("self").unwrap_or(Value::None);
+                match self.method_class() {
+                    Some(class) => *pc = self.enter(Rc::clone(&class.init), vec![], Some(receiver), Some(*pc))?,
+                    None => self.stack.push(Value::None),
+                }
+            }
+            Instr::InitParent => {
+                let receiver = self.variable("self").unwrap_or(Value::None);
+                match self.super_class() {
+                    Some(parent) => *pc = self.enter(Rc::clone(&parent.init), vec![], Some(receiver), Some(*pc))?,
+                    None => self.stack.push(Value::None),
+                }
+            }
+            Instr::CallSuper { name, argc } => {
+                if *argc >= self.stack.len() {
+                    return Err(PalladError::StackUnderflow { operation: "super call" });
+                }
+                let args = self.stack.split_off(self.stack.len() - argc);
+                let receiver = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "super call" })?;
+                let parent = self.super_class()
+                    .ok_or_else(|| PalladError::InvalidOperand { value: receiver.clone(), operation: "call 'super' on" })?;
+                let method = parent.vtable.get(name)
+                    .ok_or_else(|| PalladError::UnknownMember { class: parent.name.clone(), name: name.clone() })?;
+                self.check_method_access(&parent, method)?;
+                *pc = self.enter(Rc::clone(method), args, Some(receiver), Some(*pc))?;
+            }
+            Instr::MakeSignal { name, params } => {
+                self.stack.push(Value::Signal(SignalRef::new(name.clone(), params.clone())));
+            }
+            Instr::Import { path, alias, names, line } => self.import(path, alias.as_ref(), names.as_deref(), *line)?,
+            Instr::Exclude(paths) => {
+                let module = self.module().clone();
+                for path in paths {
+                    // A module that doesn't exist won't be imported anyway
+                    match module::resolve(&module.dir(), path, 0) {
+                        Ok(Target::File(file)) => module.exclude(file),
+                        Ok(Target::Folder(files)) => files.into_iter().for_each(|file| module.exclude(file)),
+                        Err(_) => {}
+                    }
+                }
+            }
+            Instr::Raise => {
+                let val = self.stack.pop()
+                    .ok_or(PalladError::StackUnderflow { operation: "raise" })?;
+                return Err(match val {
+                    Value::Exception(exception) => exception.into_error(),
+                    Value::Str(message) => PalladError::Raised { class: "RuntimeError".to_string(), message },
+                    other => PalladError::InvalidOperand { value: other, operation: "raise" },
+                });
+            }
         }
         Ok(())
     }
@@ -149,6 +974,604 @@ impl VM {
         Ok(())
     }
 
+    /// Implements `print(args..., sep=..., end=...)`: writes the arguments separated by `sep` and
+    /// followed by `end` (both default to a newline, so each argument gets its own line).
+    fn print(&mut self, args: &[Value], kwargs: Vec<(String, Value)>) -> Result<(), PalladError> {
+        let mut sep = "\n".to_string();
+        let mut end = "\n".to_string();
+        for (name, value) in kwargs {
+            let target = match name.as_str() {
+                "sep" => &mut sep,
+                "end" => &mut end,
+                _ => return Err(PalladError::UnexpectedKeyword { function: "print".to_string(), name }),
+            };
+            match value {
+                Value::Str(s) => *target = s,
+                Value::None => {}
+                other => return Err(PalladError::ArgumentType {
+                    function: "print".to_string(),
+                    expected: "a string",
+                    got: other,
+                }),
+            }
+        }
+
+        // One write per call, so a callback sink sees each print whole
+        let text: Vec<String> = args.iter().map(Value::stringify).collect();
+        self.output.write_all(format!("{}{}", text.join(&sep), end).as_bytes())
+            .map_err(|e| PalladError::Io { operation: "print", message: e.to_string() })
+    }
+
+    /// The variable `name`: a binding of the `match` arms being run, a local of the current call,
+    /// a field of the running method's `self`, a static of the class the method is declared in,
+    /// or else a global of the running code's module.
+    fn variable(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.frames.last()
+            .and_then(|frame| frame.scopes.iter().rev().find_map(|scope| scope.get(name)))
+        {
+            return Some(value.clone());
+        }
+        if let Some(frame) = self.frames.last()
+            && !frame.module_code
+        {
+            if let Some(value) = frame.locals.get(name) {
+                return Some(value.clone());
+            }
+            if let Some((object, _)) = self.self_field(name) {
+                return object.get(name);
+            }
+            if let Some(class) = &frame.class
+                && let Some((declarer, _)) = class.static_field(name)
+                && (!object::is_private(name) || std::ptr::eq(&**class, declarer))
+            {
+                return declarer.statics.borrow().get(name).cloned();
+            }
+        }
+        self.module().get(name)
+    }
+
+    /// Sets the variable `name`: a binding of the `match` arms being run if one has that name;
+    /// inside a call, a static of the running method's class if it has one by that name and there
+    /// is no such local, or else a local; a global of the running module outside any call. A local
+    /// or global is declared with the type `expected`, or with none.
+    fn store(&mut self, name: String, value: Value, expected: Option<Annotation>) -> Result<(), PalladError> {
+        if let Some(binding) = self.frames.last_mut()
+            .and_then(|frame| frame.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(&name)))
+        {
+            *binding = value;
+            return Ok(());
+        }
+        let Some(frame) = self.frames.last_mut().filter(|frame| !frame.module_code) else {
+            let module = self.module();
+            module.annotate(name.clone(), expected);
+            module.set(name, value);
+            return Ok(());
+        };
+        if !frame.locals.contains_key(&name)
+            && let Some(class) = &frame.class
+            && let Some((declarer, _)) = class.static_field(&name)
+            && (!object::is_private(&name) || std::ptr::eq(&**class, declarer))
+        {
+            set_static(class, &name, value)?;
+            return Ok(());
+        }
+        match expected {
+            Some(expected) => frame.annotations.insert(name.clone(), expected),
+            None => frame.annotations.remove(&name),
+        };
+        frame.locals.insert(name, value);
+        Ok(())
+    }
+
+    /// Sets the existing variable `name`, looked up like `variable` does, for `name = value`. Only
+    /// declarations create variables, so a name that isn't one is an error.
+    fn assign(&mut self, name: &str, value: Value) -> Result<(), PalladError> {
+        let Some(frame) = self.frames.last_mut() else {
+            return Err(PalladError::UndefinedVariable { name: name.to_string() });
+        };
+        if let Some(binding) = frame.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name)) {
+            *binding = value;
+            return Ok(());
+        }
+        if !frame.module_code {
+            if let Some(local) = frame.locals.get_mut(name) {
+                if let Some(expected) = frame.annotations.get(name) {
+                    check_annotation(name, expected, &value)?;
+                }
+                *local = value;
+                return Ok(());
+            }
+            if let Some((object, expected)) = self.self_field(name) {
+                if let Some(expected) = expected
+                    && !has_type(&value, &expected)
+                {
+                    return Err(PalladError::FieldType { class: object.class().name.clone(), name: name.to_string(), expected, got: value });
+                }
+                object.set(name, value);
+                return Ok(());
+            }
+            if let Some(class) = self.frames.last().and_then(|frame| frame.class.clone())
+                && let Some((declarer, _)) = class.static_field(name)
+                && (!object::is_private(name) || std::ptr::eq(&*class, declarer))
+            {
+                set_static(&class, name, value)?;
+                return Ok(());
+            }
+        }
+        let module = self.module();
+        if module.get(name).is_none() {
+            return Err(PalladError::UndefinedVariable { name: name.to_string() });
+        }
+        if let Some(expected) = module.annotation(name) {
+            check_annotation(name, &expected, &value)?;
+        }
+        module.set(name.to_string(), value);
+        Ok(())
+    }
+
+    /// The running method's `self`, if it has a field `name` its class may use, with the field's
+    /// type.
+    fn self_field(&self, name: &str) -> Option<(ObjectRef, Option<String>)> {
+        let frame = self.frames.last().filter(|frame| !frame.module_code)?;
+        let class = frame.class.as_ref()?;
+        let Some(Value::Object(object)) = frame.locals.get("self") else {
+            return None;
+        };
+        let (declarer, field) = class.field(name)?;
+        (!object::is_private(name) || std::ptr::eq(&**class, declarer))
+            .then(|| (object.clone(), field.expected.clone()))
+    }
+
+    /// Starts a call of `function` with `args`, checked against its parameters, and with `self`
+    /// set to `receiver` for a method: pushes its frame and returns where its body starts. Its
+    /// `Return` checks the result against the return type and continues at `return_pc`.
+    fn enter(&mut self, function: Rc<Function>, args: Vec<Value>, receiver: Option<Value>, return_pc: Option<usize>) -> Result<usize, PalladError> {
+        check_params(&function.name, &function.params, &args)?;
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(PalladError::RecursionLimit { depth: MAX_CALL_DEPTH });
+        }
+        let entry = function.entry;
+        let class = function.owner.as_deref().and_then(|owner| match &receiver {
+            Some(Value::Object(object)) => object.class().ancestor(owner),
+            Some(Value::Class(class)) => class.ancestor(owner),
+            _ => None,
+        });
+        let mut locals: HashMap<String, Value> = function.params.iter().map(|param| param.name.clone()).zip(args).collect();
+        let annotations = function.params.iter()
+            .filter_map(|param| Some((param.name.clone(), Annotation::Type(param.expected.clone()?))))
+            .collect();
+        if let Some(receiver @ Value::Object(_)) = receiver {
+            locals.insert("self".to_string(), receiver);
+        }
+        self.frames.push(Frame {
+            function,
+            locals,
+            annotations,
+            scopes: vec![],
+            class,
+            return_pc,
+            stack_len: self.stack.len(),
+            with_len: self.with_stack.len(),
+            handlers_len: self.handlers.len(),
+            module_code: false,
+        });
+        Ok(entry)
+    }
+
+    /// Calls the function `callee` from the VM itself (e.g. a signal handler), running it to
+    /// completion, and returns its result.
+    fn call(&mut self, callee: &Value, args: Vec<Value>) -> Result<Value, PalladError> {
+        let Value::Function(function) = callee else {
+            return Err(PalladError::NotCallable { value: callee.clone() });
+        };
+        self.call_function(Rc::clone(function), args, None)
+    }
+
+    /// Runs `function` with `args` (and `receiver`, as for `enter`) to completion, and returns its
+    /// result.
+    fn call_function(&mut self, function: Rc<Function>, args: Vec<Value>, receiver: Option<Value>) -> Result<Value, PalladError> {
+        let entry = self.enter(function, args, receiver, None)?;
+        self.execute(entry, self.frames.len())?;
+        self.stack.pop()
+            .ok_or(PalladError::StackUnderflow { operation: "return" })
+    }
+
+    /// Starts creating an instance of `class` from `args`, once its static constructors have run:
+    /// enters the constructor `args` select (or the initializer of a class without any), which
+    /// returns the instance to `return_pc`.
+    fn instantiate(&mut self, class: Rc<Class>, args: Vec<Value>, return_pc: usize) -> Result<usize, PalladError> {
+        self.run_static_constructors(&class)?;
+        let constructor = select_constructor(&class, &args)?;
+        let object = Value::Object(ObjectRef::new(class));
+        self.enter(constructor, args, Some(object), Some(return_pc))
+    }
+
+    /// Runs the static constructors of `class` and its ancestors (the root's first) that haven't
+    /// returned `true` yet, before an instance is created. A class counts as ready while its static
+    /// constructor runs, so that it can create instances.
+    fn run_static_constructors(&mut self, class: &Rc<Class>) -> Result<(), PalladError> {
+        if let Some(parent) = &class.parent {
+            self.run_static_constructors(parent)?;
+        }
+        let Some(constructor) = &class.static_constructor else {
+            return Ok(());
+        };
+        if class.ready.replace(true) {
+            return Ok(());
+        }
+        let result = self.call_function(Rc::clone(constructor), vec![], Some(Value::Class(Rc::clone(class))));
+        if result != Ok(Value::Bool(true)) {
+            class.ready.set(false);
+            result?;
+            return Err(PalladError::StaticConstructor { class: class.name.clone() });
+        }
+        Ok(())
+    }
+
+    /// The module of the running code, whose globals it uses.
+    fn module(&self) -> &ModuleRef {
+        let index = self.frames.last().map_or(0, |frame| frame.function.module);
+        &self.modules[index]
+    }
+
+    /// Runs `Instr::Import` at `line`. A folder's modules skip the importing module, the modules
+    /// it excludes, and the modules whose code is still running.
+    fn import(&mut self, path: &str, alias: Option<&String>, names: Option<&[(String, String)]>, line: usize) -> Result<(), PalladError> {
+        let importer = self.module().clone();
+        let files = match module::resolve(&importer.dir(), path, line)? {
+            Target::File(file) => {
+                if importer.is_excluded(&file) {
+                    return Err(PalladError::ExcludedModule { path: path.to_string(), line });
+                }
+                let module = self.load_module(file)?;
+                return self.bind(module, alias, names);
+            }
+            Target::Folder(files) => files,
+        };
+        match names {
+            // `from "folder/" import a, b` imports the modules named
+            Some(names) if !names.is_empty() => {
+                for (name, binding) in names {
+                    let file = files.iter()
+                        .find(|file| file.file_stem().is_some_and(|stem| stem == name.as_str()))
+                        .ok_or_else(|| PalladError::UnknownImport { module: path.to_string(), name: name.clone() })?;
+                    if importer.is_excluded(file) {
+                        return Err(PalladError::ExcludedModule { path: format!("{}{}", path, name), line });
+                    }
+                    let module = self.load_module(file.clone())?;
+                    self.store(binding.clone(), module.export(), None)?;
+                }
+            }
+            _ => {
+                for file in files {
+                    let running = self.module_ids.get(&file).is_some_and(|index| self.importing.contains(index));
+                    if running || importer.path().as_ref() == Some(&file) || importer.is_excluded(&file) {
+                        continue;
+                    }
+                    let module = self.load_module(file)?;
+                    self.store(module.name(), module.export(), None)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the module imported by `Instr::Import` (the class of a class module), or the names
+    /// it imports from it (the statics of a class module's class).
+    fn bind(&mut self, module: ModuleRef, alias: Option<&String>, names: Option<&[(String, String)]>) -> Result<(), PalladError> {
+        let Some(names) = names else {
+            let name = alias.cloned().unwrap_or_else(|| module.name());
+            return self.store(name, module.export(), None);
+        };
+        if let Value::Class(class) = module.export() {
+            return self.bind_statics(&class, names);
+        }
+        if names.is_empty() {
+            for (name, value) in module.public_globals() {
+                self.store(name, value, None)?;
+            }
+            return Ok(());
+        }
+        for (name, binding) in names {
+            self.check_module_access(&module, name)?;
+            let value = module.get(name)
+                .ok_or_else(|| PalladError::UnknownImport { module: module.name(), name: name.clone() })?;
+            self.store(binding.clone(), value, None)?;
+        }
+        Ok(())
+    }
+
+    /// Stores the statics of a class module's `class` named by `from module import names`, or all
+    /// of its public ones when `names` is empty.
+    fn bind_statics(&mut self, class: &Rc<Class>, names: &[(String, String)]) -> Result<(), PalladError> {
+        if names.is_empty() {
+            for field in &class.static_fields {
+                if let Some(value) = class.get_static(&field.name).filter(|_| !object::is_private(&field.name)) {
+                    self.store(field.name.clone(), value, None)?;
+                }
+            }
+            return Ok(());
+        }
+        for (name, binding) in names {
+            let (declarer, _) = class.static_field(name)
+                .ok_or_else(|| PalladError::UnknownMember { class: class.name.clone(), name: name.clone() })?;
+            self.check_access(declarer, name)?;
+            let value = class.get_static(name).unwrap_or(Value::None);
+            self.store(binding.clone(), value, None)?;
+        }
+        Ok(())
+    }
+
+    /// The module in `file`, which is loaded and run on its first import. Importing a module whose
+    /// code is still running fails with the chain of imports leading back to it.
+    fn load_module(&mut self, file: PathBuf) -> Result<ModuleRef, PalladError> {
+        if let Some(&index) = self.module_ids.get(&file) {
+            if let Some(start) = self.importing.iter().position(|&running| running == index) {
+                let chain = self.importing[start..].iter()
+                    .chain([&index])
+                    .map(|&module| self.modules[module].file_name())
+                    .collect();
+                return Err(PalladError::CircularImport { chain });
+            }
+            return Ok(self.modules[index].clone());
+        }
+
+        let index = self.modules.len();
+        let name = file.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let module::Compiled { program: mut code, warnings, class } = module::load(&file, &name, index)?;
+        let module = ModuleRef::new(name, Some(file.clone()), class);
+        self.modules.push(module.clone());
+        self.module_ids.insert(file.clone(), index);
+        self.warnings.extend(warnings.into_iter().map(|warning| (file.display().to_string(), warning)));
+
+        // The module's code goes after the program, and returns to the import when it ends
+        let entry = self.program.len();
+        ir::relocate(&mut code, entry);
+        let mut program = self.program.to_vec();
+        program.extend(code);
+        program.extend([Instr::LoadNone, Instr::Return]);
+        self.program = program.into();
+
+        let function = Rc::new(Function { name: module.name(), params: vec![], returns: None, entry, owner: None, module: index });
+        self.importing.push(index);
+        let result = self.run_module(function);
+        self.importing.pop();
+        if result.is_err() {
+            // A module that failed runs again when imported again
+            self.module_ids.remove(&file);
+        }
+        result.map(|()| module)
+    }
+
+    /// Runs the code of a module on its import, with its variables as the module's globals.
+    fn run_module(&mut self, code: Rc<Function>) -> Result<(), PalladError> {
+        let entry = self.enter(code, vec![], None, None)?;
+        if let Some(frame) = self.frames.last_mut() {
+            frame.module_code = true;
+        }
+        self.execute(entry, self.frames.len())?;
+        self.stack.pop()
+            .ok_or(PalladError::StackUnderflow { operation: "import" })?;
+        Ok(())
+    }
+
+    /// Fails unless the running code may use the global `name` of `module`: private globals can
+    /// only be used by the module's own code.
+    fn check_module_access(&self, module: &ModuleRef, name: &str) -> Result<(), PalladError> {
+        if !object::is_private(name) || self.module() == module {
+            return Ok(());
+        }
+        Err(PalladError::PrivateName { module: module.name(), name: name.to_string() })
+    }
+
+    /// Fails unless the running code may use the member `name` declared by `declarer`: public
+    /// members can be used anywhere, private ones only by the code of the class declaring them.
+    fn check_access(&self, declarer: &Class, name: &str) -> Result<(), PalladError> {
+        if !object::is_private(name) || self.method_class().is_some_and(|class| std::ptr::eq(&*class, declarer)) {
+            return Ok(());
+        }
+        Err(PalladError::AccessViolation { class: declarer.name.clone(), name: name.to_string() })
+    }
+
+    /// `check_access` for `method`, found in the vtable of `class`.
+    fn check_method_access(&self, class: &Rc<Class>, method: &Function) -> Result<(), PalladError> {
+        match method.owner.as_deref().and_then(|owner| class.ancestor(owner)) {
+            Some(declarer) => self.check_access(&declarer, &method.name),
+            None => Ok(()),
+        }
+    }
+
+    /// The class the running method is declared in.
+    fn method_class(&self) -> Option<Rc<Class>> {
+        self.frames.last()?.class.clone()
+    }
+
+    /// The parent of the class the running method is declared in, where `super` looks up methods.
+    fn super_class(&self) -> Option<Rc<Class>> {
+        self.method_class()?.parent.clone()
+    }
+
+    /// Runs the methods of signals: `connect(handler[, flags])`, `disconnect(handler)`,
+    /// `is_connected(handler)` and `emit(args)`.
+    fn signal_method(&mut self, signal: &SignalRef, name: String, args: Vec<Value>) -> Result<Value, PalladError> {
+        let function = format!("{}.{}", signal.name(), name);
+        match name.as_str() {
+            "connect" => {
+                methods::check_args(&function, &args, 1, 2)?;
+                let flags = match args.get(1) {
+                    None => 0,
+                    Some(Value::Int(flags)) => *flags,
+                    Some(other) => return Err(PalladError::ArgumentType {
+                        function,
+                        expected: "connection flags",
+                        got: other.clone(),
+                    }),
+                };
+                signal.connect(args[0].clone(), flags)?;
+            }
+            "disconnect" => {
+                methods::check_args(&function, &args, 1, 1)?;
+                signal.disconnect(&args[0])?;
+            }
+            "is_connected" => {
+                methods::check_args(&function, &args, 1, 1)?;
+                return Ok(Value::Bool(signal.is_connected(&args[0])));
+            }
+            "emit" => {
+                check_params(&function, &signal.params(), &args)?;
+                if self.emit_depth >= MAX_EMIT_DEPTH {
+                    return Err(PalladError::RecursionLimit { depth: MAX_EMIT_DEPTH });
+                }
+                self.emit_depth += 1;
+                let result = self.emit(signal, args);
+                self.emit_depth -= 1;
+                if result.is_err() && self.emit_depth == 0 {
+                    // A failed emit drops the handlers it deferred, so a later emit doesn't run them
+                    self.deferred.clear();
+                }
+                result?;
+                if self.emit_depth == 0 {
+                    self.run_deferred()?;
+                }
+            }
+            _ => return Err(PalladError::UnknownMethod { type_name: "signal", name }),
+        }
+        Ok(Value::None)
+    }
+
+    /// Calls the handlers connected when the emit starts, in order. Handlers connected meanwhile
+    /// wait for the next emit, and handlers disconnected before their turn are skipped. One-shot
+    /// connections are removed before their handler runs; deferred ones are queued.
+    fn emit(&mut self, signal: &SignalRef, args: Vec<Value>) -> Result<(), PalladError> {
+        for connection in signal.connections() {
+            if !signal.is_connected(&connection.callable) {
+                continue;
+            }
+            if connection.one_shot {
+                signal.disconnect(&connection.callable)?;
+            }
+            if connection.deferred {
+                self.deferred.push_back((connection.callable, args.clone()));
+            } else {
+                self.call(&connection.callable, args.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls the queued deferred handlers, including any their own emits queue.
+    fn run_deferred(&mut self) -> Result<(), PalladError> {
+        self.emit_depth += 1;
+        let mut result = Ok(());
+        while let Some((callable, args)) = self.deferred.pop_front() {
+            result = self.call(&callable, args).map(|_| ());
+            if result.is_err() {
+                self.deferred.clear();
+                break;
+            }
+        }
+        self.emit_depth -= 1;
+        result
+    }
+
+    /// Implements the `log` methods: `log.debug`, `log.info` (or `log.add` and `log.message`),
+    /// `log.warning` and `log.error` take a message and an optional color, and
+    /// `log.set_level(name)` drops records below the named level.
+    fn log(&mut self, name: String, args: &[Value], line: usize) -> Result<(), PalladError> {
+        let function = format!("log.{}", name);
+        let level = match name.as_str() {
+            "debug" => LogLevel::Debug,
+            "info" | "add" | "message" => LogLevel::Info,
+            "warning" => LogLevel::Warning,
+            "error" => LogLevel::Error,
+            "set_level" => {
+                methods::check_args(&function, args, 1, 1)?;
+                let level = match &args[0] {
+                    Value::Str(name) => LogLevel::from_name(name),
+                    _ => None,
+                };
+                self.logger.level = level.ok_or(PalladError::InvalidArgument {
+                    function,
+                    reason: "level must be 'debug', 'info', 'warning' or 'error'",
+                })?;
+                return Ok(());
+            }
+            _ => return Err(PalladError::UnknownMethod { type_name: "namespace", name }),
+        };
+        methods::check_args(&function, args, 1, 2)?;
+        let color = match args.get(1) {
+            None => None,
+            Some(Value::Color(color)) => Some(*color),
+            Some(other) => return Err(PalladError::ArgumentType {
+                function,
+                expected: "a color",
+                got: other.clone(),
+            }),
+        };
+
+        let record = LogRecord {
+            level,
+            message: args[0].stringify(),
+            line,
+            timestamp: std::time::SystemTime::now(),
+            color,
+        };
+        self.logger.log(record)
+            .map_err(|e| PalladError::Io { operation: "log", message: e.to_string() })
+    }
+
+    /// Implements `input(prompt?)`: writes the prompt (if any) to the VM's output, then reads one
+    /// line from the VM's input without its line ending, or `none` at end of input.
+    fn read_input(&mut self, prompt: Option<&Value>) -> Result<Value, PalladError> {
+        let io_error = |e: io::Error| PalladError::Io { operation: "input", message: e.to_string() };
+        if let Some(prompt) = prompt {
+            self.output.write_all(prompt.stringify().as_bytes()).map_err(io_error)?;
+            self.output.flush().map_err(io_error)?;
+        }
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line).map_err(io_error)? == 0 {
+            return Ok(Value::None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Value::Str(line))
+    }
+
+    /// Pops two values and pushes the `bool` result of comparing them.
+    ///
+    /// `==` and `!=` accept any two values (numbers compare by value across `int`, `float` and
+    /// `decimal`, other mixed types are unequal). Ordering is defined between numbers (except
+    /// `decimal` with `float`) and between strings, by Unicode code point; other combinations are a
+    /// `TypeMismatch`.
+    fn execute_comparison(&mut self, comparison: Comparison) -> Result<(), PalladError> {
+        let b = self.stack.pop()
+            .ok_or(PalladError::StackUnderflow { operation: "compare" })?;
+        let a = self.stack.pop()
+            .ok_or(PalladError::StackUnderflow { operation: "compare" })?;
+
+        let result = match comparison {
+            Comparison::Eq => values_equal(&a, &b),
+            Comparison::NotEq => !values_equal(&a, &b),
+            _ if !comparable(&a, &b) => {
+                return Err(PalladError::TypeMismatch { left: a, right: b, operation: "compare" });
+            }
+            // `NaN` is unordered, so every ordering comparison with it is false
+            _ => compare_values(&a, &b).is_some_and(|ordering| match comparison {
+                Comparison::Lt => ordering == Ordering::Less,
+                Comparison::Le => ordering != Ordering::Greater,
+                Comparison::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }),
+        };
+        self.stack.push(Value::Bool(result));
+        Ok(())
+    }
+
     /// Pop two values from the VM stack and compute the binary operation identified by `op`.
     ///
     /// Supported operation names: `Op` enum.
@@ -166,7 +1589,7 @@ impl VM {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// use std::collections::HashMap;
     ///
     /// // Minimal VM and Value setup for the example
@@ -202,7 +1625,9 @@ impl VM {
         if matches!(op, Op::Div | Op::IntDiv | Op::Mod) {
             let is_zero = match &b {
                 Value::Int(n) => *n == 0,
+                Value::BigInt(n) => n.is_zero(),
                 Value::Float(f) => *f == 0.0,
+                Value::Decimal(d) => d.is_zero(),
                 _ => false, // Others raise PalladError::TypeMismatch
             };
             if is_zero {
@@ -210,6 +1635,15 @@ impl VM {
             }
         }
 
+        // Integer results go through the overflow policy
+        if let Some(result) = self.integer_operation(&a, &b, &op)? {
+            return Ok(result);
+        }
+        if let Some(result) = self.decimal_operation(&a, &b, &op)? {
+            return Ok(result);
+        }
+        let (a, b) = widen_big_operands(a, b);
+
         Ok(match (&a, &b, &op) {
             // 'none' is invalid in all operations.
             // Other invalid operations:
@@ -219,21 +1653,24 @@ impl VM {
             
             // add (+)
             // int
-            (Value::Int(a), Value::Int(b), Op::Add) => Value::Int(a + b),
             (Value::Int(a), Value::Float(b), Op::Add) => Value::Float(*a as f64 + b),
             (Value::Int(a), Value::Str(b), Op::Add) => Value::Str(a.to_string() + b),
+            (Value::BigInt(a), Value::Str(b), Op::Add) => Value::Str(a.to_string() + b),
             // float
             (Value::Float(a), Value::Int(b), Op::Add) => Value::Float(a + *b as f64),
             (Value::Float(a), Value::Float(b), Op::Add) => Value::Float(a + b),
             (Value::Float(a), Value::Str(b), Op::Add) => Value::Str(a.to_string() + b),
+            // decimal
+            (Value::Decimal(a), Value::Str(b), Op::Add) => Value::Str(a.to_string() + b),
             // string
             (Value::Str(a), Value::Int(b), Op::Add) => Value::Str(a.clone() + &b.to_string()),
+            (Value::Str(a), Value::BigInt(b), Op::Add) => Value::Str(a.clone() + &b.to_string()),
             (Value::Str(a), Value::Float(b), Op::Add) => Value::Str(a.clone() + &b.to_string()),
+            (Value::Str(a), Value::Decimal(b), Op::Add) => Value::Str(a.clone() + &b.to_string()),
             (Value::Str(a), Value::Str(b), Op::Add) => Value::Str(a.clone() + b),
 
             // subtract (-)
             // int
-            (Value::Int(a), Value::Int(b), Op::Sub) => Value::Int(a - b),
             (Value::Int(a), Value::Float(b), Op::Sub) => Value::Float(*a as f64 - b),
             // float
             (Value::Float(a), Value::Int(b), Op::Sub) => Value::Float(a - *b as f64),
@@ -241,7 +1678,6 @@ impl VM {
 
             // multiply (*)
             // int
-            (Value::Int(a), Value::Int(b), Op::Mul) => Value::Int(a * b),
             (Value::Int(a), Value::Float(b), Op::Mul) => Value::Float(*a as f64 * b),
             // float
             (Value::Float(a), Value::Int(b), Op::Mul) => Value::Float(a * *b as f64),
@@ -258,6 +1694,13 @@ impl VM {
                     .ok_or(PalladError::RepeatOverflow)?;
                 Value::Str(a.repeat(count))
             },
+            (Value::Str(_), Value::BigInt(b), Op::Mul) => {
+                return Err(if b.is_negative() {
+                    PalladError::NegativeRepeat
+                } else {
+                    PalladError::RepeatOverflow
+                });
+            }
 
             // divide (/)
             // int
@@ -269,11 +1712,6 @@ impl VM {
 
             // integer-divide (//)
             // int
-            (Value::Int(a), Value::Int(b), Op::IntDiv) => {
-                a.checked_div(*b)
-                    .map(Value::Int)
-                    .ok_or(PalladError::IntDivOverflow)?
-            }
             (Value::Int(a), Value::Float(b), Op::IntDiv) => {
                 let result = (*a as f64 / b).floor();
                 if result.is_finite() && result >= i64::MIN as f64 && result <= i64::MAX as f64 {
@@ -302,12 +1740,18 @@ impl VM {
 
             // mod (%)
             // int
-            (Value::Int(a), Value::Int(b), Op::Mod) => Value::Int(a % b),
             (Value::Int(a), Value::Float(b), Op::Mod) => Value::Float(*a as f64 % b),
             // float
             (Value::Float(a), Value::Int(b), Op::Mod) => Value::Float(a % *b as f64),
             (Value::Float(a), Value::Float(b), Op::Mod) => Value::Float(a % b),
 
+            // power (**)
+            // int
+            (Value::Int(a), Value::Float(b), Op::Pow) => float_pow(*a as f64, *b)?,
+            // float
+            (Value::Float(a), Value::Int(b), Op::Pow) => float_pow(*a, *b as f64)?,
+            (Value::Float(a), Value::Float(b), Op::Pow) => float_pow(*a, *b)?,
+
             _ => return Err(PalladError::TypeMismatch {
                 left: a,
                 right: b,
@@ -315,4 +1759,497 @@ impl VM {
             }),
         })
     }
-}
\ No newline at end of file
+
+    /// Applies `op` to two integer operands (`int` or promoted big integers) under the VM's
+    /// `OverflowPolicy`.
+    ///
+    /// Returns `Ok(None)` when the operands aren't both integers, or for `int / int` which always
+    /// produces a float, leaving the operation to the general dispatch in `pop_two_operands`.
+    fn integer_operation(&self, a: &Value, b: &Value, op: &Op) -> Result<Option<Value>, PalladError> {
+        let (a, b) = match (a, b) {
+            (Value::Int(_), Value::Int(_)) if matches!(op, Op::Div) => return Ok(None),
+            (Value::Int(a), Value::Int(b)) => (*a, *b),
+            (Value::Int(_) | Value::BigInt(_), Value::Int(_) | Value::BigInt(_)) => {
+                return self.big_operation(to_big(a), to_big(b), op).map(Some);
+            }
+            _ => return Ok(None),
+        };
+
+        let checked = match op {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+            Op::IntDiv => a.checked_div(b),
+            // `i64::MIN % -1` is mathematically 0, only the machine instruction overflows
+            Op::Mod => Some(a.wrapping_rem(b)),
+            Op::Pow if b < 0 => return int_pow_negative(a, b).map(Some),
+            Op::Pow => checked_int_pow(a, b),
+            Op::Shl | Op::Shr if b < 0 => return Err(PalladError::NegativeShift),
+            Op::Shl => checked_int_shl(a, b),
+            // Arithmetic shift: large counts leave only the sign
+            Op::Shr => Some(a >> b.min(i64::BITS as i64 - 1)),
+            Op::BitAnd => Some(a & b),
+            Op::BitOr => Some(a | b),
+            Op::BitXor => Some(a ^ b),
+            Op::Div => None,
+        };
+        if let Some(n) = checked {
+            return Ok(Some(Value::Int(n)));
+        }
+
+        Ok(Some(match (self.overflow, op) {
+            (OverflowPolicy::Error, _) => return Err(overflow_error(op)),
+            // Exponents and shift counts can be far too large to compute exactly first
+            (OverflowPolicy::Wrap, Op::Pow) => Value::Int(wrapping_int_pow(a, b as u64)),
+            (OverflowPolicy::Wrap, Op::Shl) => Value::Int(if b >= i64::BITS as i64 { 0 } else { a << b }),
+            (OverflowPolicy::Saturate, Op::Pow | Op::Shl) => {
+                let negative = a < 0 && (matches!(op, Op::Shl) || b % 2 == 1);
+                Value::Int(if negative { i64::MIN } else { i64::MAX })
+            }
+            _ => self.big_operation(BigInt::from(a), BigInt::from(b), op)?,
+        }))
+    }
+
+    /// Applies `op` when one operand is a decimal and the other is a decimal or an integer, using
+    /// the VM's `DecimalContext`.
+    ///
+    /// Returns `Ok(None)` for other operand combinations (including decimals with floats, which
+    /// would silently lose exactness), leaving them to the general dispatch to report.
+    fn decimal_operation(&self, a: &Value, b: &Value, op: &Op) -> Result<Option<Value>, PalladError> {
+        let (x, y) = match (a, b) {
+            (Value::Decimal(_), Value::Decimal(_) | Value::Int(_) | Value::BigInt(_))
+            | (Value::Int(_) | Value::BigInt(_), Value::Decimal(_)) => (to_decimal(a), to_decimal(b)),
+            _ => return Ok(None),
+        };
+        let context = &self.decimal;
+        let error = |error| match error {
+            DecimalError::Overflow => PalladError::DecimalOverflow { operation: op.name() },
+            DecimalError::DivisionByZero => PalladError::DivisionByZero { operation: op.name() },
+        };
+
+        let result = match op {
+            Op::Add => x.add(&y, context).map_err(error)?,
+            Op::Sub => x.sub(&y, context).map_err(error)?,
+            Op::Mul => x.mul(&y, context).map_err(error)?,
+            Op::Div => x.div(&y, context).map_err(error)?,
+            Op::IntDiv | Op::Mod => {
+                let (quotient, remainder) = x.div_rem(&y)
+                    .ok_or(PalladError::DivisionByZero { operation: op.name() })?;
+                if matches!(op, Op::IntDiv) { quotient } else { remainder }
+            }
+            // Only whole exponents keep the result exact
+            Op::Pow => match b {
+                Value::Int(exp) => x.pow(*exp, context).map_err(error)?,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(Value::Decimal(result)))
+    }
+
+    /// Computes `op` exactly with arbitrary precision, then narrows the result by the VM's
+    /// `OverflowPolicy` when it doesn't fit in 64 bits.
+    ///
+    /// Product, power and shift results larger than `bigint::MAX_BITS` are reported as overflow
+    /// under every policy, before they are computed.
+    fn big_operation(&self, a: BigInt, b: BigInt, op: &Op) -> Result<Value, PalladError> {
+        let result = match op {
+            Op::Add => &a + &b,
+            Op::Sub => &a - &b,
+            Op::Mul if a.bits().saturating_add(b.bits()) > bigint::MAX_BITS => return Err(overflow_error(op)),
+            Op::Mul => &a * &b,
+            Op::Div => return Ok(Value::Float(a.to_f64() / b.to_f64())),
+            Op::IntDiv | Op::Mod => {
+                let (quotient, remainder) = a.div_rem(&b)
+                    .ok_or(PalladError::DivisionByZero { operation: op.name() })?;
+                if matches!(op, Op::IntDiv) { quotient } else { remainder }
+            }
+            Op::Pow if b.is_negative() => {
+                if a.is_zero() {
+                    return Err(PalladError::DivisionByZero { operation: op.name() });
+                }
+                return Ok(Value::Float(a.to_f64().powf(b.to_f64())));
+            }
+            Op::Pow => match a.to_i64() {
+                // These bases never grow, so huge exponents are still valid
+                Some(0 | 1) => if b.is_zero() { BigInt::from(1) } else { a },
+                Some(-1) => BigInt::from(if (&b & &BigInt::from(1)).is_zero() { 1 } else { -1 }),
+                _ => {
+                    let exp = b.to_i64()
+                        .filter(|&exp| a.bits().saturating_mul(exp as u64) <= bigint::MAX_BITS)
+                        .ok_or(PalladError::PowOverflow)?;
+                    a.pow(exp as u64)
+                }
+            },
+            Op::Shl | Op::Shr if b.is_negative() => return Err(PalladError::NegativeShift),
+            Op::Shl if a.is_zero() => a,
+            Op::Shl => {
+                let shift = b.to_i64()
+                    .filter(|&shift| a.bits().saturating_add(shift as u64) <= bigint::MAX_BITS)
+                    .ok_or(PalladError::ShiftOverflow)?;
+                &a << shift as u64
+            }
+            Op::Shr => &a >> b.to_i64().unwrap_or(i64::MAX) as u64,
+            Op::BitAnd => &a & &b,
+            Op::BitOr => &a | &b,
+            Op::BitXor => &a ^ &b,
+        };
+
+        if let Some(n) = result.to_i64() {
+            return Ok(Value::Int(n));
+        }
+        match self.overflow {
+            OverflowPolicy::Error => Err(overflow_error(op)),
+            OverflowPolicy::Wrap => Ok(Value::Int(result.wrapping_to_i64())),
+            OverflowPolicy::Saturate => Ok(Value::Int(result.saturating_to_i64())),
+            OverflowPolicy::Promote => Ok(Value::BigInt(result)),
+        }
+    }
+}
+
+/// Raises float `base` to float `exp` with IEEE 754 semantics.
+///
+/// The result may be `inf` for very large results or `NaN` for a negative base with a fractional
+/// exponent; only `0.0 ** negative` is rejected as a division by zero.
+fn float_pow(base: f64, exp: f64) -> Result<Value, PalladError> {
+    if base == 0.0 && exp < 0.0 {
+        return Err(PalladError::DivisionByZero { operation: "power" });
+    }
+    Ok(Value::Float(base.powf(exp)))
+}
+
+/// Converts an integer operand (`Value::Int` or `Value::BigInt`) to a `BigInt`.
+fn to_big(value: &Value) -> BigInt {
+    match value {
+        Value::BigInt(n) => n.clone(),
+        Value::Int(n) => BigInt::from(*n),
+        _ => BigInt::from(0),
+    }
+}
+
+/// Converts a decimal or integer operand to a `Decimal`.
+fn to_decimal(value: &Value) -> Decimal {
+    match value {
+        Value::Decimal(d) => d.clone(),
+        other => Decimal::from(to_big(other)),
+    }
+}
+
+fn is_number(value: &Value) -> bool {
+    matches!(value, Value::Int(_) | Value::BigInt(_) | Value::Float(_) | Value::Decimal(_))
+}
+
+/// Whether `<`, `<=`, `>` and `>=` are defined between the two values' types.
+fn comparable(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Str(_), Value::Str(_)) => true,
+        // Like arithmetic, comparing these would silently lose exactness
+        (Value::Decimal(_), Value::Float(_)) | (Value::Float(_), Value::Decimal(_)) => false,
+        _ => is_number(a) && is_number(b),
+    }
+}
+
+/// Orders two `comparable` values, or `None` when a float operand is `NaN`.
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    let to_f64 = |v: &Value| match v {
+        Value::Int(n) => *n as f64,
+        Value::BigInt(n) => n.to_f64(),
+        Value::Float(f) => *f,
+        _ => f64::NAN,
+    };
+    match (a, b) {
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Float(_), _) | (_, Value::Float(_)) => to_f64(a).partial_cmp(&to_f64(b)),
+        (Value::Decimal(_), _) | (_, Value::Decimal(_)) => Some(to_decimal(a).compare(&to_decimal(b))),
+        _ => Some(to_big(a).cmp(&to_big(b))),
+    }
+}
+
+/// `==` semantics: numbers compare by value, arrays and tuples item by item, dicts by their
+/// entries in any order, everything else structurally.
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Array(a), Value::Array(b)) | (Value::Tuple(a), Value::Tuple(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| values_equal(x, y))
+        }
+        (Value::Dict(a), Value::Dict(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, value)| dict_get(b, key).is_some_and(|other| values_equal(value, other)))
+        }
+        _ if is_number(a) && comparable(a, b) => compare_values(a, b) == Some(Ordering::Equal),
+        _ => a == b,
+    }
+}
+
+/// Whether `value` has the shape of `pattern`, collecting the values its names bind. Literals
+/// match only values of their own type, so `1` doesn't match `1.0` or `true`.
+fn match_pattern(pattern: &Pattern, value: &Value, bindings: &mut Vec<(String, Value)>) -> bool {
+    let match_items = |patterns: &[Pattern], items: &[Value], bindings: &mut Vec<(String, Value)>| {
+        patterns.len() == items.len() && patterns.iter().zip(items).all(|(p, item)| match_pattern(p, item, bindings))
+    };
+    match (pattern, value) {
+        (Pattern::Wildcard, _) => true,
+        (Pattern::Bind(name), _) => {
+            bindings.push((name.clone(), value.clone()));
+            true
+        }
+        (Pattern::Value(expected), _) => expected.type_name() == value.type_name() && values_equal(expected, value),
+        (Pattern::Range { start, end }, _) => {
+            let at_most = |a: &Value, b: &Value| {
+                comparable(a, b) && matches!(compare_values(a, b), Some(Ordering::Less | Ordering::Equal))
+            };
+            is_number(value) && at_most(start, value) && at_most(value, end)
+        }
+        (Pattern::Tuple(patterns), Value::Tuple(items)) | (Pattern::Array(patterns), Value::Array(items)) => {
+            match_items(patterns, items, bindings)
+        }
+        (Pattern::Dict(entries), Value::Dict(dict)) => entries.iter().all(|(key, pattern)| {
+            dict_get(dict, key).is_some_and(|item| match_pattern(pattern, item, bindings))
+        }),
+        (Pattern::Or(alternatives), _) => alternatives.iter().any(|alternative| {
+            let mut alternative_bindings = vec![];
+            let matched = match_pattern(alternative, value, &mut alternative_bindings);
+            if matched {
+                bindings.extend(alternative_bindings);
+            }
+            matched
+        }),
+        _ => false,
+    }
+}
+
+/// Whether `value` may be stored where the type `expected` (a `Value::type_name` or a class name)
+/// is declared; `none` always may, and so may instances of classes extending the class.
+fn has_type(value: &Value, expected: &str) -> bool {
+    match value {
+        Value::None => true,
+        Value::Object(object) => object.class().ancestor(expected).is_some(),
+        _ => value.type_name() == expected,
+    }
+}
+
+/// Fails unless `value` may be given to the variable `name` declared with the type `expected`:
+/// `none`, or a value of the type (a member's value for an enum).
+fn check_annotation(name: &str, expected: &Annotation, value: &Value) -> Result<(), PalladError> {
+    let accepted = match (expected, value) {
+        (_, Value::None) => true,
+        (Annotation::Type(expected), _) => has_type(value, expected),
+        (Annotation::Enum { members, .. }, Value::Int(n)) => members.contains(n),
+        (Annotation::Enum { .. }, _) => false,
+    };
+    if !accepted {
+        return Err(PalladError::AnnotationMismatch { name: name.to_string(), expected: expected.to_string(), got: value.clone() });
+    }
+    Ok(())
+}
+
+/// Fails unless `args` fit the parameters of `function`: as many arguments, each of its
+/// parameter's annotated type.
+fn check_params(function: &str, params: &[Param], args: &[Value]) -> Result<(), PalladError> {
+    methods::check_args(function, args, params.len(), params.len())?;
+    for (param, arg) in params.iter().zip(args) {
+        if let Some(expected) = &param.expected
+            && !has_type(arg, expected)
+        {
+            return Err(PalladError::ParameterType {
+                function: function.to_string(),
+                name: param.name.clone(),
+                expected: expected.clone(),
+                got: arg.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Sets the static `name` of `class` (or of the ancestor declaring it) to `value`, checked
+/// against its type, and returns whether there is such a static.
+fn set_static(class: &Class, name: &str, value: Value) -> Result<bool, PalladError> {
+    let Some((owner, field)) = class.static_field(name) else {
+        return Ok(false);
+    };
+    if let Some(expected) = &field.expected
+        && !has_type(&value, expected)
+    {
+        return Err(PalladError::FieldType {
+            class: owner.name.clone(),
+            name: name.to_string(),
+            expected: expected.clone(),
+            got: value,
+        });
+    }
+    owner.statics.borrow_mut().insert(name.to_string(), value);
+    Ok(true)
+}
+
+/// The constructor of `class` that creates an instance from `args`: of those whose parameters
+/// `args` fit, the one checking the types of the most arguments, and the first declared on a tie.
+/// A class without constructors is created with no arguments, by its initializer.
+fn select_constructor(class: &Class, args: &[Value]) -> Result<Rc<Function>, PalladError> {
+    if class.constructors.is_empty() {
+        methods::check_args(&class.name, args, 0, 0)?;
+        return Ok(Rc::clone(&class.init));
+    }
+    let mut best: Option<(usize, &Rc<Function>)> = None;
+    for constructor in &class.constructors {
+        if check_params(&class.name, &constructor.params, args).is_err() {
+            continue;
+        }
+        let checked = constructor.params.iter()
+            .zip(args)
+            .filter(|(param, arg)| param.expected.is_some() && **arg != Value::None)
+            .count();
+        if best.is_none_or(|(most, _)| checked > most) {
+            best = Some((checked, constructor));
+        }
+    }
+    best.map(|(_, constructor)| Rc::clone(constructor))
+        .ok_or_else(|| PalladError::NoConstructor { class: class.name.clone(), args: args.to_vec() })
+}
+
+/// The value stored under `key` in a dict's entries.
+fn dict_get<'a>(entries: &'a [(Value, Value)], key: &Value) -> Option<&'a Value> {
+    entries.iter().find(|(k, _)| values_equal(k, key)).map(|(_, value)| value)
+}
+
+/// Returns the character of a string or the item of an array or tuple at an integer `index`
+/// (negative indexes count from the end), or the value of a dict under the key `index`.
+fn index_value(target: Value, index: Value) -> Result<Value, PalladError> {
+    if let Value::Dict(entries) = &target {
+        return dict_get(entries, &index).cloned().ok_or(PalladError::KeyNotFound { key: index });
+    }
+    let Value::Int(i) = index else {
+        return Err(PalladError::TypeMismatch { left: target, right: index, operation: "index" });
+    };
+    let resolve = |len: usize| {
+        let position = if i < 0 { i + len as i64 } else { i };
+        usize::try_from(position).ok()
+            .filter(|&position| position < len)
+            .ok_or(PalladError::IndexOutOfRange { index: i, len })
+    };
+    match target {
+        Value::Str(s) => {
+            let position = resolve(s.chars().count())?;
+            Ok(Value::Str(s.chars().nth(position).map(String::from).unwrap_or_default()))
+        }
+        Value::Array(mut items) | Value::Tuple(mut items) => {
+            let position = resolve(items.len())?;
+            Ok(items.swap_remove(position))
+        }
+        other => Err(PalladError::InvalidOperand { value: other, operation: "index" }),
+    }
+}
+
+/// Converts big integers to floats when both operands are numeric, so mixed big integer and
+/// float operations use float arithmetic.
+fn widen_big_operands(a: Value, b: Value) -> (Value, Value) {
+    let is_numeric = |v: &Value| matches!(v, Value::Int(_) | Value::BigInt(_) | Value::Float(_));
+    if !(is_numeric(&a) && is_numeric(&b)) {
+        return (a, b);
+    }
+    let widen = |v: Value| match v {
+        Value::BigInt(n) => Value::Float(n.to_f64()),
+        other => other,
+    };
+    (widen(a), widen(b))
+}
+
+/// The error reported for `op` under `OverflowPolicy::Error`.
+fn overflow_error(op: &Op) -> PalladError {
+    match op {
+        Op::IntDiv => PalladError::IntDivOverflow,
+        Op::Pow => PalladError::PowOverflow,
+        Op::Shl => PalladError::ShiftOverflow,
+        _ => PalladError::IntOverflow { operation: op.name() },
+    }
+}
+
+/// Raises integer `base` to a non-negative `exp`, or `None` when the result doesn't fit in 64 bits.
+fn checked_int_pow(base: i64, exp: i64) -> Option<i64> {
+    match base {
+        // These bases never grow, so huge exponents are still valid
+        0 | 1 => Some(if exp == 0 { 1 } else { base }),
+        -1 => Some(if exp % 2 == 0 { 1 } else { -1 }),
+        _ => u32::try_from(exp).ok().and_then(|exp| base.checked_pow(exp)),
+    }
+}
+
+/// Raises integer `base` to a negative `exp`, producing a `float` (`2 ** -1` is `0.5`).
+///
+/// `0 ** -n` is a division by zero.
+fn int_pow_negative(base: i64, exp: i64) -> Result<Value, PalladError> {
+    if base == 0 {
+        return Err(PalladError::DivisionByZero { operation: "power" });
+    }
+    Ok(Value::Float((base as f64).powf(exp as f64)))
+}
+
+/// Raises `base` to `exp` keeping only the low 64 bits, for exponents beyond `u32`.
+fn wrapping_int_pow(mut base: i64, mut exp: u64) -> i64 {
+    let mut result: i64 = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Shifts `value` left by a non-negative `shift`, or `None` when significant bits would be lost.
+fn checked_int_shl(value: i64, shift: i64) -> Option<i64> {
+    if value == 0 {
+        return Some(0);
+    }
+    if shift >= i64::BITS as i64 {
+        return None;
+    }
+    let result = value << shift;
+    // Bits shifted out (or into the sign bit) can't be recovered
+    (result >> shift == value).then_some(result)
+}
+
+/// `target.name` for the values that have attributes: a file's `name`, `mode`, `closed` and
+/// `lines` (the rest of a text file, split into lines), an exception's `class` and `message`, a
+/// color's `r`, `g`, `b` and `a`, the fields of an instance, the globals of a module, and the
+/// named colors of `Color` (`Color.RED`).
+fn get_attribute(target: Value, name: String) -> Result<Value, PalladError> {
+    match (&target, name.as_str()) {
+        (Value::File(file), "name") => Ok(Value::Str(file.path())),
+        (Value::File(file), "mode") => Ok(Value::Str(file.mode())),
+        (Value::File(file), "closed") => Ok(Value::Bool(file.is_closed())),
+        (Value::File(file), "lines") => file.lines(),
+        (Value::Exception(exception), "class") => Ok(Value::Str(exception.class.clone())),
+        (Value::Exception(exception), "message") => Ok(Value::Str(exception.message.clone())),
+        (Value::Color(color), "r") => Ok(Value::Float(color.r)),
+        (Value::Color(color), "g") => Ok(Value::Float(color.g)),
+        (Value::Color(color), "b") => Ok(Value::Float(color.b)),
+        (Value::Color(color), "a") => Ok(Value::Float(color.a)),
+        (Value::Signal(signal), "name") => Ok(Value::Str(signal.name())),
+        (Value::Function(function), "name") => Ok(Value::Str(function.name.clone())),
+        (Value::Class(class), name) => match class.get_static(name) {
+            Some(value) => Ok(value),
+            None if name == "name" => Ok(Value::Str(class.name.clone())),
+            None => Err(PalladError::UnknownMember { class: class.name.clone(), name: name.to_string() }),
+        },
+        (Value::Object(object), name) => object.get(name).ok_or_else(|| PalladError::UnknownMember {
+            class: object.class().name.clone(),
+            name: name.to_string(),
+        }),
+        (Value::Module(module), name) => module.get(name).ok_or_else(|| PalladError::UnknownImport {
+            module: module.name(),
+            name: name.to_string(),
+        }),
+        (Value::Namespace("Signal"), name) => match signal::flag(name) {
+            Some(flag) => Ok(Value::Int(flag)),
+            None => Err(PalladError::UnknownAttribute { type_name: "Signal", name: name.to_string() }),
+        },
+        (Value::Namespace("Color"), name) => match Color::named(name) {
+            Some(color) => Ok(Value::Color(color)),
+            None => Err(PalladError::UnknownAttribute { type_name: "Color", name: name.to_string() }),
+        },
+        _ => Err(PalladError::UnknownAttribute { type_name: target.type_name(), name }),
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{compile_error, output, run, run_files};
+
+const ACCOUNT: &str = "\
+class Account:
+    var _balance = 0
+    func deposit(amount):
+        self._balance = self._balance + amount
+        return self._check()
+    func _check():
+        return self._balance
+class Savings extends Account:
+    func peek():
+        return self._balance
+";
+
+#[test]
+fn private_members_are_usable_inside_their_class() {
+    let source = format!("{}print(Account().deposit(5))\n", ACCOUNT);
+    assert_eq!(output(&source), "5\n");
+}
+
+#[test]
+fn private_members_outside_any_class_fail_to_compile() {
+    let source = format!("{}var a = Account()\nprint(a._balance)\n", ACCOUNT);
+    assert_eq!(
+        compile_error(&source),
+        "Line 12: '_balance' is private and can only be used inside its class or module"
+    );
+}
+
+#[test]
+fn subclasses_cannot_use_private_members_of_their_parent() {
+    let source = format!("{}print(Savings().peek())\n", ACCOUNT);
+    let (_, result) = run(&source);
+    assert_eq!(result.unwrap_err().to_string(), "'_balance' is private to class 'Account'");
+}
+
+#[test]
+fn access_violations_are_catchable_access_errors() {
+    let source = format!("{}\
+try:
+    Savings().peek()
+except AccessError:
+    print(\"denied\")
+", ACCOUNT);
+    assert_eq!(output(&source), "denied\n");
+}
+
+#[test]
+fn private_module_names_stay_in_their_module() {
+    let library = "var _secret = 1\nvar shown = _secret + 1\n";
+    let (printed, result) = run_files("access-import", &[
+        ("main.pd", "import lib\nprint(lib.shown)\nclass Spy:\n    static func peek():\n        return lib._secret\nprint(Spy.peek())\n"),
+        ("lib.pd", library),
+    ]);
+    assert_eq!(printed, "2\n");
+    assert_eq!(result.unwrap_err().to_string(), "'_secret' is private to module 'lib'");
+}
+
+#[test]
+fn private_module_names_cannot_be_imported() {
+    assert_eq!(
+        compile_error("from lib import _helper\n"),
+        "Line 1: '_helper' is private and can only be used inside its class or module"
+    );
+}
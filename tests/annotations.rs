@@ -0,0 +1,51 @@
+mod common;
+
+use common::{output, run};
+
+const STATES: &str = "\
+enum States:
+    DAY,
+    NIGHT = 6
+";
+
+#[test]
+fn enum_variables_take_only_member_values() {
+    let source = format!("{}var x = 5\nvar e: States = x\n", STATES);
+    let (_, result) = run(&source);
+    assert_eq!(result.unwrap_err().to_string(), "Variable 'e' is declared as States but was given 'integer'");
+
+    let source = format!("{}var x = 6\nvar e: States = x\nprint(e)\n", STATES);
+    assert_eq!(output(&source), "6\n");
+}
+
+#[test]
+fn assignments_are_checked_against_the_declared_type() {
+    let source = format!("{}var t: States = States.DAY\nt = States.NIGHT\nprint(t)\nt = 99\n", STATES);
+    let (printed, result) = run(&source);
+    assert_eq!(printed, "6\n");
+    assert_eq!(result.unwrap_err().to_string(), "Variable 't' is declared as States but was given 'integer'");
+
+    let (_, result) = run("var q: int = 1\nq = none\nq = \"s\"\n");
+    assert_eq!(result.unwrap_err().to_string(), "Variable 'q' is declared as integer but was given 'string'");
+}
+
+#[test]
+fn parameters_keep_their_types_and_locals_shadow_annotated_globals() {
+    let source = "\
+var q: int = 1
+func f(p: int):
+    var q = \"local\"
+    q = \"fine\"
+    print(q)
+    p = \"no\"
+f(1)
+";
+    let (printed, result) = run(source);
+    assert_eq!(printed, "fine\n");
+    assert_eq!(result.unwrap_err().to_string(), "Variable 'p' is declared as integer but was given 'string'");
+}
+
+#[test]
+fn declaring_again_without_a_type_drops_the_annotation() {
+    assert_eq!(output("var q: int = 1\nvar q = \"s\"\nq = \"t\"\nprint(q)\n"), "t\n");
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn assignment_in_a_function_updates_the_global() {
+    let source = "\
+var total = 0
+func add(n):
+    total = total + n
+add(5)
+add(2)
+print(total)
+";
+    assert_eq!(output(source), "7\n");
+}
+
+#[test]
+fn var_in_a_function_shadows_the_global() {
+    let source = "\
+var total = 0
+func f():
+    var total = 10
+    total = total + 1
+    return total
+print(f(), total, sep=\" \")
+";
+    assert_eq!(output(source), "11 0\n");
+}
+
+#[test]
+fn assigning_an_undeclared_name_fails() {
+    let (_, result) = run("undeclared = 3\n");
+    assert_eq!(result.unwrap_err().to_string(), "Undefined variable: undeclared");
+
+    let source = "\
+func f():
+    missing = 1
+f()
+";
+    let (_, result) = run(source);
+    assert_eq!(result.unwrap_err().to_string(), "Undefined variable: missing");
+}
+
+#[test]
+fn methods_read_and_assign_fields_by_name() {
+    let source = "\
+class Counter:
+    var count: int = 0
+    static var made: int = 0
+    func bump():
+        count = count + 1
+        made = made + 1
+        return count
+var a = Counter()
+var b = Counter()
+a.bump()
+a.bump()
+b.bump()
+print(a.count, b.count, Counter.made, sep=\" \")
+";
+    assert_eq!(output(source), "2 1 3\n");
+}
+
+#[test]
+fn field_assignment_checks_the_field_type() {
+    let source = "\
+class Counter:
+    var count: int = 0
+    func reset():
+        count = \"zero\"
+Counter().reset()
+";
+    let (_, result) = run(source);
+    assert!(result.is_err());
+}
+
+#[test]
+fn locals_shadow_fields() {
+    let source = "\
+class Box:
+    var size: int = 1
+    func grow(size):
+        size = size * 2
+        return size
+var box = Box()
+print(box.grow(5), box.size, sep=\" \")
+";
+    assert_eq!(output(source), "10 1\n");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::run_files;
+use pallad::compiler::compile;
+use pallad::error::Warning;
+use pallad::lexer::tokenize;
+use pallad::parser::Parser;
+
+#[test]
+fn a_file_with_an_extends_header_is_a_class_named_after_the_file() {
+    let (printed, result) = run_files("class-modules-stem", &[
+        ("main.pd", "import Person\nvar ada = Person()\nprint(ada.greet(), ada.legs, sep=\" \")\n"),
+        ("Person.pd", "\
+extends Animal
+
+import Animal
+
+var name = \"Ada\"
+func greet():
+    return \"Hi, \" + self.name
+"),
+        ("Animal.pd", "class Animal\n\nvar legs = 2\n"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "Hi, Ada 2\n");
+}
+
+#[test]
+fn a_class_header_overrides_the_file_name() {
+    let (printed, result) = run_files("class-modules-header", &[
+        ("main.pd", "import shapes\nprint(shapes, shapes().side, sep=\" \")\n"),
+        ("shapes.pd", "class Square\n\nvar side = 4\n"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "<class Square> 4\n");
+}
+
+#[test]
+fn class_modules_warn_about_names_that_are_not_pascal_case() {
+    let stmts = Parser::new(tokenize("extends Base\nvar x = 1\n").unwrap())
+        .parse_module(Some("my_widget"))
+        .unwrap();
+    let (_, warnings) = compile(stmts).unwrap();
+    assert_eq!(warnings, vec![Warning::ClassName { name: "my_widget".to_string(), line: 1 }]);
+}
+
+#[test]
+fn headers_must_start_the_file() {
+    let error = Parser::new(tokenize("var x = 1\nextends Base\n").unwrap()).parse_module(Some("Late")).unwrap_err();
+    assert_eq!(error.to_string(), "Line 2: Class header must start the file, which declares one class");
+
+    let error = Parser::new(tokenize("extends Base\n").unwrap()).parse_module(None).unwrap_err();
+    assert_eq!(error.to_string(), "Line 1: Class header needs a class name when the source isn't read from a file");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn keywords_are_allowed_as_attribute_names() {
+    let source = "\
+try:
+    print(1 / 0)
+except RuntimeError as error:
+    print(error.class)
+";
+    assert_eq!(output(source), "RuntimeError\n");
+}
+
+const PLACES: &str = "\
+class Place:
+    var name: string = \"somewhere\"
+    func describe():
+        return \"Place \" + self.name
+    func kind():
+        return \"place\"
+class City extends Place:
+    var population = 0
+    func describe():
+        return super.describe() + \" of \" + string(self.population)
+";
+
+#[test]
+fn methods_dispatch_through_inheritance_and_super() {
+    let source = format!("{}\
+var c = City()
+c.name = \"Oslo\"
+c.population = 700000
+print(c.describe(), c.kind(), sep=\" | \")
+print(Place().describe())
+", PLACES);
+    assert_eq!(output(&source), "Place Oslo of 700000 | place\nPlace somewhere\n");
+}
+
+#[test]
+fn objects_are_shared_references() {
+    let source = format!("{}\
+var c = City()
+var d = c
+d.name = \"Bergen\"
+print(c.name)
+", PLACES);
+    assert_eq!(output(&source), "Bergen\n");
+}
+
+#[test]
+fn unknown_members_are_errors() {
+    let (_, result) = run(&format!("{}print(City().nope)", PLACES));
+    assert_eq!(result.unwrap_err().to_string(), "Class 'City' has no field or method 'nope'");
+}
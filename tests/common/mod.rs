@@ -0,0 +1,81 @@
+// Each test file uses its own share of these helpers
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::process;
+
+use pallad::compiler::compile;
+use pallad::error::{PalladError, Warning};
+use pallad::ir::Instr;
+use pallad::lexer::tokenize;
+use pallad::output::SharedBuffer;
+use pallad::parser::Parser;
+use pallad::vm::VM;
+
+/// Runs `source` on a fresh VM, returning what it printed and how the run ended. Panics if the
+/// source doesn't compile.
+pub fn run(source: &str) -> (String, Result<(), PalladError>) {
+    run_with(source, |_| {})
+}
+
+/// Like `run`, on a VM `configure` sets up first.
+pub fn run_with(source: &str, configure: impl FnOnce(&mut VM)) -> (String, Result<(), PalladError>) {
+    let output = SharedBuffer::new();
+    let mut vm = VM::new();
+    vm.set_output(Box::new(output.clone()));
+    configure(&mut vm);
+    let result = vm.run(program(source));
+    (output.contents(), result)
+}
+
+/// Writes `files` (paths and sources) to a fresh folder named after `name`, and runs the first one
+/// like `run` does, as the script its imports are relative to.
+pub fn run_files(name: &str, files: &[(&str, &str)]) -> (String, Result<(), PalladError>) {
+    let dir = env::temp_dir().join(format!("pallad-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, source) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    let (main, source) = files[0];
+    let result = run_with(source, |vm| vm.set_script_path(dir.join(main)));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// The compiled `source`, panicking if it doesn't compile.
+pub fn program(source: &str) -> Vec<Instr> {
+    compile_source(source).0
+}
+
+/// Runs `source` and returns what it printed, panicking if it fails.
+pub fn output(source: &str) -> String {
+    let (output, result) = run(source);
+    if let Err(error) = result {
+        panic!("run failed with '{}' after printing {:?}", error, output);
+    }
+    output
+}
+
+/// The warnings of compiling `source`.
+pub fn warnings(source: &str) -> Vec<Warning> {
+    compile_source(source).1
+}
+
+/// The message of the error compiling `source` fails with, panicking if it compiles.
+pub fn compile_error(source: &str) -> String {
+    let tokens = tokenize(source).unwrap_or_else(|error| panic!("source should tokenize: {}", error));
+    let stmts = Parser::new(tokens).parse().unwrap_or_else(|error| panic!("source should parse: {}", error));
+    match compile(stmts) {
+        Ok(_) => panic!("source should fail to compile"),
+        Err(error) => error.to_string(),
+    }
+}
+
+fn compile_source(source: &str) -> (Vec<Instr>, Vec<Warning>) {
+    let tokens = tokenize(source).unwrap_or_else(|error| panic!("source should tokenize: {}", error));
+    let stmts = Parser::new(tokens).parse().unwrap_or_else(|error| panic!("source should parse: {}", error));
+    compile(stmts).unwrap_or_else(|error| panic!("source should compile: {}", error))
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn constructors_are_chosen_by_their_arguments() {
+    let source = "\
+class User:
+    var name: string = \"anon\"
+    var age: int = 0
+    constructor():
+        print(\"default\", self.name, sep=\" \")
+    constructor(name: string):
+        self.name = name
+    constructor(name: string, age: int):
+        self.name = name
+        self.age = age
+    constructor(copy: User):
+        self.name = copy.name + \" (copy)\"
+        self.age = copy.age
+var a = User()
+var b = User(\"Ada\")
+var c = User(\"Ada\", 36)
+var d = User(c)
+print(b.name, b.age, c.age, d.name, d.age, sep=\" \")
+";
+    assert_eq!(output(source), "default anon\nAda 0 36 Ada (copy) 36\n");
+}
+
+#[test]
+fn no_matching_constructor_is_an_error() {
+    let class = "class U:\n    constructor(x: int):\n        pass\n";
+    let (_, result) = run(&format!("{}U(\"s\")", class));
+    assert_eq!(result.unwrap_err().to_string(), "No constructor of 'U' takes (string)");
+    let (_, result) = run(&format!("{}U()", class));
+    assert_eq!(result.unwrap_err().to_string(), "No constructor of 'U' takes ()");
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{output, run};
+use pallad::error::PalladError;
+use pallad::value::Value;
+
+#[test]
+fn int_parses_strictly_with_an_optional_radix() {
+    assert_eq!(output("print(int(\"42\"), int(\"-7\"), int(\"ff\", 16), int(\"101\", 2), sep=\" \")"), "42 -7 255 5\n");
+    let (_, result) = run("print(int(\"12abc\"))");
+    let error = result.unwrap_err();
+    assert_eq!(error.to_string(), "Cannot convert string \"12abc\" to integer");
+    assert!(matches!(error, PalladError::ConversionError { value: Value::Str(ref s), target: "integer" } if s == "12abc"));
+    let (_, result) = run("print(int(\"12\", 37))");
+    assert_eq!(result.unwrap_err().to_string(), "Invalid argument to 'int': radix must be between 2 and 36");
+}
+
+#[test]
+fn int_rounds_floats_with_a_mode() {
+    assert_eq!(output("print(int(3.7), int(-3.7), sep=\" \")"), "3 -3\n");
+    assert_eq!(output("print(int(2.5, \"half-even\"), int(2.5, \"half-up\"), int(-2.5, \"floor\"), sep=\" \")"), "2 3 -3\n");
+    let (_, result) = run("print(int(float(\"inf\")))");
+    assert_eq!(result.unwrap_err().to_string(), "Cannot convert float inf to integer");
+}
+
+#[test]
+fn float_string_and_bool() {
+    let source = "\
+print(float(\"2.5\"), string(12), string(true), sep=\" \")
+print(bool(0), bool(\"\"), bool(\"x\"), bool([]), sep=\" \")
+";
+    assert_eq!(output(source), "2.5 12 true\nfalse false true false\n");
+    let (_, result) = run("print(float(\"x\"))");
+    assert_eq!(result.unwrap_err().to_string(), "Cannot convert string \"x\" to float");
+}
+
+#[test]
+fn byte_takes_bits_or_a_number_up_to_255() {
+    assert_eq!(output("print(byte(\"00001111\"), byte(255), sep=\" \")"), "15 255\n");
+    let (_, result) = run("print(byte(256))");
+    assert_eq!(result.unwrap_err().to_string(), "Cannot convert integer 256 to byte");
+}
+
+#[test]
+fn conversion_errors_are_value_errors() {
+    let source = "\
+try:
+    int(\"nope\")
+except ValueError as error:
+    print(error.message)
+";
+    assert_eq!(output(source), "Cannot convert string \"nope\" to integer\n");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{output, run, run_with};
+use pallad::decimal::{DecimalContext, RoundingMode};
+use pallad::vm::OverflowPolicy;
+
+#[test]
+fn oversized_decimal_results_overflow_instead_of_computing() {
+    for source in ["print(1.5d ** 60000)", "print(0.1d ** 200000)", "var big = 10d ** 50000\nprint(big * big)"] {
+        let (_, result) = run(source);
+        let error = result.unwrap_err().to_string();
+        assert!(error.starts_with("Decimal overflow at"), "{}: {}", source, error);
+    }
+}
+
+#[test]
+fn decimal_overflow_is_an_overflow_error() {
+    let source = "\
+try:
+    print(1.5d ** 60000)
+except OverflowError as error:
+    print(error.message)
+print(1.5d ** 4)
+";
+    assert_eq!(output(source), "Decimal overflow at power operation\n5.0625\n");
+}
+
+#[test]
+fn decimal_arithmetic_is_exact() {
+    let source = "\
+print(0.1d + 0.2d)
+print(0.1d + 0.2d == 0.3d)
+print(1.50d * 2)
+print(1.25d * 1.25d)
+print(10.00d / 4d)
+print(2d ** -2)
+print(7.5d // 2d, 7.5d % 2d, sep=\" \")
+";
+    assert_eq!(output(source), "0.3\ntrue\n3.00\n1.5625\n2.50\n0.25\n3 1.5\n");
+}
+
+#[test]
+fn decimals_compare_by_value_and_do_not_mix_with_floats() {
+    assert_eq!(output("print(1.50d == 1.5d)\nprint(1.5d < 2)"), "true\ntrue\n");
+    let (_, result) = run("print(1.5d + 1.0)");
+    assert_eq!(result.unwrap_err().to_string(), "Cannot add 'decimal' and 'float'");
+}
+
+#[test]
+fn division_rounds_to_the_context_precision() {
+    assert_eq!(output("print(1d / 3d)"), "0.3333333333333333333333333333\n");
+    let cases = [
+        (RoundingMode::HalfEven, "0.12 -0.12"),
+        (RoundingMode::HalfUp, "0.13 -0.13"),
+        (RoundingMode::Down, "0.12 -0.12"),
+        (RoundingMode::Ceiling, "0.13 -0.12"),
+        (RoundingMode::Floor, "0.12 -0.13"),
+    ];
+    for (rounding, expected) in cases {
+        let context = DecimalContext { precision: 2, rounding };
+        let (printed, result) = run_with("print(1d / 8d, -1d / 8d, sep=\" \")", |vm| vm.set_decimal_context(context));
+        result.unwrap();
+        assert_eq!(printed, format!("{}\n", expected), "{:?}", rounding);
+    }
+}
+
+#[test]
+fn integers_promote_to_big_integers() {
+    let source = "\
+var big = 123456789012345678901234567890
+print(big * 10)
+print(9223372036854775807 + 1)
+";
+    let (printed, result) = run_with(source, |vm| vm.set_overflow_policy(OverflowPolicy::Promote));
+    result.unwrap();
+    assert_eq!(printed, "1234567890123456789012345678900\n9223372036854775808\n");
+}
@@ -0,0 +1,67 @@
+use pallad::ast::Stmt;
+use pallad::doc::{entries, render, Format};
+use pallad::lexer::tokenize_with_trivia;
+use pallad::parser::Parser;
+
+const SOURCE: &str = "\
+# The [b]current[/b] total.
+# Starts at [code]0[/code].
+var total = 0
+
+\"Adds [code]amount[/code] to [var total], see [func reset].\"
+func add(amount: int) -> int:
+    total = total + amount
+    return total
+
+# Sets the total back to 0.[br]Returns nothing.
+func reset():
+    total = 0
+
+var _hidden = 1
+";
+
+fn parse(source: &str) -> Vec<Stmt> {
+    let (tokens, trivia) = tokenize_with_trivia(source).unwrap();
+    Parser::with_trivia(tokens, trivia).parse().unwrap()
+}
+
+#[test]
+fn docstrings_and_merged_comments_attach_to_public_declarations() {
+    let docs: Vec<(String, Option<String>)> = entries(&parse(SOURCE))
+        .into_iter()
+        .map(|entry| (entry.signature, entry.doc))
+        .collect();
+    assert_eq!(docs, vec![
+        ("total".to_string(), Some("The [b]current[/b] total. Starts at [code]0[/code].".to_string())),
+        (
+            "add(amount: int) -> int".to_string(),
+            Some("Adds [code]amount[/code] to [var total], see [func reset].".to_string()),
+        ),
+        ("reset()".to_string(), Some("Sets the total back to 0.[br]Returns nothing.".to_string())),
+    ]);
+}
+
+#[test]
+fn markdown_references_link_func_and_var_tags() {
+    let expected = concat!(
+        "# counter\n",
+        "\n<a id=\"total\"></a>`total` _variable_  \n",
+        "**->** The **current** total. Starts at `0`.\n",
+        "\n<a id=\"add\"></a>`add(amount: int) -> int` _function_  \n",
+        "**->** Adds `amount` to [total](#total), see [reset()](#reset).\n",
+        "\n<a id=\"reset\"></a>`reset()` _function_  \n",
+        "**->** Sets the total back to 0.  \nReturns nothing.\n",
+    );
+    assert_eq!(render("counter", &parse(SOURCE), Format::Markdown), expected);
+}
+
+#[test]
+fn html_references_escape_and_link() {
+    let html = render("counter", &parse(SOURCE), Format::Html);
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains("<dt id=\"add\"><code>add(amount: int) -&gt; int</code> <em>function</em></dt>\n"));
+    assert!(html.contains(
+        "<dd>Adds <code>amount</code> to <a href=\"#total\">total</a>, see <a href=\"#reset\">reset()</a>.</dd>\n"
+    ));
+    assert!(html.contains("<dd>Sets the total back to 0.<br>Returns nothing.</dd>\n"));
+}
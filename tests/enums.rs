@@ -0,0 +1,68 @@
+mod common;
+
+use common::{compile_error, output};
+
+#[test]
+fn anonymous_enum_members_are_usable_by_name() {
+    let source = "\
+enum:
+    ON,
+    OFF,
+    BOTH,
+print(ON, OFF, BOTH, sep=\" \")
+";
+    assert_eq!(output(source), "0 1 2\n");
+}
+
+#[test]
+fn named_enum_values_continue_from_the_last_explicit_one() {
+    let source = "\
+enum TimeStates:
+    DAY,
+    NIGHT = 6,
+    MIDNIGHT = 11,
+    MORNING,
+print(TimeStates.DAY, TimeStates.NIGHT, TimeStates.MORNING, sep=\" \")
+";
+    assert_eq!(output(source), "0 6 12\n");
+}
+
+#[test]
+fn anonymous_shadow_makes_named_members_usable_alone() {
+    let source = "\
+@anonymous_shadow
+enum Error:
+    OK,
+    FAILED,
+print(OK, Error.FAILED, sep=\" \")
+";
+    assert_eq!(output(source), "0 1\n");
+}
+
+#[test]
+fn named_enums_are_types() {
+    let source = "\
+enum States:
+    DAY,
+    NIGHT = 6
+var t: States = States.NIGHT
+var u: States = t
+print(u)
+";
+    assert_eq!(output(source), "6\n");
+}
+
+#[test]
+fn enum_mistakes_are_compile_errors() {
+    let states = "enum S:\n    A\n";
+    for (code, message) in [
+        ("var s: S = 3", "Line 3: 's' must be given a member of enum 'S'"),
+        ("S = 1", "Line 3: Cannot assign to enum or enum member 'S'"),
+        ("print(S.B)", "Line 3: Enum 'S' has no member 'B'"),
+        ("var s: Nope = 1", "Line 3: Unknown type 'Nope'"),
+    ] {
+        assert_eq!(compile_error(&format!("{}{}", states, code)), message, "{}", code);
+    }
+    assert_eq!(compile_error("enum S:\n    A = \"x\""), "Line 2: Value of enum member 'A' must be an integer literal");
+    assert_eq!(compile_error("enum:\n    A\nA = 2"), "Line 3: Cannot assign to enum or enum member 'A'");
+}
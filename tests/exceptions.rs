@@ -0,0 +1,84 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn runtime_errors_are_caught_as_exception_classes() {
+    let source = "\
+try:
+    print(1 / 0)
+except RuntimeError as error:
+    print(error.class, error.message, sep=\": \")
+try:
+    int(\"x\")
+except TypeError, ValueError as error:
+    print(error.class)
+";
+    assert_eq!(output(source), "RuntimeError: Division by zero at divide operation is not valid\nValueError\n");
+}
+
+#[test]
+fn else_runs_when_nothing_was_raised() {
+    let source = "\
+try:
+    print(\"ok\")
+except:
+    print(\"handled\")
+else:
+    print(\"else\")
+";
+    assert_eq!(output(source), "ok\nelse\n");
+}
+
+#[test]
+fn raised_errors_unwind_across_calls() {
+    let source = "\
+func inner():
+    raise \"Custom Error!\"
+func outer():
+    inner()
+    print(\"unreachable\")
+try:
+    outer()
+except RuntimeError as error:
+    print(error.message)
+";
+    assert_eq!(output(source), "Custom Error!\n");
+}
+
+#[test]
+fn raise_with_a_class_and_bare_raise() {
+    let source = "\
+try:
+    raise ValueError(\"bad\")
+except Error as error:
+    print(error.class, error.message, sep=\": \")
+try:
+    try:
+        raise \"inner\"
+    except:
+        raise
+except RuntimeError as error:
+    print(error.message)
+";
+    assert_eq!(output(source), "ValueError: bad\ninner\n");
+}
+
+#[test]
+fn assert_raises_an_assertion_error() {
+    let source = "\
+assert(1 == 1)
+try:
+    assert(1 == 2, \"math broke\")
+except AssertionError as error:
+    print(error.message)
+";
+    assert_eq!(output(source), "math broke\n");
+}
+
+#[test]
+fn uncaught_errors_end_the_run() {
+    let (printed, result) = run("print(\"before\")\nraise \"Custom\"\nprint(\"after\")");
+    assert_eq!(printed, "before\n");
+    assert_eq!(result.unwrap_err().to_string(), "RuntimeError: Custom");
+}
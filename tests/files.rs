@@ -0,0 +1,96 @@
+mod common;
+
+use std::env;
+use std::fs;
+
+use common::output;
+
+/// A path for a test's file in the system's temporary folder, as a Pallad string literal.
+fn temp_path(name: &str) -> String {
+    let path = env::temp_dir().join(format!("pallad-{}-{}", std::process::id(), name));
+    format!("{:?}", path.to_string_lossy())
+}
+
+#[test]
+fn return_inside_with_closes_the_file_before_enclosing_finally_blocks() {
+    let path = temp_path("return-with.txt");
+    let source = format!("\
+var kept = none
+func save(text: string):
+    try:
+        with open({path}, \"w\") as f:
+            kept = f
+            f.write(text)
+            return \"saved\"
+    finally:
+        print(kept.closed)
+print(save(\"hello\"))
+with open({path}, \"r\") as f:
+    print(f.read())
+");
+    assert_eq!(output(&source), "true\nsaved\nhello\n");
+    let _ = fs::remove_file(path.trim_matches('"'));
+}
+
+#[test]
+fn write_append_and_read_text() {
+    let path = temp_path("text.txt");
+    let source = format!("\
+with open({path}, \"w\") as f:
+    f.write(\"one\\ntwo\\n\")
+with open({path}, \"a\") as f:
+    f.write(\"three\\n\")
+with open({path}, \"r\") as f:
+    print(f.lines)
+with open({path}) as f:
+    print(f.read())
+");
+    assert_eq!(output(&source), "[\"one\", \"two\", \"three\"]\none\ntwo\nthree\n\n");
+    let _ = fs::remove_file(path.trim_matches('"'));
+}
+
+#[test]
+fn binary_modes_read_and_write_bytes() {
+    let path = temp_path("bytes.bin");
+    let source = format!("\
+with open({path}, \"wb\") as f:
+    f.write([1, 2, 255])
+with open({path}, \"rb\") as f:
+    print(f.read())
+");
+    assert_eq!(output(&source), "[1, 2, 255]\n");
+    assert_eq!(fs::read(path.trim_matches('"')).unwrap(), [1, 2, 255]);
+    let _ = fs::remove_file(path.trim_matches('"'));
+}
+
+#[test]
+fn with_closes_the_file_when_its_block_fails() {
+    let path = temp_path("failing.txt");
+    let source = format!("\
+var kept = none
+try:
+    with open({path}, \"w\") as f:
+        kept = f
+        raise \"boom\"
+except RuntimeError as error:
+    print(error.message, kept.closed)
+");
+    assert_eq!(output(&source), "boom\ntrue\n");
+    let _ = fs::remove_file(path.trim_matches('"'));
+}
+
+#[test]
+fn io_errors_are_catchable() {
+    let missing = temp_path("missing/nope.txt");
+    let source = format!("\
+try:
+    open({missing}, \"r\")
+except IOError as error:
+    print(error.class)
+try:
+    open({missing}, \"x\")
+except ValueError as error:
+    print(error.message)
+");
+    assert_eq!(output(&source), "IOError\nInvalid argument to 'open': mode must be 'r', 'w' or 'a', optionally followed by 'b'\n");
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn finally_runs_after_the_body() {
+    let source = "\
+try:
+    print(\"body\")
+finally:
+    print(\"finally\")
+print(\"after\")
+";
+    assert_eq!(output(source), "body\nfinally\nafter\n");
+}
+
+#[test]
+fn finally_runs_before_an_uncaught_error_propagates() {
+    let source = "\
+try:
+    raise \"boom\"
+finally:
+    print(\"finally\")
+print(\"unreachable\")
+";
+    let (printed, result) = run(source);
+    assert_eq!(printed, "finally\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn finally_runs_after_a_handled_error() {
+    let source = "\
+try:
+    raise \"boom\"
+except:
+    print(\"handled\")
+finally:
+    print(\"finally\")
+";
+    assert_eq!(output(source), "handled\nfinally\n");
+}
+
+#[test]
+fn return_runs_finally() {
+    let source = "\
+func f():
+    try:
+        return 1
+    finally:
+        print(\"finally ran\")
+print(f())
+";
+    assert_eq!(output(source), "finally ran\n1\n");
+}
+
+#[test]
+fn return_runs_nested_finally_blocks_innermost_first() {
+    let source = "\
+func f():
+    try:
+        try:
+            return \"value\"
+        except:
+            print(\"not raised\")
+        finally:
+            print(\"inner\")
+    finally:
+        print(\"outer\")
+print(f())
+";
+    assert_eq!(output(source), "inner\nouter\nvalue\n");
+}
+
+#[test]
+fn return_in_finally_replaces_the_returned_value() {
+    let source = "\
+func f():
+    try:
+        return 1
+    finally:
+        return 2
+print(f())
+";
+    assert_eq!(output(source), "2\n");
+}
+
+#[test]
+fn return_from_except_runs_finally() {
+    let source = "\
+func f():
+    try:
+        raise \"boom\"
+    except:
+        return \"handled\"
+    finally:
+        print(\"finally\")
+print(f())
+";
+    assert_eq!(output(source), "finally\nhandled\n");
+}
+
+#[test]
+fn error_in_finally_on_return_reaches_outer_handler() {
+    let source = "\
+func f():
+    try:
+        try:
+            return 1
+        finally:
+            raise \"boom\"
+    except:
+        print(\"caught\")
+    return 3
+print(f())
+";
+    assert_eq!(output(source), "caught\n3\n");
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{output, run_files};
+
+#[test]
+fn inner_classes_are_usable_before_their_declaration() {
+    let source = "\
+class Utils:
+    static var counter = Counter()
+    static func make():
+        return Counter()
+    class Counter:
+        var n = 0
+        func bump():
+            self.n = self.n + 1
+            return self.n
+print(Utils.counter.bump(), Utils.counter.bump(), sep=\" \")
+print(Utils.Counter().bump(), Utils.make().bump(), sep=\" \")
+";
+    assert_eq!(output(source), "1 2\n1 1\n");
+}
+
+#[test]
+fn inner_classes_of_a_class_module_can_be_imported() {
+    let main = "\
+from Utils import Counter
+print(Counter().n)
+import Utils as U
+print(U.Counter().n)
+";
+    let utils = "class Utils\n\nvar first = Counter()\n\nclass Counter:\n    var n = 5\n";
+    let (printed, result) = run_files("inner-classes", &[("main.pd", main), ("Utils.pd", utils)]);
+    result.unwrap();
+    assert_eq!(printed, "5\n5\n");
+}
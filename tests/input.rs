@@ -0,0 +1,30 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::run_with;
+
+fn run_with_input(source: &str, input: &'static str) -> String {
+    let (printed, result) = run_with(source, |vm| vm.set_input(Box::new(Cursor::new(input))));
+    result.unwrap();
+    printed
+}
+
+#[test]
+fn input_reads_lines_and_writes_the_prompt_to_the_output() {
+    let source = "\
+var name: string = input(\"Name: \")
+print(f\"Hi {name}\")
+print(input())
+";
+    assert_eq!(run_with_input(source, "Ada\r\nBob"), "Name: Hi Ada\nBob\n");
+}
+
+#[test]
+fn input_returns_none_at_the_end_of_input() {
+    let source = "\
+print(input() == \"only\")
+print(input() == none)
+";
+    assert_eq!(run_with_input(source, "only\n"), "true\ntrue\n");
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::output;
+use pallad::lexer::{tokenize, Token};
+
+#[test]
+fn hashes_inside_strings_are_not_comments() {
+    assert_eq!(output("print(\"Issue #42\") # prints the issue\nprint('#') #\n"), "Issue #42\n#\n");
+}
+
+#[test]
+fn tokens_record_their_line_and_column() {
+    let spans: Vec<(Token, usize, usize)> = tokenize("var x = 1\n  # comment\nprint(x)\n")
+        .unwrap()
+        .into_iter()
+        .map(|spanned| (spanned.token, spanned.line, spanned.column))
+        .collect();
+    assert_eq!(spans, vec![
+        (Token::Var, 1, 1),
+        (Token::Ident("x".to_string()), 1, 5),
+        (Token::Eq, 1, 7),
+        (Token::Int(1), 1, 9),
+        (Token::Eol, 1, 10),
+        (Token::Print, 3, 1),
+        (Token::LParen, 3, 6),
+        (Token::Ident("x".to_string()), 3, 7),
+        (Token::RParen, 3, 8),
+        (Token::Eol, 3, 9),
+    ]);
+}
+
+#[test]
+fn unterminated_strings_report_their_line() {
+    let error = tokenize("var a = 1\nprint(\"open # not a comment)\n").unwrap_err();
+    assert_eq!(error.to_string(), "Line 2: Unterminated string");
+}
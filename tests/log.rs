@@ -0,0 +1,62 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use common::run_with;
+use pallad::log::{self, LogLevel, LogRecord};
+
+/// Runs `source` with its log records collected instead of written to standard error.
+fn records(source: &str, level: Option<LogLevel>) -> Vec<LogRecord> {
+    let records = Rc::new(RefCell::new(vec![]));
+    let sink = Rc::clone(&records);
+    let (_, result) = run_with(source, |vm| {
+        vm.set_log_sink(Box::new(move |record: &LogRecord| sink.borrow_mut().push(record.clone())));
+        if let Some(level) = level {
+            vm.set_log_level(level);
+        }
+    });
+    result.unwrap();
+    records.take()
+}
+
+#[test]
+fn records_carry_level_message_line_and_color() {
+    let source = "\
+log.add(\"Test\")
+log.error(\"Something failed\")
+log.message(\"Red!\", Color.RED)
+";
+    let records = records(source, None);
+    let summary: Vec<_> = records.iter().map(|r| (r.level, r.message.as_str(), r.line, r.color.is_some())).collect();
+    assert_eq!(summary, [
+        (LogLevel::Info, "Test", 1, false),
+        (LogLevel::Error, "Something failed", 2, false),
+        (LogLevel::Info, "Red!", 3, true),
+    ]);
+}
+
+#[test]
+fn records_below_the_level_are_dropped() {
+    let source = "\
+log.debug(\"hidden\")
+log.set_level(\"debug\")
+log.debug(\"shown\")
+log.set_level(\"error\")
+log.warning(\"hidden too\")
+";
+    let messages: Vec<_> = records(source, None).into_iter().map(|r| r.message).collect();
+    assert_eq!(messages, ["shown"]);
+
+    let messages: Vec<_> = records("log.info(\"a\")\nlog.error(\"b\")", Some(LogLevel::Error)).into_iter().map(|r| r.message).collect();
+    assert_eq!(messages, ["b"]);
+}
+
+#[test]
+fn records_format_with_a_utc_timestamp() {
+    let mut record = records("log.error(\"Failed\")", None).remove(0);
+    record.timestamp = UNIX_EPOCH + Duration::from_secs(1_769_860_800);
+    assert_eq!(record.format(), "2026-01-31T12:00:00Z ERROR line 1: Failed");
+    assert_eq!(log::format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use common::{output, run, warnings};
+use pallad::error::Warning;
+
+#[test]
+fn literals_match_only_their_own_type() {
+    let source = "\
+match 1.0:
+    case 1:
+        print(\"int\")
+    case 1.0:
+        print(\"float\")
+";
+    assert_eq!(output(source), "float\n");
+}
+
+#[test]
+fn bare_names_capture_even_when_a_variable_has_that_name() {
+    let source = "\
+var x = 123
+var status = 2
+match (3, 0):
+    case (x, 0) | (0, x):
+        print(x)
+match status:
+    case x:
+        print(x)
+print(x)
+";
+    assert_eq!(output(source), "3\n2\n123\n");
+}
+
+#[test]
+fn enum_members_compare_by_value() {
+    let source = "\
+enum:
+    IDLE,
+    DONE
+enum Color:
+    RED,
+    GREEN
+match 1:
+    case IDLE:
+        print(\"idle\")
+    case Color.RED:
+        print(\"red\")
+    case DONE:
+        print(\"done\")
+    case Color.GREEN:
+        print(\"green\")
+";
+    assert_eq!(output(source), "done\n");
+}
+
+#[test]
+fn captures_bind_only_inside_their_arm() {
+    let source = "\
+match [1, 5]:
+    case [1, x]:
+        print(x)
+print(x)
+";
+    let (printed, result) = run(source);
+    assert_eq!(printed, "5\n");
+    assert_eq!(result.unwrap_err().to_string(), "Undefined variable: x");
+}
+
+#[test]
+fn captures_in_functions_do_not_write_globals() {
+    let source = "\
+func f(value):
+    match value:
+        case [first, rest]:
+            return first + rest
+print(f([1, 2]))
+match 3:
+    case first:
+        print(first)
+";
+    assert_eq!(output(source), "3\n3\n");
+}
+
+#[test]
+fn failed_guard_falls_through_without_its_bindings() {
+    let source = "\
+match [1, 5]:
+    case [1, y] if y > 9:
+        print(\"big\")
+    case [1, z]:
+        print(z)
+";
+    assert_eq!(output(source), "5\n");
+}
+
+#[test]
+fn or_patterns_dicts_and_tuples() {
+    let source = "\
+func describe(value):
+    match value:
+        case 1 | 2:
+            return \"small\"
+        case {\"name\": name}:
+            return name
+        case (a, b):
+            return a * b
+        case _:
+            return \"other\"
+print(describe(2), describe({\"name\": \"Ada\"}), describe((3, 4)), describe(\"?\"), sep=\" \")
+";
+    assert_eq!(output(source), "small Ada 12 other\n");
+}
+
+#[test]
+fn irrefutable_capture_warns_about_later_arms() {
+    let source = "\
+match 3:
+    case n:
+        print(n)
+    case 3:
+        print(\"three\")
+";
+    assert_eq!(warnings(source), vec![Warning::UnreachableArms { line: 2 }]);
+    assert_eq!(output(source), "3\n");
+}
+
+#[test]
+fn capture_in_the_last_arm_does_not_warn() {
+    let source = "\
+match 3:
+    case 1:
+        print(\"one\")
+    case other:
+        print(other)
+";
+    assert!(warnings(source).is_empty());
+}
+
+#[test]
+fn arrays_ranges_and_guards() {
+    let source = "\
+func describe(value):
+    match value:
+        case [first, _, last]:
+            return first + last
+        case 0..9:
+            return \"digit\"
+        case (x, y) if x == y:
+            return \"diagonal\"
+        case (x, _):
+            return x
+    return \"nothing\"
+print(describe([1, 2, 3]), describe(7), describe((2, 2)), describe((5, 1)), describe(10), sep=\" \")
+";
+    assert_eq!(output(source), "4 digit diagonal 5 nothing\n");
+}
+
+#[test]
+fn matches_over_enums_report_missing_members() {
+    let source = "\
+enum Color:
+    RED,
+    GREEN,
+    BLUE
+match Color.RED:
+    case Color.RED:
+        print(\"red\")
+";
+    assert_eq!(warnings(source), vec![Warning::NonExhaustiveMatch {
+        enum_name: "Color".to_string(),
+        missing: vec!["GREEN".to_string(), "BLUE".to_string()],
+        line: 5,
+    }]);
+    let covered = source.replace("    case Color.RED:", "    case Color.RED | Color.GREEN | Color.BLUE:");
+    assert!(warnings(&covered).is_empty());
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{compile_error, run_files};
+
+#[test]
+fn modules_run_once_and_keep_their_own_globals() {
+    let (printed, result) = run_files("modules-once", &[
+        ("main.pd", "\
+var name = \"main\"
+import counter
+import \"counter.pd\" as again
+counter.bump()
+print(again.count, name, counter.name, sep=\" \")
+"),
+        ("counter.pd", "\
+var name = \"counter\"
+var count = 0
+print(\"loading\")
+func bump():
+    count = count + 1
+"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "loading\n1 main counter\n");
+}
+
+#[test]
+fn from_imports_bind_names_with_aliases() {
+    let (printed, result) = run_files("modules-from", &[
+        ("main.pd", "from \"lib/shapes\" import area, unit as one\nprint(area(3), one, sep=\" \")\n"),
+        ("lib/shapes.pd", "var unit = 1\nfunc area(side):\n    return side * side\n"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "9 1\n");
+}
+
+#[test]
+fn paths_resolve_from_the_importing_file() {
+    let (printed, result) = run_files("modules-relative", &[
+        ("main.pd", "import \"lib/outer\"\nprint(outer.value)\n"),
+        ("lib/outer.pd", "import inner\nvar value = inner.value + 1\n"),
+        ("lib/inner.pd", "var value = 41\n"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "42\n");
+}
+
+#[test]
+fn folder_imports_load_every_module_but_excluded_ones() {
+    let (printed, result) = run_files("modules-folder", &[
+        ("main.pd", "exclude \"tools/slow\"\nimport \"tools/\"\nprint(fast.speed)\nprint(slow)\n"),
+        ("tools/fast.pd", "var speed = 10\n"),
+        ("tools/slow.pd", "print(\"never\")\n"),
+    ]);
+    assert_eq!(printed, "10\n");
+    assert_eq!(result.unwrap_err().to_string(), "Undefined variable: slow");
+}
+
+#[test]
+fn excluding_after_an_import_fails() {
+    assert_eq!(
+        compile_error("import helper\nexclude helper\n"),
+        "Line 2: 'helper' is excluded after it is imported; exclude it before the import"
+    );
+}
+
+#[test]
+fn imports_can_be_conditional() {
+    let (printed, result) = run_files("modules-conditional", &[
+        ("main.pd", "\
+import config
+if config.debug:
+    import \"debug/verbose\" as logger
+else:
+    import \"debug/quiet\" as logger
+print(logger.level)
+"),
+        ("config.pd", "var debug = false\n"),
+        ("debug/verbose.pd", "var level = \"verbose\"\n"),
+        ("debug/quiet.pd", "var level = \"quiet\"\n"),
+    ]);
+    result.unwrap();
+    assert_eq!(printed, "quiet\n");
+}
+
+#[test]
+fn missing_and_circular_imports_fail() {
+    let (_, result) = run_files("modules-missing", &[("main.pd", "import nowhere\n")]);
+    assert_eq!(result.unwrap_err().to_string(), "Line 1: Module 'nowhere' not found");
+
+    let (_, result) = run_files("modules-cycle", &[
+        ("main.pd", "import a\n"),
+        ("a.pd", "import b\n"),
+        ("b.pd", "import a\n"),
+    ]);
+    assert_eq!(result.unwrap_err().to_string(), "Circular import: a.pd -> b.pd -> a.pd");
+}
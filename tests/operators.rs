@@ -0,0 +1,36 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn power_is_right_associative_and_binds_tighter_than_unary_minus() {
+    assert_eq!(output("print(2 ** 3 ** 2)\nprint(-2 ** 2)\nprint(1 + 2 * 3 ** 2)"), "512\n-4\n19\n");
+}
+
+#[test]
+fn negative_and_float_powers_give_floats() {
+    assert_eq!(output("print(2 ** -1)\nprint(2.0 ** 0.5)"), "0.5\n1.4142135623730951\n");
+}
+
+#[test]
+fn bitwise_operators() {
+    let source = "print(6 & 3, 6 | 3, 6 ^ 3, ~5, 1 << 4, -16 >> 2, sep=\" \")";
+    assert_eq!(output(source), "2 7 5 -6 16 -4\n");
+}
+
+#[test]
+fn bitwise_precedence_follows_and_xor_or_below_shifts() {
+    assert_eq!(output("print(1 | 2 ^ 3 & 4)\nprint(1 << 2 + 1)"), "3\n8\n");
+}
+
+#[test]
+fn power_and_shift_overflow_are_errors() {
+    for (source, message) in [
+        ("print(2 ** 64)", "Integer power overflow"),
+        ("print(1 << 64)", "Integer shift overflow"),
+        ("print(1 << -1)", "Shift count can't be negative"),
+    ] {
+        let (_, result) = run(source);
+        assert_eq!(result.unwrap_err().to_string(), message, "{}", source);
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{output, program, run};
+use pallad::output::{CallbackWriter, SharedBuffer};
+use pallad::vm::VM;
+
+#[test]
+fn print_puts_each_argument_on_its_own_line_by_default() {
+    assert_eq!(output("print(1, \"two\", 3.5)"), "1\ntwo\n3.5\n");
+}
+
+#[test]
+fn sep_and_end_control_the_separators() {
+    let source = "\
+print(1, 2, 3, sep=\", \", end=\"!\\n\")
+print(\"a\", \"b\", sep=\"\", end=\"\")
+print(\"c\", end=none)
+";
+    assert_eq!(output(source), "1, 2, 3!\nabc\n");
+}
+
+#[test]
+fn print_rejects_unknown_keywords_and_non_string_separators() {
+    let (_, result) = run("print(1, color=\"red\")");
+    assert_eq!(result.unwrap_err().to_string(), "'print' got an unexpected keyword argument 'color'");
+    let (_, result) = run("print(1, 2, sep=3)");
+    assert_eq!(result.unwrap_err().to_string(), "'print' expected a string, got 'integer'");
+}
+
+#[test]
+fn shared_buffer_clones_see_the_same_output() {
+    let buffer = SharedBuffer::new();
+    let mut vm = VM::new();
+    vm.set_output(Box::new(buffer.clone()));
+    vm.run(program("print(\"first\")")).unwrap();
+    assert_eq!(buffer.contents(), "first\n");
+    buffer.clear();
+    vm.run(program("print(\"second\")")).unwrap();
+    assert_eq!(buffer.contents(), "second\n");
+}
+
+#[test]
+fn callback_writer_gets_each_print_whole() {
+    let prints = Rc::new(RefCell::new(vec![]));
+    let seen = Rc::clone(&prints);
+    let mut vm = VM::new();
+    vm.set_output(Box::new(CallbackWriter::new(move |text: &str| seen.borrow_mut().push(text.to_string()))));
+    vm.run(program("print(1, 2, sep=\" \")\nprint(\"done\")")).unwrap();
+    assert_eq!(*prints.borrow(), ["1 2\n", "done\n"]);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::{run, run_with};
+use pallad::vm::OverflowPolicy;
+
+const LIMITS: &str = "\
+var max = 9223372036854775807
+var min = -9223372036854775807 - 1
+";
+
+/// What `expr` prints under each policy, or the error it fails with.
+fn under_each_policy(expr: &str) -> Vec<String> {
+    let source = format!("{}print({})", LIMITS, expr);
+    [OverflowPolicy::Error, OverflowPolicy::Wrap, OverflowPolicy::Saturate, OverflowPolicy::Promote]
+        .into_iter()
+        .map(|policy| match run_with(&source, |vm| vm.set_overflow_policy(policy)) {
+            (printed, Ok(())) => printed.trim_end().to_string(),
+            (_, Err(error)) => error.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn errors_by_default() {
+    let (_, result) = run(&format!("{}print(max + 1)", LIMITS));
+    assert_eq!(result.unwrap_err().to_string(), "Integer overflow at add operation");
+}
+
+#[test]
+fn each_policy_handles_addition_overflow() {
+    assert_eq!(under_each_policy("max + 1"), [
+        "Integer overflow at add operation",
+        "-9223372036854775808",
+        "9223372036854775807",
+        "9223372036854775808",
+    ]);
+}
+
+#[test]
+fn each_policy_handles_subtraction_and_multiplication_overflow() {
+    assert_eq!(under_each_policy("min - 1"), [
+        "Integer overflow at subtract operation",
+        "9223372036854775807",
+        "-9223372036854775808",
+        "-9223372036854775809",
+    ]);
+    assert_eq!(under_each_policy("max * 2"), [
+        "Integer overflow at multiply operation",
+        "-2",
+        "9223372036854775807",
+        "18446744073709551614",
+    ]);
+}
+
+#[test]
+fn minimum_divided_by_minus_one() {
+    assert_eq!(under_each_policy("min // -1"), [
+        "Integer division overflow",
+        "-9223372036854775808",
+        "9223372036854775807",
+        "9223372036854775808",
+    ]);
+    assert_eq!(under_each_policy("min % -1"), ["0", "0", "0", "0"]);
+}
+
+#[test]
+fn integer_power_overflow() {
+    assert_eq!(under_each_policy("3 ** 40"), [
+        "Integer power overflow",
+        "-6289078614652622815",
+        "9223372036854775807",
+        "12157665459056928801",
+    ]);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{output, run};
+
+#[test]
+fn failed_emit_drops_its_deferred_handlers() {
+    let source = "\
+signal changed(value)
+signal other(value)
+func later(value):
+    print(\"deferred\", value, sep=\" \")
+func fail(value):
+    raise \"boom\"
+func show(value):
+    print(\"other\", value, sep=\" \")
+changed.connect(later, Signal.DEFERRED)
+changed.connect(fail)
+try:
+    changed.emit(1)
+except:
+    print(\"caught\")
+changed.disconnect(later)
+other.connect(show)
+other.emit(2)
+";
+    assert_eq!(output(source), "caught\nother 2\n");
+}
+
+const RECEIVED: &str = "\
+signal data_received(data: string)
+func a(data):
+    print(\"a\", data, sep=\" \")
+func b(data):
+    print(\"b\", data, sep=\" \")
+";
+
+#[test]
+fn emit_calls_handlers_in_connection_order() {
+    let source = format!("{}\
+data_received.connect(a)
+data_received.connect(b)
+data_received.emit(\"x\")
+data_received.disconnect(a)
+data_received.emit(\"y\")
+", RECEIVED);
+    assert_eq!(output(&source), "a x\nb x\nb y\n");
+}
+
+#[test]
+fn one_shot_and_deferred_handlers() {
+    let source = format!("{}\
+data_received.connect(a, Signal.ONE_SHOT)
+data_received.connect(b, Signal.DEFERRED)
+data_received.emit(\"1\")
+data_received.emit(\"2\")
+", RECEIVED);
+    assert_eq!(output(&source), "a 1\nb 1\nb 2\n");
+}
+
+#[test]
+fn emit_checks_arguments_against_the_declaration() {
+    let (_, result) = run(&format!("{}data_received.emit(5)", RECEIVED));
+    assert_eq!(result.unwrap_err().to_string(), "Parameter 'data' of 'data_received.emit' expects string, got 'integer'");
+    let (_, result) = run(&format!("{}data_received.emit()", RECEIVED));
+    assert_eq!(result.unwrap_err().to_string(), "'data_received.emit' takes 1 argument(s), got 0");
+    let (_, result) = run(&format!("{}data_received.connect(a)\ndata_received.connect(a)", RECEIVED));
+    assert_eq!(result.unwrap_err().to_string(), "Signal 'data_received': 'a' is already connected");
+}
+
+#[test]
+fn handlers_changed_during_emit_take_effect_safely() {
+    let source = "\
+signal s(v)
+func second(v):
+    print(\"second\", v, sep=\" \")
+func first(v):
+    print(\"first\", v, sep=\" \")
+    s.disconnect(second)
+    s.connect(added)
+func added(v):
+    print(\"added\", v, sep=\" \")
+s.connect(first)
+s.connect(second)
+s.emit(1)
+s.disconnect(first)
+s.emit(2)
+";
+    assert_eq!(output(source), "first 1\nadded 2\n");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::output;
+
+const COUNTER: &str = "\
+class Counter:
+    static var count: int = 0
+    static var ready = false
+    print(\"First static\")
+    static:
+        count = 10
+        print(\"Second static\")
+    print(\"Last static\")
+    static constructor -> bool:
+        print(\"Constructors initialized\")
+        return ready
+    constructor():
+        count = count + 1
+    static func total() -> int:
+        return count
+";
+
+#[test]
+fn class_code_and_static_blocks_run_once_in_source_order() {
+    let source = format!("{}print(\"after\")\nprint(Counter.total())\n", COUNTER);
+    assert_eq!(output(&source), "First static\nSecond static\nLast static\nafter\n10\n");
+}
+
+#[test]
+fn statics_are_shared_by_instances() {
+    let source = format!("{}\
+Counter.ready = true
+var a = Counter()
+var b = Counter()
+print(Counter.count, Counter.total(), sep=\" \")
+", COUNTER);
+    assert!(output(&source).ends_with("Constructors initialized\n12 12\n"));
+}
+
+#[test]
+fn a_static_constructor_returning_false_stops_creation() {
+    let source = format!("{}\
+try:
+    Counter()
+except RuntimeError as error:
+    print(error.message)
+", COUNTER);
+    assert!(output(&source).ends_with("Constructors initialized\nStatic constructor of 'Counter' didn't return true, so it can't be created\n"));
+}
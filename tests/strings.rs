@@ -0,0 +1,81 @@
+mod common;
+
+use common::{output, run};
+use pallad::lexer::tokenize;
+use pallad::parser::Parser;
+
+#[test]
+fn f_strings_embed_expressions() {
+    let source = "\
+var name = \"Ada\"
+print(f\"Hello {name}!\")
+print(f\"{1 + 2} {name.upper()} {{literal}}\")
+";
+    assert_eq!(output(source), "Hello Ada!\n3 ADA {literal}\n");
+}
+
+#[test]
+fn f_string_format_specs() {
+    let source = "\
+print(f\"{3.14159:.2f}|{42:>6}|{42:<6}|{42:^6}|\")
+print(f\"{255:x} {255:#x} {255:08b} {1234567:,}\")
+print(f\"{0.000012345:g} {1234.5:e} {3.0:g}\")
+";
+    assert_eq!(output(source), "3.14|    42|42    |  42  |\nff 0xff 11111111 1,234,567\n1.2345e-05 1.234500e+03 3\n");
+}
+
+#[test]
+fn unsupported_format_specs_are_named() {
+    let error = tokenize("print(f\"{1:q}\")").unwrap_err();
+    assert_eq!(error.to_string(), "Line 1: Unsupported format spec 'q'");
+}
+
+#[test]
+fn triple_quoted_strings_span_lines() {
+    let source = "\
+var text = \"\"\"line one
+line two\"\"\"
+print(text)
+";
+    assert_eq!(output(source), "line one\nline two\n");
+}
+
+#[test]
+fn lines_after_a_multi_line_string_keep_their_numbers() {
+    let source = "var s = \"\"\"a\nb\nc\"\"\"\nvar = 1\n";
+    let error = Parser::new(tokenize(source).unwrap()).parse().unwrap_err();
+    assert_eq!(error.to_string(), "Line 4: Expected identifier, got Eq");
+}
+
+#[test]
+fn string_methods() {
+    let source = "\
+var s = \"  Hello, Wörld  \"
+print(s.strip().upper(), s.strip().lower(), sep=\"|\")
+print(\"a,b,c\".split(\",\"))
+print(\"-\".join([\"x\", \"y\", \"z\"]))
+print(\"banana\".replace(\"an\", \"AN\"))
+print(\"banana\".find(\"na\"), \"banana\".find(\"zz\"), sep=\" \")
+print(\"banana\".starts_with(\"ba\"), \"banana\".ends_with(\"na\"), sep=\" \")
+print(\"{} + {} = {}\".format(1, 2, 3))
+";
+    assert_eq!(output(source), "HELLO, WÖRLD|hello, wörld\n[\"a\", \"b\", \"c\"]\nx-y-z\nbANANa\n2 -1\ntrue true\n1 + 2 = 3\n");
+}
+
+#[test]
+fn strings_index_by_character() {
+    assert_eq!(output("print(\"Wörld\".len(), \"Wörld\"[1], \"Wörld\"[-1], sep=\" \")"), "5 ö d\n");
+    let (_, result) = run("print(\"abc\"[5])");
+    assert_eq!(result.unwrap_err().to_string(), "Index 5 out of range for length 3");
+}
+
+#[test]
+fn strings_compare_and_repeat() {
+    assert_eq!(output("print(\"apple\" < \"banana\", \"x\" == \"x\", \"ab\" * 3, sep=\" \")"), "true true ababab\n");
+}
+
+#[test]
+fn unknown_string_methods_are_errors() {
+    let (_, result) = run("print(\"abc\".nope())");
+    assert_eq!(result.unwrap_err().to_string(), "Type 'string' has no method 'nope'");
+}
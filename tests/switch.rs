@@ -0,0 +1,81 @@
+mod common;
+
+use common::{compile_error, output, program, warnings};
+use pallad::error::Warning;
+use pallad::ir::Instr;
+
+#[test]
+fn switch_runs_the_matching_case_or_the_default() {
+    let source = "\
+enum TimeState:
+    DAY,
+    NIGHT = 6,
+    MORNING,
+func describe(t):
+    switch t:
+        case TimeState.DAY:
+            print(\"day\")
+        case TimeState.NIGHT, TimeState.MORNING:
+            print(\"night or morning\")
+        default:
+            print(\"other\")
+describe(TimeState.DAY)
+describe(TimeState.MORNING)
+describe(3)
+";
+    assert_eq!(output(source), "day\nnight or morning\nother\n");
+}
+
+#[test]
+fn switch_over_strings_and_without_a_default() {
+    let source = "\
+func letter(s):
+    switch s:
+        case \"a\":
+            print(\"A\")
+        case \"b\", \"c\":
+            print(\"B or C\")
+letter(\"c\")
+letter(\"zzz\")
+letter(\"a\")
+";
+    assert_eq!(output(source), "B or C\nA\n");
+}
+
+#[test]
+fn dense_cases_use_a_jump_table_and_others_a_map() {
+    let dense = "switch 2:\n    case 1:\n        pass\n    case 2, 3:\n        pass\n";
+    assert!(program(dense).iter().any(|instr| matches!(instr, Instr::JumpTable { min: 1, .. })));
+    let sparse = "switch 2:\n    case 1:\n        pass\n    case 1000000:\n        pass\n";
+    assert!(program(sparse).iter().any(|instr| matches!(instr, Instr::JumpMap { .. })));
+    let strings = "switch \"a\":\n    case \"a\":\n        pass\n";
+    assert!(program(strings).iter().any(|instr| matches!(instr, Instr::JumpMap { .. })));
+    assert_eq!(output("switch 1000000:\n    case 1:\n        print(1)\n    case 1000000:\n        print(2)\n"), "2\n");
+}
+
+#[test]
+fn duplicate_case_values_warn() {
+    let source = "switch 1:\n    case 1:\n        pass\n    case 2, 1:\n        pass\n";
+    assert!(matches!(&warnings(source)[..], [Warning::DuplicateCase { value, line: 4 }] if value == "1"));
+}
+
+#[test]
+fn case_values_must_be_constants() {
+    let source = "var x = 1\nswitch 1:\n    case x:\n        pass\n";
+    assert_eq!(compile_error(source), "Line 3: Case values must be integer or string constants, or enum members");
+}
+
+#[test]
+fn match_over_constants_compiles_like_a_switch() {
+    let source = "\
+match 2:
+    case 1:
+        print(\"one\")
+    case 2 | 3:
+        print(\"two or three\")
+    default:
+        print(\"other\")
+";
+    assert!(program(source).iter().any(|instr| matches!(instr, Instr::JumpTable { .. })));
+    assert_eq!(output(source), "two or three\n");
+}
@@ -0,0 +1,57 @@
+use pallad::lexer::{tokenize_with_trivia, Trivia, TriviaKind};
+use pallad::tags::{self, CommentTag};
+
+fn trivia(source: &str) -> Vec<Trivia> {
+    tokenize_with_trivia(source).unwrap().1
+}
+
+#[test]
+fn tags_split_a_comment_at_each_keyword() {
+    assert_eq!(tags::find("FIXME: reset it, ASSIGNED_TO - ada; NOTE"), vec![
+        (CommentTag::Fixme, "reset it".to_string()),
+        (CommentTag::AssignedTo, "ada".to_string()),
+        (CommentTag::Note, String::new()),
+    ]);
+    assert!(tags::find("NOTES and FIXMEs").is_empty());
+}
+
+#[test]
+fn comments_yield_tags_and_balanced_regions() {
+    let source = "\
+#region Setup
+var x = 1 # NOTE: later, DEBUG: print x
+#endregion
+print(\"# NOTE not a comment\")
+";
+    let found: Vec<(TriviaKind, String, usize, usize)> = trivia(source)
+        .into_iter()
+        .filter(|trivia| !matches!(trivia.kind, TriviaKind::Comment | TriviaKind::TrailingComment))
+        .map(|trivia| (trivia.kind, trivia.text, trivia.line, trivia.end_line))
+        .collect();
+    assert_eq!(found, vec![
+        (TriviaKind::Region, "Setup".to_string(), 1, 3),
+        (TriviaKind::Tag(CommentTag::Note), "later".to_string(), 2, 2),
+        (TriviaKind::Tag(CommentTag::Debug), "print x".to_string(), 2, 2),
+    ]);
+}
+
+#[test]
+fn unbalanced_regions_are_reported() {
+    let source = "#endregion\n#region Open\nvar x = 1\n";
+    let report = tags::report(&[("main.pd".to_string(), Ok(trivia(source)))]);
+    let expected = concat!(
+        "{\n",
+        "  \"main.pd\": {\n",
+        "    \"tags\": [],\n",
+        "    \"regions\": [\n",
+        "      { \"name\": \"Open\", \"line\": 2, \"end_line\": 3 }\n",
+        "    ],\n",
+        "    \"warnings\": [\n",
+        "      { \"message\": \"'#endregion' closes no '#region'\", \"line\": 1 },\n",
+        "      { \"message\": \"'#region' is never closed by '#endregion'\", \"line\": 2 }\n",
+        "    ]\n",
+        "  }\n",
+        "}\n",
+    );
+    assert_eq!(report, expected);
+}